@@ -1,11 +1,10 @@
-use std::{io, str};
+use std::io;
 use tokio::net::UdpSocket;
 use tokio::time::{sleep, timeout, Duration};
 
 use super::types::{APIError, Result};
 use rpc_contracts::body::contracts::*;
-use rpc_contracts::{DecodeBody, EncodeBody, RPCRequest, RPCResponse, ResponseStatus};
-use serde::json;
+use rpc_contracts::{Codec, DecodeBody, EncodeBody, RPCRequest, RPCResponse, ResponseStatus, MONITOR_ACK};
 
 const TIME_OUT: Duration = Duration::from_secs(5);
 const RESPONSE_SIZE: usize = 4096; // limit response size to be 4 bytes
@@ -15,15 +14,15 @@ pub struct ServiceConsumer<'a> {
     pub host_addr: String,
     pub port_addr: u16,
     pub retry: bool,
+    pub codec: Codec,
 }
 
 macro_rules! match_consumer_response {
     ($timeout_future:expr, $request:ident, $buffer:ident, $time_elapsed:ident, $service_type:ident, $body_struct:ty, $($field:ident),+) => {
         match $timeout_future.await {
             Ok(Ok((size, _))) => {
-                let str_response = str::from_utf8(&$buffer[..size]).unwrap();
-                let response: RPCResponse = json::from_str(str_response)?;
-                if $request.id == response.request_id {
+                let response = RPCResponse::from_bytes(&$buffer[..size])?;
+                if response.answers(&$request) {
                     match response.status {
                         ResponseStatus::Finished => {
                             let body = response.decode_body::<$body_struct>()?;
@@ -58,12 +57,13 @@ macro_rules! match_consumer_response {
 }
 
 impl<'a> ServiceConsumer<'a> {
-    pub fn new(socket: &'a UdpSocket, host_addr: String, port_addr: u16, retry: bool) -> Self {
+    pub fn new(socket: &'a UdpSocket, host_addr: String, port_addr: u16, retry: bool, codec: Codec) -> Self {
         ServiceConsumer {
             socket,
             host_addr,
             port_addr,
             retry,
+            codec,
         }
     }
 
@@ -90,14 +90,14 @@ impl<'a> ServiceConsumer<'a> {
         destination: String,
     ) -> Result<RPCResponse> {
         let service = "Service 1";
-        let mut request = RPCRequest::new(1).await;
+        let mut request = RPCRequest::new(1, self.codec).await;
         request.encode_body(Service1RequestBody {
-            source,
-            destination,
+            source: source.into(),
+            destination: destination.into(),
         });
 
-        let encoded_request = json::to_string(&request);
-        match self.send_package(encoded_request.as_bytes()).await {
+        let encoded_request = request.to_bytes();
+        match self.send_package(&encoded_request).await {
             Ok(_) => {}
             Err(_) => {
                 println!("{} communication failed: {:?}", service, APIError::IOFailed);
@@ -132,10 +132,10 @@ impl<'a> ServiceConsumer<'a> {
     }
     pub async fn request_service_2(&self, flight_id: u32) -> Result<RPCResponse> {
         let service = "Service 2";
-        let mut request = RPCRequest::new(2).await;
+        let mut request = RPCRequest::new(2, self.codec).await;
         request.encode_body(Service2RequestBody { flight_id });
-        let encoded_request = json::to_string(&request);
-        match self.send_package(encoded_request.as_bytes()).await {
+        let encoded_request = request.to_bytes();
+        match self.send_package(&encoded_request).await {
             Ok(_) => {}
             Err(_) => {
                 println!("{} communication failed: {:?}", service, APIError::IOFailed);
@@ -171,13 +171,13 @@ impl<'a> ServiceConsumer<'a> {
     }
     pub async fn request_service_3(&self, flight_id: u32, num_seat: u32) -> Result<RPCResponse> {
         let service = "Service 3";
-        let mut request = RPCRequest::new(3).await;
+        let mut request = RPCRequest::new(3, self.codec).await;
         request.encode_body(Service3RequestBody {
             flight_id,
             num_seat,
         });
-        let encoded_request = json::to_string(&request);
-        match self.send_package(encoded_request.as_bytes()).await {
+        let encoded_request = request.to_bytes();
+        match self.send_package(&encoded_request).await {
             Ok(_) => {}
             Err(_) => {
                 println!("{} communication failed: {:?}", service, APIError::IOFailed);
@@ -203,14 +203,54 @@ impl<'a> ServiceConsumer<'a> {
     }
 
     pub async fn request_service_4(&self, flight_id: u32, monitor_interval: u32) {
-        let service = "Service 4";
-        let mut request = RPCRequest::new(4).await;
+        let mut request = RPCRequest::new(4, self.codec).await;
         request.encode_body(Service4RequestBody {
             flight_id,
             monitor_interval,
         });
-        let encoded_request = json::to_string(&request);
-        match self.send_package(encoded_request.as_bytes()).await {
+        let established = |response: &RPCResponse| -> Result<String> {
+            let Service4ResponseBody { message } = response.decode_body()?;
+            Ok(format!("message = {:?}", message))
+        };
+        let update = |response: &RPCResponse| -> Result<(u32, String)> {
+            let Service4MonitorResponseBody { seq, seat_avail } = response.decode_body()?;
+            Ok((seq, format!("seat_avail = {:?}", seat_avail)))
+        };
+        self.monitor("Service 4", &request, monitor_interval, established, update).await;
+    }
+
+    pub async fn request_service_7(&self, source: String, destination: String, seat_threshold: u32, monitor_interval: u32) {
+        let mut request = RPCRequest::new(7, self.codec).await;
+        request.encode_body(Service7RequestBody {
+            source: source.into(),
+            destination: destination.into(),
+            seat_threshold,
+            monitor_interval,
+        });
+        let established = |response: &RPCResponse| -> Result<String> {
+            let Service7ResponseBody { message, flight_ids } = response.decode_body()?;
+            Ok(format!("message = {:?}\nflight_ids = {:?}", message, flight_ids))
+        };
+        let update = |response: &RPCResponse| -> Result<(u32, String)> {
+            let Service7MonitorResponseBody { seq, changes } = response.decode_body()?;
+            let changes: Vec<String> = changes.iter().map(|change| format!("{:?}", change)).collect();
+            Ok((seq, changes.join("\n")))
+        };
+        self.monitor("Service 7", &request, monitor_interval, established, update).await;
+    }
+
+    /// Send a monitor request and print its updates until `monitor_interval` ends. `established` describes the reply
+    /// to the request, `update` describes an update and returns its sequence number.
+    async fn monitor(
+        &self,
+        service: &str,
+        request: &RPCRequest,
+        monitor_interval: u32,
+        established: impl Fn(&RPCResponse) -> Result<String>,
+        update: impl Fn(&RPCResponse) -> Result<(u32, String)>,
+    ) {
+        let encoded_request = request.to_bytes();
+        match self.send_package(&encoded_request).await {
             Ok(_) => {}
             Err(_) => {
                 println!("{} communication failed: {:?}", service, APIError::IOFailed);
@@ -219,6 +259,7 @@ impl<'a> ServiceConsumer<'a> {
 
         let mut buffer = [0_u8; RESPONSE_SIZE];
         let mut ack = false; // ack must be received from server.
+        let mut last_seq = 0; // updates resent by the server or arriving late are dropped
 
         let request_timeout = sleep(TIME_OUT);
         tokio::pin!(request_timeout);
@@ -243,31 +284,46 @@ impl<'a> ServiceConsumer<'a> {
                 response_result = self.socket.recv_from(&mut buffer) => {
                     match response_result {
                         Ok((size, _)) => {
-                            let str_response = str::from_utf8(&buffer[..size]).unwrap();
-                            let response: RPCResponse = match json::from_str(str_response) {
+                            let response = match RPCResponse::from_bytes(&buffer[..size]) {
                                 Ok(response) => response,
                                 Err(_) => {
                                     println!("{} receives malformed request format from server", service);
                                     continue;
                                 }
                             };
-                            // NOTE: will handle decode error in the future
+                            if !response.answers(request) {
+                                continue;
+                            }
                             match response.status {
                                 ResponseStatus::Finished => {
-                                    let Service4ResponseBody { message } = response.decode_body().unwrap();
+                                    let Ok(details) = established(&response) else {
+                                        println!("{} receives malformed response body from server", service);
+                                        continue;
+                                    };
                                     println!("{} responses with the following details:", service);
-                                    println!("message = {:?}", message);
+                                    println!("{}", details);
                                     ack = true;
                                 }
                                 ResponseStatus::Failed => {
-                                    let ServiceFailedResponse { error } = response.decode_body().unwrap();
-                                    println!("{} responses with error: {}", service, error.to_string());
+                                    let Ok(ServiceFailedResponse { error }) = response.decode_body() else {
+                                        println!("{} receives malformed response body from server", service);
+                                        continue;
+                                    };
+                                    println!("{} responses with error: {}", service, error);
                                     break;
                                 }
                                 ResponseStatus::Updated => {
-                                    let Service4MonitorResponseBody { seat_avail } = response.decode_body().unwrap();
+                                    let Ok((seq, details)) = update(&response) else {
+                                        println!("{} receives malformed response body from server", service);
+                                        continue;
+                                    };
+                                    self.acknowledge_update(request, seq).await;
+                                    if seq <= last_seq {
+                                        continue;
+                                    }
+                                    last_seq = seq;
                                     println!("{} receives the following update:", service);
-                                    println!("seat_avail = {:?}", seat_avail);
+                                    println!("{}", details);
                                 }
                             }
                         }
@@ -281,6 +337,17 @@ impl<'a> ServiceConsumer<'a> {
         }
     }
 
+    /// Acknowledge every update of the monitor `request` up to `seq`, so that the server stops resending them.
+    /// Duplicates are acknowledged again in case the previous acknowledgement was lost.
+    async fn acknowledge_update(&self, request: &RPCRequest, seq: u32) {
+        let mut ack = RPCRequest::new(MONITOR_ACK, self.codec).await;
+        ack.encode_body(MonitorAckRequestBody {
+            request_id: request.id,
+            seq,
+        });
+        self.send_package(&ack.to_bytes()).await.ok();
+    }
+
     pub async fn invoke_request_service_5(&self, flight_id: u32) {
         let mut response = self.request_service_5(flight_id).await;
         while self.retry && response.err() == Some(APIError::TimeOutError) {
@@ -290,10 +357,10 @@ impl<'a> ServiceConsumer<'a> {
     }
     pub async fn request_service_5(&self, flight_id: u32) -> Result<RPCResponse> {
         let service = "Service 5";
-        let mut request = RPCRequest::new(5).await;
+        let mut request = RPCRequest::new(5, self.codec).await;
         request.encode_body(Service5RequestBody { flight_id });
-        let encoded_request = json::to_string(&request);
-        match self.send_package(encoded_request.as_bytes()).await {
+        let encoded_request = request.to_bytes();
+        match self.send_package(&encoded_request).await {
             Ok(_) => {}
             Err(_) => {
                 println!("{} communication failed: {:?}", service, APIError::IOFailed);
@@ -330,14 +397,14 @@ impl<'a> ServiceConsumer<'a> {
         flight_id: u32,
         amount_in_kg: u32,
     ) -> Result<RPCResponse> {
-        let mut request = RPCRequest::new(6).await;
+        let mut request = RPCRequest::new(6, self.codec).await;
         request.encode_body(Service6RequestBody {
             flight_id,
             amount_in_kg,
         });
         let service = "Service 6";
-        let encoded_request = json::to_string(&request);
-        match self.send_package(encoded_request.as_bytes()).await {
+        let encoded_request = request.to_bytes();
+        match self.send_package(&encoded_request).await {
             Ok(_) => {}
             Err(_) => {
                 println!("{} communication failed: {:?}", service, APIError::IOFailed);
@@ -1,6 +1,7 @@
 use clap::Parser;
 use client::api::ServiceConsumer;
 use rand::prelude::*;
+use rpc_contracts::Codec;
 use std::error::Error;
 use std::io::{stdin, stdout, Write};
 use tokio::net::UdpSocket;
@@ -28,6 +29,9 @@ struct Args {
 
     #[arg(short, long, default_value = "false")]
     retry: bool,
+
+    #[arg(short, long, default_value = "json")]
+    codec: Codec,
 }
 
 #[tokio::main]
@@ -40,9 +44,10 @@ async fn main() -> Result<(), Box<dyn Error>> {
     let loss = options.loss;
     let loss_prob = options.loss_prob;
     let retry = options.retry;
+    let codec = options.codec;
 
     let socket = UdpSocket::bind((addr, port)).await?;
-    let consumer = ServiceConsumer::new(&socket, server_addr, server_port, retry);
+    let consumer = ServiceConsumer::new(&socket, server_addr, server_port, retry, codec);
 
     let mut rng = rand::thread_rng();
     loop {
@@ -59,7 +64,8 @@ async fn main() -> Result<(), Box<dyn Error>> {
         println!("4. Service 4");
         println!("5. Service 5");
         println!("6. Service 6");
-        println!("7. Exit");
+        println!("7. Service 7");
+        println!("8. Exit");
 
         print!("Choice: ");
         let _ = stdout().flush();
@@ -151,7 +157,36 @@ async fn main() -> Result<(), Box<dyn Error>> {
 
                 consumer.invoke_request_service_6(flight_id, amount).await;
             }
-            7 => break,
+            7 => {
+                print!("Enter flight source: ");
+                let _ = stdout().flush();
+                let mut source = String::new();
+                stdin().read_line(&mut source)?;
+                let source = source.trim().to_string();
+
+                print!("Enter flight destination: ");
+                let _ = stdout().flush();
+                let mut destination = String::new();
+                stdin().read_line(&mut destination)?;
+                let destination = destination.trim().to_string();
+
+                print!("Enter seat threshold: ");
+                let _ = stdout().flush();
+                let mut seat_threshold = String::new();
+                stdin().read_line(&mut seat_threshold)?;
+                let seat_threshold = seat_threshold.trim().parse::<u32>().unwrap_or(0);
+
+                print!("Enter length of monitoring period: ");
+                let _ = stdout().flush();
+                let mut monitor_interval = String::new();
+                stdin().read_line(&mut monitor_interval)?;
+                let monitor_interval = monitor_interval.trim().parse::<u32>().unwrap_or(0);
+
+                consumer
+                    .request_service_7(source, destination, seat_threshold, monitor_interval)
+                    .await;
+            }
+            8 => break,
             _ => println!("Invalid choice. Please try again"),
         }
     }
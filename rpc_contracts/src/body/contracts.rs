@@ -1,14 +1,16 @@
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 
 #[derive(Serialize, Deserialize)]
 pub struct ServiceFailedResponse {
     pub error: String,
 }
 
+/// Airport codes are borrowed from the request body unless they were escaped.
 #[derive(Serialize, Deserialize)]
-pub struct Service1RequestBody {
-    pub source: String,
-    pub destination: String,
+pub struct Service1RequestBody<'a> {
+    pub source: Cow<'a, str>,
+    pub destination: Cow<'a, str>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -50,11 +52,24 @@ pub struct Service4ResponseBody {
     pub message: String,
 }
 
+/// Sequence numbers start at 1 and increase with every update of the subscription, a resent update keeps its number.
 #[derive(Serialize, Deserialize)]
 pub struct Service4MonitorResponseBody {
+    pub seq: u32,
     pub seat_avail: u32,
 }
 
+/// Service type of the acknowledgements of Service 4 and Service 7 updates, which get no reply. Reserved outside of the
+/// service numbers so that new services keep sequential ids.
+pub const MONITOR_ACK: u8 = u8::MAX;
+
+/// Acknowledges every update up to `seq` of the subscription opened by the monitor request `request_id`.
+#[derive(Serialize, Deserialize)]
+pub struct MonitorAckRequestBody {
+    pub request_id: u32,
+    pub seq: u32,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct Service5RequestBody {
     pub flight_id: u32,
@@ -75,3 +90,34 @@ pub struct Service6RequestBody {
 pub struct Service6ResponseBody {
     pub message: String,
 }
+
+/// Monitor the flights from `source` to `destination`.
+#[derive(Serialize, Deserialize)]
+pub struct Service7RequestBody<'a> {
+    pub source: Cow<'a, str>,
+    pub destination: Cow<'a, str>,
+    /// Notify when the seats available on a flight drop below this number.
+    pub seat_threshold: u32,
+    pub monitor_interval: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Service7ResponseBody {
+    pub message: String,
+    pub flight_ids: Vec<u32>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub enum RouteChange {
+    FlightAdded { flight_id: u32, departure_time: i32, airfare: f32, seat_avail: u32 },
+    SeatsBelowThreshold { flight_id: u32, seat_avail: u32 },
+    AirfareChanged { flight_id: u32, airfare: f32 },
+}
+
+/// Numbered like [`Service4MonitorResponseBody`]. The server sends the next update only once the previous one is
+/// acknowledged, so every change reaches the client in order.
+#[derive(Serialize, Deserialize)]
+pub struct Service7MonitorResponseBody {
+    pub seq: u32,
+    pub changes: Vec<RouteChange>,
+}
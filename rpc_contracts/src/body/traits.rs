@@ -1,12 +1,13 @@
 use serde::Result;
 use serde::{Deserialize, Serialize};
 
-/// this trait decodes `body` from type Vec<u8> to rust types.
+/// this trait decodes `body` from type Vec<u8> to rust types, using the codec of the message. Strings of the body may
+/// be borrowed by the decoded value.
 pub trait DecodeBody {
-    fn decode_body<D: Deserialize>(&self) -> Result<D>;
+    fn decode_body<'de, D: Deserialize<'de>>(&'de self) -> Result<D>;
 }
 
-/// this trait encodes an body into bytes.
+/// this trait encodes an body into bytes, using the codec of the message.
 // #[async_trait(?Send)]
 pub trait EncodeBody {
     fn encode_body<S: Serialize>(&mut self, body: S);
@@ -0,0 +1,217 @@
+use core::fmt::{self, Display};
+use core::str::FromStr;
+
+use serde::json::{self, DeserializerConfig};
+use serde::binary;
+use serde::{Deserialize, Error, ErrorKind, Result, Serialize};
+
+/// Wire format of an RPC message.
+///
+/// Every datagram starts with the codec tag byte, followed by the message encoded with that codec, so a server can talk
+/// to clients using different codecs at the same time and reply to each of them in its own codec.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Codec {
+    #[default]
+    Json,
+    Binary,
+}
+
+impl Codec {
+    pub fn tag(self) -> u8 {
+        match self {
+            Codec::Json => 0,
+            Codec::Binary => 1,
+        }
+    }
+
+    pub fn from_tag(tag: u8) -> Option<Codec> {
+        match tag {
+            0 => Some(Codec::Json),
+            1 => Some(Codec::Binary),
+            _ => None,
+        }
+    }
+
+    pub fn encode<S: ?Sized + Serialize>(self, value: &S) -> Vec<u8> {
+        match self {
+            Codec::Json => json::to_string(value).into_bytes(),
+            Codec::Binary => binary::to_bytes(value),
+        }
+    }
+
+    pub fn decode<'de, D: Deserialize<'de>>(self, bytes: &'de [u8]) -> Result<D> {
+        self.decode_with(bytes, &DeserializerConfig::new())
+    }
+
+    /// Decode within `limits`, which bound binary messages the same way as JSON ones.
+    pub fn decode_with<'de, D: Deserialize<'de>>(self, bytes: &'de [u8], limits: &DeserializerConfig) -> Result<D> {
+        match self {
+            Codec::Json => limits.from_slice(bytes),
+            Codec::Binary => binary::from_bytes_with(bytes, limits),
+        }
+    }
+
+    /// Encode a message, prefixed with the codec tag byte.
+    pub fn encode_message<S: ?Sized + Serialize>(self, message: &S) -> Vec<u8> {
+        let mut bytes = vec![self.tag()];
+        bytes.extend(self.encode(message));
+        bytes
+    }
+
+    /// Decode a message prefixed with a codec tag byte, returning the codec it was encoded with.
+    pub fn decode_message<'de, D: Deserialize<'de>>(bytes: &'de [u8]) -> Result<(Codec, D)> {
+        Codec::decode_message_with(bytes, &DeserializerConfig::new())
+    }
+
+    /// Same as [`decode_message`](Codec::decode_message) within `limits`.
+    pub fn decode_message_with<'de, D: Deserialize<'de>>(bytes: &'de [u8], limits: &DeserializerConfig) -> Result<(Codec, D)> {
+        let (&tag, message) = bytes.split_first().ok_or_else(|| Error::new(ErrorKind::Eof))?;
+        let codec = Codec::from_tag(tag).ok_or_else(|| Error::new(ErrorKind::InvalidTag(tag)))?;
+        Ok((codec, codec.decode_with(message, limits)?))
+    }
+}
+
+impl Display for Codec {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Codec::Json => formatter.write_str("json"),
+            Codec::Binary => formatter.write_str("binary"),
+        }
+    }
+}
+
+impl FromStr for Codec {
+    type Err = String;
+
+    fn from_str(s: &str) -> core::result::Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(Codec::Json),
+            "binary" => Ok(Codec::Binary),
+            _ => Err(format!("unknown codec `{}`, expected `json` or `binary`", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::body::{
+        DecodeBody, EncodeBody, Service1RequestBody, Service2RequestBody, Service2ResponseBody, Service3RequestBody,
+        Service3ResponseBody,
+    };
+    use crate::{Codec, RPCRequest, RPCResponse};
+    use serde::json::Value;
+    use serde::{ErrorKind, Limit};
+    use std::borrow::Cow;
+
+    #[tokio::test]
+    async fn request_should_round_trip_in_every_codec() {
+        for codec in [Codec::Json, Codec::Binary] {
+            let mut request = RPCRequest::new(3, codec).await;
+            request.encode_body(Service3RequestBody { flight_id: 7, num_seat: 2 });
+
+            let bytes = request.to_bytes();
+            assert_eq!(bytes[0], codec.tag());
+
+            let decoded = RPCRequest::from_bytes(&bytes).unwrap();
+            assert_eq!(decoded.key(), request.key());
+            assert_eq!(decoded.codec, codec);
+            let Service3RequestBody { flight_id, num_seat } = decoded.decode_body().unwrap();
+            assert_eq!((flight_id, num_seat), (7, 2));
+        }
+    }
+
+    #[tokio::test]
+    async fn airport_codes_should_be_borrowed_from_the_body() {
+        for codec in [Codec::Json, Codec::Binary] {
+            let mut request = RPCRequest::new(1, codec).await;
+            request.encode_body(Service1RequestBody { source: "LAS".into(), destination: "HAN".into() });
+
+            let request = RPCRequest::from_bytes(&request.to_bytes()).unwrap();
+            let body = request.decode_body::<Service1RequestBody>().unwrap();
+            assert!(matches!(body.source, Cow::Borrowed("LAS")));
+            assert!(matches!(body.destination, Cow::Borrowed("HAN")));
+        }
+    }
+
+    #[test]
+    fn unknown_codec_tag_should_be_rejected() {
+        let error = RPCRequest::from_bytes(&[42, b'{', b'}']).unwrap_err();
+        assert_eq!(error.kind(), &ErrorKind::InvalidTag(42));
+        let error = RPCRequest::from_bytes(&[]).unwrap_err();
+        assert_eq!(error.kind(), &ErrorKind::Eof);
+    }
+
+    #[test]
+    fn non_utf8_json_datagram_should_be_rejected() {
+        let error = RPCRequest::from_bytes(b"\x00{\"service_type\":\"\xff\"}").unwrap_err();
+        assert_eq!(error.kind(), &ErrorKind::InvalidUtf8);
+        assert_eq!(error.offset(), Some(17));
+    }
+
+    #[tokio::test]
+    async fn missing_body_field_should_report_its_path() {
+        for codec in [Codec::Json, Codec::Binary] {
+            let mut request = RPCRequest::new(3, codec).await;
+            request.encode_body(Service2RequestBody { flight_id: 7 });
+            let error = match request.decode_body::<Service3RequestBody>() {
+                Ok(_) => panic!("num_seat is missing"),
+                Err(error) => error,
+            };
+            assert_eq!(error.kind(), &ErrorKind::MissingField("num_seat"));
+            assert_eq!(error.path(), "num_seat");
+        }
+    }
+
+    #[tokio::test]
+    async fn response_dump_should_show_decoded_body() {
+        for codec in [Codec::Json, Codec::Binary] {
+            let request = RPCRequest::new(3, codec).await;
+            let mut response = RPCResponse::finished(&request).await;
+            response.encode_body(Service3ResponseBody { message: "Reserved".to_owned() });
+
+            let dump = response.to_pretty_json();
+            assert!(dump.contains("\"status\": \"Finished\""), "{}", dump);
+            assert!(dump.contains("\"body\": {\n    \"message\": \"Reserved\"\n  }"), "{}", dump);
+        }
+    }
+
+    #[tokio::test]
+    async fn json_body_should_be_byte_stable() {
+        let request = RPCRequest::new(2, Codec::Json).await;
+        let mut response = RPCResponse::finished(&request).await;
+        response.encode_body(Service2ResponseBody { departure_time: 1680000000, airfare: 150.99, seat_avail: 3 });
+        assert_eq!(response.body, br#"{"departure_time":1680000000,"airfare":150.99,"seat_avail":3}"#);
+
+        let body = response.decode_body::<Value>().unwrap();
+        assert_eq!(Codec::Json.encode(&body), response.body);
+    }
+
+    #[tokio::test]
+    async fn hostile_json_request_should_hit_limits() {
+        let nested = format!("\x00{{\"client_id\":1,\"id\":2,\"service_type\":3,\"body\":[{}]}}", "[".repeat(64));
+        let error = RPCRequest::from_bytes(nested.as_bytes()).unwrap_err();
+        assert_eq!((error.kind(), error.path()), (&ErrorKind::LimitExceeded(Limit::Depth), "body[0]"));
+
+        let mut long = vec![b' '; crate::MAX_DATAGRAM_LEN + 2];
+        long[0] = Codec::Json.tag();
+        let error = RPCRequest::from_bytes(&long).unwrap_err();
+        assert_eq!(error.kind(), &ErrorKind::LimitExceeded(Limit::InputLength));
+
+        let mut request = RPCRequest::new(1, Codec::Json).await;
+        request.body = format!(r#"{{"source":"{}","destination":"LAS"}}"#, "A".repeat(300)).into_bytes();
+        let request = RPCRequest::from_bytes(&request.to_bytes()).unwrap();
+        let error = request.decode_body::<Value>().unwrap_err();
+        assert_eq!((error.kind(), error.path()), (&ErrorKind::LimitExceeded(Limit::StringLength), "source"));
+    }
+
+    #[tokio::test]
+    async fn hostile_binary_request_should_hit_limits() {
+        let mut request = RPCRequest::new(1, Codec::Binary).await;
+        // 64 nested sequences of one element each
+        request.body = [0x0D, 0, 0, 0, 1].repeat(64);
+        request.body.push(0x00);
+        let request = RPCRequest::from_bytes(&request.to_bytes()).unwrap();
+        let error = request.decode_body::<Value>().unwrap_err();
+        assert_eq!((error.kind(), error.path()), (&ErrorKind::LimitExceeded(Limit::Depth), "[0][0]"));
+    }
+}
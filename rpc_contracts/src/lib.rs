@@ -6,3 +6,6 @@ pub use response::*;
 
 pub mod body;
 pub use body::*;
+
+pub mod codec;
+pub use codec::*;
@@ -1,24 +1,49 @@
 use lazy_static::lazy_static;
-use std::str;
 use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
 use crate::body::{DecodeBody, EncodeBody};
-use serde::json;
-use serde::{Deserialize, Error, Result, Serialize};
+use crate::Codec;
+use serde::json::DeserializerConfig;
+use serde::{Deserialize, Result, Serialize};
 
+/// Largest datagram a server reads, longer requests are truncated.
+pub const MAX_DATAGRAM_LEN: usize = 2048;
+
+/// Limits applied to requests received from the network. A request fits in one datagram, its body is encoded as an
+/// array of bytes and holds a flat struct, and airport codes or similar strings are short.
+pub fn request_limits() -> DeserializerConfig {
+    DeserializerConfig::new()
+        .max_input_len(MAX_DATAGRAM_LEN)
+        .max_depth(2)
+        .max_string_len(256)
+        .max_collection_len(MAX_DATAGRAM_LEN)
+}
+
+/// A request is identified by the pair (`client_id`, `id`): `client_id` is drawn at random once per client run and `id` is a
+/// sequence number increasing monotonically within that run, so requests from different or restarted clients never collide.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct RPCRequest {
+    pub client_id: u64,
     pub id: u32,
     pub service_type: u8,
+    /// Codec of the whole message, also used for `body`. Carried by the message header rather than the message itself.
+    #[serde(skip)]
+    pub codec: Codec,
     pub body: Vec<u8>,
 }
 
 lazy_static! {
+    static ref CLIENT_ID: u64 = rand::random();
     static ref ID_COUNTER: Arc<Mutex<AtomicU32>> = Arc::new(Mutex::new(AtomicU32::new(0)));
 }
 
+/// Identity of this client process, fixed for the whole run.
+pub fn get_client_id() -> u64 {
+    *CLIENT_ID
+}
+
 pub async fn get_request_id() -> u32 {
     let counter = ID_COUNTER.lock().await;
     let id = counter.fetch_add(1, Ordering::SeqCst);
@@ -27,30 +52,47 @@ pub async fn get_request_id() -> u32 {
 
 impl RPCRequest {
     #[allow(dead_code)]
-    pub async fn new(service_type: u8) -> Self {
+    pub async fn new(service_type: u8, codec: Codec) -> Self {
         let id = get_request_id().await;
         RPCRequest {
+            client_id: get_client_id(),
             id,
             service_type,
+            codec,
             body: Vec::new(),
         }
     }
+
+    /// Encode this request into a datagram, prefixed with its codec tag.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.codec.encode_message(self)
+    }
+
+    /// Decode a request from a datagram, in whichever codec the header says it was encoded with, within
+    /// [`request_limits`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let (codec, mut request): (Codec, RPCRequest) = Codec::decode_message_with(bytes, &request_limits())?;
+        request.codec = codec;
+        Ok(request)
+    }
+}
+
+impl RPCRequest {
+    /// Key uniquely identifying this request across clients and client restarts.
+    pub fn key(&self) -> (u64, u32) {
+        (self.client_id, self.id)
+    }
 }
 
 impl DecodeBody for RPCRequest {
-    fn decode_body<D: Deserialize>(&self) -> Result<D> {
-        let body_string = match str::from_utf8(&self.body) {
-            Ok(str) => str,
-            Err(_) => return Err(Error),
-        };
-        let decoded_body: D = json::from_str(body_string)?;
-        Ok(decoded_body)
+    /// The body is decoded within [`request_limits`] as well.
+    fn decode_body<'de, D: Deserialize<'de>>(&'de self) -> Result<D> {
+        self.codec.decode_with(&self.body, &request_limits())
     }
 }
 
 impl EncodeBody for RPCRequest {
     fn encode_body<S: Serialize>(&mut self, body: S) {
-        let body_string = json::to_string(&body);
-        self.body = body_string.into_bytes();
+        self.body = self.codec.encode(&body);
     }
 }
@@ -1,21 +1,23 @@
 use lazy_static::lazy_static;
-use std::str;
 use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
 use crate::body::{DecodeBody, EncodeBody};
-use crate::RPCRequest;
-use serde::json;
-use serde::{Deserialize, Error, Result, Serialize};
+use crate::{Codec, RPCRequest};
+use serde::json::{self, Value};
+use serde::{Deserialize, Result, Serialize};
 
-// NOTE: no requirement for request caching so reinitialize all request Id to 1 after each server session,
-// can easily be improved in the future.
+// NOTE: response ids restart after each server session, replies are matched to requests through (`client_id`, `request_id`).
 #[derive(Debug, Serialize, Deserialize)]
 pub struct RPCResponse {
     id: u32,
+    pub client_id: u64,
     pub request_id: u32,
     pub status: ResponseStatus,
+    /// Codec of the whole message, always the one used by the request this response answers.
+    #[serde(skip)]
+    pub codec: Codec,
     pub body: Vec<u8>,
 }
 
@@ -37,32 +39,38 @@ pub async fn get_response_id() -> u32 {
 }
 
 impl RPCResponse {
-    pub async fn finished(request_id: u32) -> Self {
+    pub async fn finished(request: &RPCRequest) -> Self {
         let id = get_response_id().await;
         RPCResponse {
             id,
-            request_id,
+            client_id: request.client_id,
+            request_id: request.id,
             status: ResponseStatus::Finished,
+            codec: request.codec,
             body: Vec::new(),
         }
     }
 
-    pub async fn failed(request_id: u32) -> Self {
+    pub async fn failed(request: &RPCRequest) -> Self {
         let id = get_response_id().await;
         RPCResponse {
             id,
-            request_id,
+            client_id: request.client_id,
+            request_id: request.id,
             status: ResponseStatus::Failed,
+            codec: request.codec,
             body: Vec::new(),
         }
     }
 
-    pub async fn updated(request_id: u32) -> Self {
+    pub async fn updated(request: &RPCRequest) -> Self {
         let id = get_response_id().await;
         RPCResponse {
             id,
-            request_id,
+            client_id: request.client_id,
+            request_id: request.id,
             status: ResponseStatus::Updated,
+            codec: request.codec,
             body: Vec::new(),
         }
     }
@@ -72,30 +80,67 @@ impl RPCResponse {
         let message = "Invalid service type";
         let mut response = RPCResponse {
             id,
+            client_id: request.client_id,
             request_id: request.id,
             status: ResponseStatus::Failed,
+            codec: request.codec,
             body: Vec::new(),
         };
 
         response.encode_body(message);
         response
     }
+
+    /// Encode this response into a datagram, prefixed with its codec tag.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.codec.encode_message(self)
+    }
+
+    /// Decode a response from a datagram, in whichever codec the header says it was encoded with.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let (codec, mut response): (Codec, RPCResponse) = Codec::decode_message(bytes)?;
+        response.codec = codec;
+        Ok(response)
+    }
+
+    /// Pretty-printed JSON of this response with its body decoded, for logs.
+    pub fn to_pretty_json(&self) -> String {
+        let dump = ResponseDump {
+            id: self.id,
+            client_id: self.client_id,
+            request_id: self.request_id,
+            status: &self.status,
+            codec: self.codec,
+            body: self.decode_body().ok(),
+        };
+        json::to_string_pretty(&dump)
+    }
+
+    /// Whether this response is the reply to `request`.
+    pub fn answers(&self, request: &RPCRequest) -> bool {
+        self.client_id == request.client_id && self.request_id == request.id
+    }
+}
+
+#[derive(Serialize)]
+struct ResponseDump<'a> {
+    id: u32,
+    client_id: u64,
+    request_id: u32,
+    status: &'a ResponseStatus,
+    codec: Codec,
+    /// `None` when the body is not valid in the response codec.
+    body: Option<Value>,
 }
 
 impl EncodeBody for RPCResponse {
     fn encode_body<S: Serialize>(&mut self, body: S) {
-        let body_string = json::to_string(&body);
-        self.body = body_string.into_bytes();
+        self.body = self.codec.encode(&body);
     }
 }
 
 impl DecodeBody for RPCResponse {
-    fn decode_body<D: Deserialize>(&self) -> Result<D> {
-        let body_string = match str::from_utf8(&self.body) {
-            Ok(str) => str,
-            Err(_) => return Err(Error),
-        };
-        let decoded_body: D = json::from_str(body_string)?;
-        Ok(decoded_body)
+    fn decode_body<'de, D: Deserialize<'de>>(&'de self) -> Result<D> {
+        self.codec.decode(&self.body)
     }
 }
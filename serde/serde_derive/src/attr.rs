@@ -1,9 +1,156 @@
-use syn::{Field, Result, Variant};
+use crate::case::RenameRule;
+use syn::meta::ParseNestedMeta;
+use syn::{Attribute, DeriveInput, Error, ExprPath, Field, Fields, LitStr, Result, Token, Variant};
 
-pub fn name_of_field(field: &Field) -> Result<String> {
-    Ok(field.ident.as_ref().unwrap().to_string())
+/// Enum representation selected by the `#[serde(tag = "...", content = "...")]` container attributes.
+pub enum Tagging {
+    External,
+    Internal(String),
+    Adjacent(String, String),
 }
 
-pub fn name_of_variant(var: &Variant) -> Result<String> {
-    Ok(var.ident.to_string())
+/// Attributes of the struct or enum being derived.
+pub struct Container {
+    pub tagging: Tagging,
+    pub rename_all: Option<RenameRule>,
+    pub deny_unknown_fields: bool,
+}
+
+/// Value of a field absent from the input.
+pub enum Default {
+    /// The field is required.
+    None,
+    /// `Default::default()`
+    Trait,
+    /// Function returning the value.
+    Path(ExprPath),
+}
+
+/// Attributes of a named field.
+pub struct FieldAttrs {
+    pub name: String,
+    /// Neither serialized nor deserialized, filled from its default value.
+    pub skip: bool,
+    pub default: Default,
+    pub skip_serializing_if: Option<ExprPath>,
+}
+
+fn parse_serde_attrs(attrs: &[Attribute], mut parse: impl FnMut(&ParseNestedMeta) -> Result<bool>) -> Result<()> {
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+        attr.parse_nested_meta(|meta| match parse(&meta)? {
+            true => Ok(()),
+            false => Err(meta.error("unsupported serde attribute")),
+        })?;
+    }
+    Ok(())
+}
+
+fn parse_str(meta: &ParseNestedMeta) -> Result<LitStr> {
+    meta.value()?.parse::<LitStr>()
+}
+
+pub fn container(input: &DeriveInput) -> Result<Container> {
+    let mut tag = None;
+    let mut content = None;
+    let mut rename_all = None;
+    let mut deny_unknown_fields = false;
+    parse_serde_attrs(&input.attrs, |meta| {
+        if meta.path.is_ident("tag") {
+            tag = Some(parse_str(meta)?);
+        } else if meta.path.is_ident("content") {
+            content = Some(parse_str(meta)?);
+        } else if meta.path.is_ident("rename_all") {
+            let rule = parse_str(meta)?;
+            rename_all = match RenameRule::from_str(&rule.value()) {
+                Some(rule) => Some(rule),
+                None => {
+                    let message = format!("unknown rename rule, expected one of {}", RenameRule::names());
+                    return Err(Error::new_spanned(rule, message));
+                }
+            };
+        } else if meta.path.is_ident("deny_unknown_fields") {
+            deny_unknown_fields = true;
+        } else {
+            return Ok(false);
+        }
+        Ok(true)
+    })?;
+
+    let tagging = match (tag, content) {
+        (None, None) => Tagging::External,
+        (Some(tag), None) => Tagging::Internal(tag.value()),
+        (Some(tag), Some(content)) => Tagging::Adjacent(tag.value(), content.value()),
+        (None, Some(content)) => return Err(Error::new_spanned(content, "`content` requires a `tag` attribute")),
+    };
+    Ok(Container {
+        tagging,
+        rename_all,
+        deny_unknown_fields,
+    })
+}
+
+pub fn container_of_struct(input: &DeriveInput) -> Result<Container> {
+    let container = container(input)?;
+    match container.tagging {
+        Tagging::External => Ok(container),
+        _ => Err(Error::new_spanned(input, "`tag` and `content` attributes are only supported on enums")),
+    }
+}
+
+pub fn field(field: &Field, rename_all: Option<RenameRule>) -> Result<FieldAttrs> {
+    let ident = field.ident.as_ref().unwrap().to_string();
+    let ident = ident.trim_start_matches("r#");
+    let mut attrs = FieldAttrs {
+        name: rename_all.map_or_else(|| ident.to_owned(), |rule| rule.apply_to_field(ident)),
+        skip: false,
+        default: Default::None,
+        skip_serializing_if: None,
+    };
+    parse_serde_attrs(&field.attrs, |meta| {
+        if meta.path.is_ident("rename") {
+            attrs.name = parse_str(meta)?.value();
+        } else if meta.path.is_ident("skip") {
+            attrs.skip = true;
+        } else if meta.path.is_ident("default") {
+            attrs.default = match meta.input.peek(Token![=]) {
+                true => Default::Path(parse_str(meta)?.parse()?),
+                false => Default::Trait,
+            };
+        } else if meta.path.is_ident("skip_serializing_if") {
+            attrs.skip_serializing_if = Some(parse_str(meta)?.parse()?);
+        } else {
+            return Ok(false);
+        }
+        Ok(true)
+    })?;
+
+    if attrs.skip && matches!(attrs.default, Default::None) {
+        attrs.default = Default::Trait;
+    }
+    Ok(attrs)
+}
+
+/// Field attributes only apply to named fields, reject them on the fields of tuple structs and tuple variants rather
+/// than ignoring them.
+pub fn reject_unnamed_field_attrs(fields: &Fields) -> Result<()> {
+    if let Fields::Unnamed(fields) = fields {
+        let mut attrs = fields.unnamed.iter().flat_map(|field| &field.attrs);
+        if let Some(attr) = attrs.find(|attr| attr.path().is_ident("serde")) {
+            return Err(Error::new_spanned(attr, "serde attributes are not supported on tuple fields"));
+        }
+    }
+    Ok(())
+}
+
+pub fn name_of_variant(var: &Variant, rename_all: Option<RenameRule>) -> Result<String> {
+    let ident = var.ident.to_string();
+    let mut name = rename_all.map_or_else(|| ident.clone(), |rule| rule.apply_to_variant(&ident));
+    parse_serde_attrs(&var.attrs, |meta| {
+        if meta.path.is_ident("rename") {
+            name = parse_str(meta)?.value();
+            return Ok(true);
+        }
+        Ok(false)
+    })?;
+    Ok(name)
 }
@@ -1,8 +1,8 @@
 use proc_macro2::{Span, TokenStream};
 use syn::punctuated::Punctuated;
 use syn::{
-    parse_quote, GenericParam, Generics, Lifetime, LifetimeParam, TypeParamBound, WhereClause,
-    WherePredicate,
+    parse_quote, GenericArgument, GenericParam, Generics, Ident, Lifetime, LifetimeParam, PathArguments, ReturnType, Type,
+    TypeParamBound, WhereClause, WherePredicate,
 };
 
 pub fn with_lifetime_bound(generics: &Generics, lifetime: &str) -> Generics {
@@ -36,11 +36,51 @@ pub fn with_lifetime_bound(generics: &Generics, lifetime: &str) -> Generics {
     }
 }
 
-pub fn where_clause_with_bound(generics: &Generics, bound: TokenStream) -> WhereClause {
-    let new_predicates = generics.type_params().map::<WherePredicate, _>(|param| {
-        let param = &param.ident;
-        parse_quote!(#param : #bound)
-    });
+/// The `'de` lifetime of the input, outliving every lifetime parameter of `borrowed` so that fields may borrow from
+/// the input, e.g. `'de: 'a` for `struct Route<'a> { source: &'a str }`.
+pub fn de_lifetime(borrowed: &Generics) -> LifetimeParam {
+    LifetimeParam {
+        attrs: Vec::new(),
+        lifetime: Lifetime::new("'de", Span::call_site()),
+        colon_token: None,
+        bounds: borrowed.lifetimes().map(|param| param.lifetime.clone()).collect(),
+    }
+}
+
+/// Prepend the `'de` lifetime of [`de_lifetime`] to `generics`.
+pub fn with_de_lifetime(generics: &Generics, borrowed: &Generics) -> Generics {
+    let params = Some(GenericParam::Lifetime(de_lifetime(borrowed)))
+        .into_iter()
+        .chain(generics.params.iter().cloned())
+        .collect();
+
+    Generics {
+        params,
+        ..generics.clone()
+    }
+}
+
+/// Add `T: bound` for every type parameter `T` used by the serialized fields. Parameters only appearing in
+/// `PhantomData` or in skipped fields are left unbounded, e.g. `struct Page<T, M> { items: Vec<T>, marker: PhantomData<M> }`
+/// only requires `T: bound`.
+pub fn where_clause_with_bound<'a>(
+    generics: &Generics,
+    field_types: impl IntoIterator<Item = &'a Type>,
+    bound: TokenStream,
+) -> WhereClause {
+    let params = generics.type_params().map(|param| &param.ident).collect::<Vec<_>>();
+    let mut used = Vec::new();
+    for ty in field_types {
+        collect_type_params(ty, &params, &mut used);
+    }
+
+    let new_predicates = generics
+        .type_params()
+        .filter(|param| used.contains(&&param.ident))
+        .map::<WherePredicate, _>(|param| {
+            let param = &param.ident;
+            parse_quote!(#param : #bound)
+        });
 
     let mut generics = generics.clone();
     generics
@@ -49,3 +89,65 @@ pub fn where_clause_with_bound(generics: &Generics, bound: TokenStream) -> Where
         .extend(new_predicates);
     generics.where_clause.unwrap()
 }
+
+/// Whether `ty` mentions any of the type parameters of `generics`.
+pub fn mentions_type_params(generics: &Generics, ty: &Type) -> bool {
+    let params = generics.type_params().map(|param| &param.ident).collect::<Vec<_>>();
+    let mut used = Vec::new();
+    collect_type_params(ty, &params, &mut used);
+    !used.is_empty()
+}
+
+fn collect_type_params<'a>(ty: &Type, params: &[&'a Ident], used: &mut Vec<&'a Ident>) {
+    match ty {
+        Type::Path(ty) => {
+            if let Some(qself) = &ty.qself {
+                collect_type_params(&qself.ty, params, used);
+            }
+            if let Some(first) = ty.path.segments.first() {
+                if let Some(param) = params.iter().find(|param| **param == &first.ident) {
+                    if !used.contains(param) {
+                        used.push(param);
+                    }
+                }
+            }
+            if ty.path.segments.last().is_some_and(|last| last.ident == "PhantomData") {
+                return;
+            }
+            for segment in &ty.path.segments {
+                match &segment.arguments {
+                    PathArguments::AngleBracketed(arguments) => {
+                        for argument in &arguments.args {
+                            match argument {
+                                GenericArgument::Type(ty) => collect_type_params(ty, params, used),
+                                GenericArgument::AssocType(assoc) => collect_type_params(&assoc.ty, params, used),
+                                _ => {}
+                            }
+                        }
+                    }
+                    PathArguments::Parenthesized(arguments) => {
+                        for ty in &arguments.inputs {
+                            collect_type_params(ty, params, used);
+                        }
+                        if let ReturnType::Type(_, ty) = &arguments.output {
+                            collect_type_params(ty, params, used);
+                        }
+                    }
+                    PathArguments::None => {}
+                }
+            }
+        }
+        Type::Reference(ty) => collect_type_params(&ty.elem, params, used),
+        Type::Slice(ty) => collect_type_params(&ty.elem, params, used),
+        Type::Array(ty) => collect_type_params(&ty.elem, params, used),
+        Type::Ptr(ty) => collect_type_params(&ty.elem, params, used),
+        Type::Paren(ty) => collect_type_params(&ty.elem, params, used),
+        Type::Group(ty) => collect_type_params(&ty.elem, params, used),
+        Type::Tuple(ty) => {
+            for elem in &ty.elems {
+                collect_type_params(elem, params, used);
+            }
+        }
+        _ => {}
+    }
+}
@@ -1,8 +1,11 @@
-use crate::{attr, bound};
+use crate::attr::{self, Tagging};
+use crate::case::RenameRule;
+use crate::{bound, ser};
 use proc_macro2::{Span, TokenStream};
-use quote::quote;
+use quote::{format_ident, quote};
 use syn::{
-    parse_quote, Data, DataEnum, DataStruct, DeriveInput, Error, Fields, FieldsNamed, Ident, Result,
+    parse_quote, Data, DataEnum, DataStruct, DeriveInput, Error, Fields, FieldsNamed, Generics, Ident, Result,
+    Type, WhereClause, WherePredicate,
 };
 
 pub fn derive(input: DeriveInput) -> Result<TokenStream> {
@@ -11,10 +14,11 @@ pub fn derive(input: DeriveInput) -> Result<TokenStream> {
             fields: Fields::Named(fields),
             ..
         }) => derive_struct(&input, fields),
+        Data::Struct(DataStruct { fields, .. }) => derive_tuple_struct(&input, fields),
         Data::Enum(enumeration) => derive_enum(&input, enumeration),
-        _ => Err(Error::new(
+        Data::Union(_) => Err(Error::new(
             Span::call_site(),
-            "only named fields structs and variants enums are supported"
+            "only structs and enums are supported"
         ))
     }
 }
@@ -23,33 +27,34 @@ pub fn derive_struct(input: &DeriveInput, fields: &FieldsNamed) -> Result<TokenS
     let ident = &input.ident;
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
 
-    let fieldname = fields.named.iter().map(|f| &f.ident).collect::<Vec<_>>();
-    let fieldty = fields.named.iter().map(|f| &f.ty);
-    let fieldstr = fields
-        .named
-        .iter()
-        .map(attr::name_of_field)
-        .collect::<Result<Vec<_>>>()?;
+    let container = attr::container_of_struct(input)?;
+    let NamedFields {
+        slots,
+        tys,
+        key_arms,
+        values,
+        defaulted,
+    } = named_fields(fields, container.rename_all, |fieldname| fieldname.clone())?;
+    let unknown = unknown_field(container.deny_unknown_fields);
 
+    let de_generics = bound::with_de_lifetime(&input.generics, &input.generics);
+    let (de_impl_generics, _, _) = de_generics.split_for_impl();
     let wrapper_generics = bound::with_lifetime_bound(&input.generics, "'__a");
     let (wrapper_impl_generics, wrapper_ty_generics, _) = wrapper_generics.split_for_impl();
-    let bound = parse_quote!(serde::Deserialize);
-    let bounded_where_clause = bound::where_clause_with_bound(&input.generics, bound);
+    let bounded_where_clause = bounded_where_clause(&input.generics, tys.iter().copied(), &defaulted);
+    let de_wrapper_generics = bound::with_de_lifetime(&wrapper_generics, &input.generics);
+    let (de_wrapper_impl_generics, _, _) = de_wrapper_generics.split_for_impl();
 
-    let dummy_indent = Ident::new(
-        &format!("_IMPL_DESERIALIZE_FOR_{}", ident),
-        Span::call_site(),
-    );
     Ok(quote! {
         #[allow(non_upper_case_globals)]
-        const #dummy_indent: () = {
+        const _: () = {
             #[repr(C)]
             struct __Visitor #impl_generics #where_clause {
                 __out: serde::__private::Option<#ident #ty_generics>,
             }
 
-            impl #impl_generics serde::Deserialize for #ident #ty_generics #bounded_where_clause {
-                fn begin(__out: &mut serde::__private::Option<Self>) -> &mut dyn serde::de::Visitor {
+            impl #de_impl_generics serde::Deserialize<'de> for #ident #ty_generics #bounded_where_clause {
+                fn begin(__out: &mut serde::__private::Option<Self>) -> &mut dyn serde::de::Visitor<'de> {
                     unsafe {
                         &mut *{
                             __out
@@ -60,11 +65,11 @@ pub fn derive_struct(input: &DeriveInput, fields: &FieldsNamed) -> Result<TokenS
                 }
             }
 
-            impl #impl_generics serde::de::Visitor for __Visitor #ty_generics #bounded_where_clause {
-                fn map(&mut self) -> serde::Result<serde::__private::Box<dyn serde::de::Map + '_>> {
+            impl #de_impl_generics serde::de::Visitor<'de> for __Visitor #ty_generics #bounded_where_clause {
+                fn map(&mut self) -> serde::Result<serde::__private::Box<dyn serde::de::Map<'de> + '_>> {
                     Ok(serde::__private::Box::new(__State {
                         #(
-                            #fieldname: serde::Deserialize::default(),
+                            #slots: serde::Deserialize::default(),
                         )*
                         __out: &mut self.__out,
                     }))
@@ -73,28 +78,23 @@ pub fn derive_struct(input: &DeriveInput, fields: &FieldsNamed) -> Result<TokenS
 
             struct __State #wrapper_impl_generics #where_clause {
                 #(
-                    #fieldname: serde::__private::Option<#fieldty>,
+                    #slots: serde::__private::Option<#tys>,
                 )*
                 __out: &'__a mut serde::__private::Option<#ident #ty_generics>,
             }
 
-            impl #wrapper_impl_generics serde::de::Map for __State #wrapper_ty_generics #bounded_where_clause {
-                fn key(&mut self, __k: &serde::__private::str) -> serde::Result<&mut dyn serde::de::Visitor> {
+            impl #de_wrapper_impl_generics serde::de::Map<'de> for __State #wrapper_ty_generics #bounded_where_clause {
+                fn key(&mut self, __k: &serde::__private::str) -> serde::Result<&mut dyn serde::de::Visitor<'de>> {
                     match __k {
-                        #(
-                            #fieldstr => serde::__private::Ok(serde::Deserialize::begin(&mut self.#fieldname)),
-                        )*
-                        _ => serde::__private::Ok(<dyn serde::de::Visitor>::ignore()),
+                        #( #key_arms )*
+                        _ => #unknown,
                     }
                 }
 
                 fn finish(&mut self) -> serde::Result<()> {
-                    #(
-                        let #fieldname = self.#fieldname.take().ok_or(serde::Error)?;
-                    )*
                     *self.__out = serde::__private::Some(#ident {
                         #(
-                            #fieldname,
+                            #values,
                         )*
                     });
                     serde::__private::Ok(())
@@ -104,67 +104,583 @@ pub fn derive_struct(input: &DeriveInput, fields: &FieldsNamed) -> Result<TokenS
     })
 }
 
-pub fn derive_enum(input: &DeriveInput, enumeration: &DataEnum) -> Result<TokenStream> {
-    if input.generics.lt_token.is_some() || input.generics.where_clause.is_some() {
-        return Err(Error::new(
-            Span::call_site(),
-            "Enums with generics are not supported",
-        ));
+/// Newtype structs are deserialized from their field, tuple structs from a sequence and unit structs from null.
+pub fn derive_tuple_struct(input: &DeriveInput, fields: &Fields) -> Result<TokenStream> {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    attr::container_of_struct(input)?;
+    attr::reject_unnamed_field_attrs(fields)?;
+
+    let fields = match fields {
+        Fields::Unnamed(fields) => fields.unnamed.iter().collect::<Vec<_>>(),
+        _ => Vec::new(),
+    };
+    let de_generics = bound::with_de_lifetime(&input.generics, &input.generics);
+    let (de_impl_generics, _, _) = de_generics.split_for_impl();
+    let bounded_where_clause = bounded_where_clause(&input.generics, fields.iter().map(|f| &f.ty), &[]);
+
+    if let [field] = fields.as_slice() {
+        let fieldty = &field.ty;
+        return Ok(quote! {
+            const _: () = {
+                impl #de_impl_generics serde::__private::Wrapper<'de> for #ident #ty_generics #bounded_where_clause {
+                    type Inner = #fieldty;
+
+                    fn wrap(__inner: #fieldty) -> Self {
+                        #ident(__inner)
+                    }
+                }
+
+                impl #de_impl_generics serde::Deserialize<'de> for #ident #ty_generics #bounded_where_clause {
+                    fn begin(__out: &mut serde::__private::Option<Self>) -> &mut dyn serde::de::Visitor<'de> {
+                        serde::__private::begin_wrapper(__out)
+                    }
+
+                    fn default() -> serde::__private::Option<Self> {
+                        serde::__private::default_wrapper()
+                    }
+                }
+            };
+        });
     }
 
+    let visitor = quote! {
+        #[repr(C)]
+        struct __Visitor #impl_generics #where_clause {
+            __out: serde::__private::Option<#ident #ty_generics>,
+        }
+
+        impl #de_impl_generics serde::Deserialize<'de> for #ident #ty_generics #bounded_where_clause {
+            fn begin(__out: &mut serde::__private::Option<Self>) -> &mut dyn serde::de::Visitor<'de> {
+                unsafe {
+                    &mut *{
+                        __out
+                        as *mut serde::__private::Option<Self>
+                        as *mut __Visitor #ty_generics
+                    }
+                }
+            }
+        }
+    };
+
+    if fields.is_empty() {
+        return Ok(quote! {
+            const _: () = {
+                #visitor
+
+                impl #de_impl_generics serde::de::Visitor<'de> for __Visitor #ty_generics #bounded_where_clause {
+                    fn null(&mut self) -> serde::Result<()> {
+                        self.__out = serde::__private::Some(#ident);
+                        serde::__private::Ok(())
+                    }
+                }
+            };
+        });
+    }
+
+    let slot = (0..fields.len()).map(|i| format_ident!("__f{}", i)).collect::<Vec<_>>();
+    let fieldty = fields.iter().map(|f| &f.ty);
+    let index = 0usize..;
+    let mismatch = quote!(serde::Error::new(serde::ErrorKind::TypeMismatch));
+
+    let wrapper_generics = bound::with_lifetime_bound(&input.generics, "'__a");
+    let (wrapper_impl_generics, wrapper_ty_generics, _) = wrapper_generics.split_for_impl();
+    let de_wrapper_generics = bound::with_de_lifetime(&wrapper_generics, &input.generics);
+    let (de_wrapper_impl_generics, _, _) = de_wrapper_generics.split_for_impl();
+
+    Ok(quote! {
+        const _: () = {
+            #visitor
+
+            impl #de_impl_generics serde::de::Visitor<'de> for __Visitor #ty_generics #bounded_where_clause {
+                fn seq(&mut self) -> serde::Result<serde::__private::Box<dyn serde::de::Seq<'de> + '_>> {
+                    serde::__private::Ok(serde::__private::Box::new(__State {
+                        #( #slot: serde::Deserialize::default(), )*
+                        __index: 0,
+                        __out: &mut self.__out,
+                    }))
+                }
+            }
+
+            struct __State #wrapper_impl_generics #where_clause {
+                #( #slot: serde::__private::Option<#fieldty>, )*
+                __index: serde::__private::usize,
+                __out: &'__a mut serde::__private::Option<#ident #ty_generics>,
+            }
+
+            impl #de_wrapper_impl_generics serde::de::Seq<'de> for __State #wrapper_ty_generics #bounded_where_clause {
+                fn element(&mut self) -> serde::Result<&mut dyn serde::de::Visitor<'de>> {
+                    let __index = self.__index;
+                    self.__index += 1;
+                    match __index {
+                        #( #index => serde::__private::Ok(serde::Deserialize::begin(&mut self.#slot)), )*
+                        _ => serde::__private::Err(#mismatch),
+                    }
+                }
+
+                fn finish(&mut self) -> serde::Result<()> {
+                    *self.__out = serde::__private::Some(#ident(
+                        #( self.#slot.take().ok_or_else(|| #mismatch)?, )*
+                    ));
+                    serde::__private::Ok(())
+                }
+            }
+        };
+    })
+}
+
+pub fn derive_enum(input: &DeriveInput, enumeration: &DataEnum) -> Result<TokenStream> {
     let ident = &input.ident;
-    let var_idents = enumeration
-        .variants
-        .iter()
-        .map(|variant| match variant.fields {
-            Fields::Unit => Ok(&variant.ident),
-            _ => Err(Error::new_spanned(
-                variant,
-                "Invalid variant: only simple enum variants without fields are supported",
-            )),
-        })
-        .collect::<Result<Vec<_>>>()?;
-    let names = enumeration
-        .variants
-        .iter()
-        .map(attr::name_of_variant)
-        .collect::<Result<Vec<_>>>()?;
-
-    let dummy_indent = Ident::new(
-        &format!("_IMPL_DESERIALIZE_FOR_{}", ident),
-        Span::call_site(),
-    );
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let container = attr::container(input)?;
+    let repr = &container.tagging;
+    let unknown = unknown_field(container.deny_unknown_fields);
+    let mismatch = quote!(serde::Error::new(serde::ErrorKind::TypeMismatch));
+
+    let mut tags = Vec::new();
+    let mut names = Vec::new();
+    let mut slots = Vec::new();
+    let mut slot_defs = Vec::new();
+    let mut string_arms = Vec::new();
+    let mut constructors = Vec::new();
+    let mut unit_tags = Vec::new();
+    let mut newtype_arms = Vec::new();
+    let mut seq_tags = Vec::new();
+    let mut element_arms = Vec::new();
+    let mut map_tags = Vec::new();
+    let mut field_arms = Vec::new();
+    let mut deserialized_types = Vec::new();
+    let mut defaulted_types = Vec::new();
+    let mut replay_arms = Vec::new();
+    let internal = matches!(repr, Tagging::Internal(_));
+
+    for (i, variant) in enumeration.variants.iter().enumerate() {
+        let var_ident = &variant.ident;
+        let name = attr::name_of_variant(variant, container.rename_all)?;
+        attr::reject_unnamed_field_attrs(&variant.fields)?;
+        let tag = format_ident!("__V{}", i);
+        match &variant.fields {
+            Fields::Unit => {
+                string_arms.push(quote!(#name => #ident::#var_ident,));
+                constructors.push(quote!(serde::__private::Some(__Tag::#tag) => #ident::#var_ident,));
+                unit_tags.push(tag.clone());
+            }
+            Fields::Unnamed(fields) if internal && fields.unnamed.len() != 1 => {
+                return Err(ser::internal_variant_error(variant));
+            }
+            Fields::Unnamed(fields) => {
+                let slot = (0..fields.unnamed.len())
+                    .map(|j| format_ident!("__v{}_{}", i, j))
+                    .collect::<Vec<_>>();
+                let fieldty = fields.unnamed.iter().map(|f| &f.ty);
+                deserialized_types.extend(fieldty.clone());
+                slot_defs.push(quote!(#( #slot: serde::__private::Option<#fieldty>, )*));
+                string_arms.push(quote!(#name => return serde::__private::Err(#mismatch),));
+                constructors.push(quote! {
+                    serde::__private::Some(__Tag::#tag) => #ident::#var_ident(
+                        #( self.#slot.take().ok_or_else(|| #mismatch)?, )*
+                    ),
+                });
+                if let ([newtype], true) = (slot.as_slice(), internal) {
+                    // the content is every entry besides the tag, read as a map once they are all known
+                    field_arms.push(quote! {
+                        serde::__private::Some(__Tag::#tag) => {
+                            serde::__private::Ok(serde::__private::buffer_entry(&mut self.__buffered, __k))
+                        }
+                    });
+                    replay_arms.push(quote! {
+                        serde::__private::Some(__Tag::#tag) => serde::__private::replay_map(
+                            serde::__private::take(&mut self.__buffered),
+                            serde::Deserialize::begin(&mut self.#newtype),
+                        )?,
+                    });
+                } else if let [newtype] = slot.as_slice() {
+                    newtype_arms.push(quote! {
+                        serde::__private::Some(__Tag::#tag) => {
+                            serde::__private::Ok(serde::Deserialize::begin(&mut self.#newtype))
+                        }
+                    });
+                } else {
+                    let index = 0usize..;
+                    element_arms.push(quote! {
+                        #(
+                            (serde::__private::Some(__Tag::#tag), #index) => {
+                                serde::__private::Ok(serde::Deserialize::begin(&mut self.__state.#slot))
+                            }
+                        )*
+                    });
+                    seq_tags.push(tag.clone());
+                }
+                slots.extend(slot);
+            }
+            Fields::Named(fields) => {
+                let NamedFields {
+                    slots: slot,
+                    tys,
+                    key_arms,
+                    values,
+                    defaulted,
+                } = named_fields(fields, None, |fieldname| format_ident!("__v{}_{}", i, fieldname))?;
+                deserialized_types.extend(tys.iter().copied());
+                defaulted_types.extend(defaulted);
+                slot_defs.push(quote!(#( #slot: serde::__private::Option<#tys>, )*));
+                string_arms.push(quote!(#name => return serde::__private::Err(#mismatch),));
+                constructors.push(quote! {
+                    serde::__private::Some(__Tag::#tag) => #ident::#var_ident {
+                        #( #values, )*
+                    },
+                });
+                field_arms.push(quote! {
+                    serde::__private::Some(__Tag::#tag) => match __k {
+                        #( #key_arms )*
+                        _ => #unknown,
+                    },
+                });
+                map_tags.push(tag.clone());
+                slots.extend(slot);
+            }
+        }
+        tags.push(tag);
+        names.push(name);
+    }
+
+    // Externally tagged enums read the variant from a string or from the single key of a map, the other
+    // representations from the value of the tag key. Adjacently tagged content must come after the tag, internally
+    // tagged entries read before it are buffered until the variant is known.
+    let (missing_tag, visitor_string, key_body) = match repr {
+        Tagging::External => (
+            mismatch.clone(),
+            quote! {
+                fn string(&mut self, __s: &serde::__private::str) -> serde::Result<()> {
+                    let __value = match __s {
+                        #( #string_arms )*
+                        _ => return serde::__private::Err(serde::Error::unknown_variant(__s)),
+                    };
+                    self.__out = serde::__private::Some(__value);
+                    serde::__private::Ok(())
+                }
+            },
+            quote! {
+                if self.__tag.is_some() {
+                    return serde::__private::Err(#mismatch);
+                }
+                self.__tag = serde::__private::Some(__tag_of(__k)?);
+                self.__content()
+            },
+        ),
+        Tagging::Internal(tag) => (
+            quote!(serde::Error::missing_field(#tag)),
+            quote!(),
+            quote! {
+                if __k == #tag {
+                    self.__reading_tag = true;
+                    return serde::__private::Ok(self);
+                }
+                if self.__tag.is_none() {
+                    return serde::__private::Ok(serde::__private::buffer_entry(&mut self.__buffered, __k));
+                }
+                self.__field(__k)
+            },
+        ),
+        Tagging::Adjacent(tag, content) => (
+            quote!(serde::Error::missing_field(#tag)),
+            quote!(),
+            quote! {
+                if __k == #tag {
+                    self.__reading_tag = true;
+                    return serde::__private::Ok(self);
+                }
+                if __k == #content {
+                    if self.__tag.is_none() {
+                        return serde::__private::Err(serde::Error::missing_field(#tag));
+                    }
+                    return self.__content();
+                }
+                #unknown
+            },
+        ),
+    };
+
+    let (buffered_def, buffered_init, replay_buffered, finish_buffered) = match internal {
+        true => (
+            quote!(__buffered: serde::__private::Buffered,),
+            quote!(__buffered: serde::__private::Vec::new(),),
+            quote!(serde::__private::replay_entries(serde::__private::take(&mut self.__buffered), self)?;),
+            quote! {
+                match self.__tag {
+                    #( #replay_arms )*
+                    _ => {}
+                }
+            },
+        ),
+        false => (quote!(), quote!(), quote!(), quote!()),
+    };
+
+    let content_visitor = match repr {
+        Tagging::Internal(_) => quote!(),
+        _ => quote! {
+            fn null(&mut self) -> serde::Result<()> {
+                match self.__tag {
+                    #( serde::__private::Some(__Tag::#unit_tags) => serde::__private::Ok(()), )*
+                    _ => serde::__private::Err(#mismatch),
+                }
+            }
+
+            fn seq(&mut self) -> serde::Result<serde::__private::Box<dyn serde::de::Seq<'de> + '_>> {
+                match self.__tag {
+                    #(
+                        serde::__private::Some(__Tag::#seq_tags) => {
+                            serde::__private::Ok(serde::__private::Box::new(__Elements { __state: self, __index: 0 }))
+                        }
+                    )*
+                    _ => serde::__private::Err(#mismatch),
+                }
+            }
+
+            fn map(&mut self) -> serde::Result<serde::__private::Box<dyn serde::de::Map<'de> + '_>> {
+                match self.__tag {
+                    #(
+                        serde::__private::Some(__Tag::#map_tags) => {
+                            serde::__private::Ok(serde::__private::Box::new(__Fields { __state: self }))
+                        }
+                    )*
+                    _ => serde::__private::Err(#mismatch),
+                }
+            }
+        },
+    };
+
+    let bounded_where_clause = bounded_where_clause(&input.generics, deserialized_types, &defaulted_types);
+    let wrapper_generics = bound::with_lifetime_bound(&input.generics, "'__a");
+    let (wrapper_impl_generics, wrapper_ty_generics, _) = wrapper_generics.split_for_impl();
+    let content_generics = bound::with_lifetime_bound(&wrapper_generics, "'__b");
+    let (content_impl_generics, content_ty_generics, _) = content_generics.split_for_impl();
+    let de_generics = bound::with_de_lifetime(&input.generics, &input.generics);
+    let (de_impl_generics, _, _) = de_generics.split_for_impl();
+    let de_wrapper_generics = bound::with_de_lifetime(&wrapper_generics, &input.generics);
+    let (de_wrapper_impl_generics, _, _) = de_wrapper_generics.split_for_impl();
+    let de_content_generics = bound::with_de_lifetime(&content_generics, &input.generics);
+    let (de_content_impl_generics, _, _) = de_content_generics.split_for_impl();
+    let de_lifetime = bound::de_lifetime(&input.generics);
 
     Ok(quote! {
-        #[allow(non_upper_case_globals)]
-        const #dummy_indent: () = {
+        #[allow(non_upper_case_globals, dead_code, unreachable_code, unreachable_patterns)]
+        const _: () = {
+            #[derive(Copy, Clone)]
+            enum __Tag {
+                #( #tags, )*
+            }
+
+            fn __tag_of(__s: &serde::__private::str) -> serde::Result<__Tag> {
+                match __s {
+                    #( #names => serde::__private::Ok(__Tag::#tags), )*
+                    _ => serde::__private::Err(serde::Error::unknown_variant(__s)),
+                }
+            }
+
             #[repr(C)]
-            struct __Visitor {
-                __out: serde::__private::Option<#ident>,
+            struct __Visitor #impl_generics #where_clause {
+                __out: serde::__private::Option<#ident #ty_generics>,
             }
 
-            impl serde::Deserialize for #ident {
-                fn begin(__out: &mut serde::__private::Option<Self>) -> &mut dyn serde::de::Visitor {
+            impl #de_impl_generics serde::Deserialize<'de> for #ident #ty_generics #bounded_where_clause {
+                fn begin(__out: &mut serde::__private::Option<Self>) -> &mut dyn serde::de::Visitor<'de> {
                     unsafe {
                         &mut *{
                             __out
                             as *mut serde::__private::Option<Self>
-                            as *mut __Visitor
+                            as *mut __Visitor #ty_generics
                         }
                     }
                 }
             }
 
-            impl serde::de::Visitor for __Visitor {
-                fn string(&mut self, s: &serde::__private::str) -> serde::Result<()> {
-                    let value = match s {
-                        #( #names => #ident::#var_idents, )*
-                        _ => return serde::__private::Err(serde::Error),
+            impl #de_impl_generics serde::de::Visitor<'de> for __Visitor #ty_generics #bounded_where_clause {
+                #visitor_string
+
+                fn map(&mut self) -> serde::Result<serde::__private::Box<dyn serde::de::Map<'de> + '_>> {
+                    serde::__private::Ok(serde::__private::Box::new(__State {
+                        __tag: serde::__private::None,
+                        __reading_tag: false,
+                        #buffered_init
+                        #( #slots: serde::Deserialize::default(), )*
+                        __out: &mut self.__out,
+                    }))
+                }
+            }
+
+            /// Fields of every variant, only the ones of the variant being read get filled.
+            struct __State #wrapper_impl_generics #where_clause {
+                __tag: serde::__private::Option<__Tag>,
+                __reading_tag: bool,
+                #buffered_def
+                #( #slot_defs )*
+                __out: &'__a mut serde::__private::Option<#ident #ty_generics>,
+            }
+
+            impl #wrapper_impl_generics __State #wrapper_ty_generics #where_clause {
+                fn __content<#de_lifetime>(&mut self) -> serde::Result<&mut dyn serde::de::Visitor<'de>>
+                #bounded_where_clause
+                {
+                    match self.__tag {
+                        #( #newtype_arms )*
+                        _ => serde::__private::Ok(self),
+                    }
+                }
+
+                fn __field<#de_lifetime>(&mut self, __k: &serde::__private::str) -> serde::Result<&mut dyn serde::de::Visitor<'de>>
+                #bounded_where_clause
+                {
+                    match self.__tag {
+                        #( #field_arms )*
+                        serde::__private::None => serde::__private::Err(#missing_tag),
+                        _ => #unknown,
+                    }
+                }
+            }
+
+            impl #de_wrapper_impl_generics serde::de::Map<'de> for __State #wrapper_ty_generics #bounded_where_clause {
+                fn key(&mut self, __k: &serde::__private::str) -> serde::Result<&mut dyn serde::de::Visitor<'de>> {
+                    #key_body
+                }
+
+                fn finish(&mut self) -> serde::Result<()> {
+                    #finish_buffered
+                    let __value = match self.__tag {
+                        #( #constructors )*
+                        serde::__private::None => return serde::__private::Err(#missing_tag),
                     };
-                    self.__out = serde::__private::Some(value);
+                    *self.__out = serde::__private::Some(__value);
+                    serde::__private::Ok(())
+                }
+            }
+
+            impl #de_wrapper_impl_generics serde::de::Visitor<'de> for __State #wrapper_ty_generics #bounded_where_clause {
+                fn string(&mut self, __s: &serde::__private::str) -> serde::Result<()> {
+                    if !self.__reading_tag {
+                        return serde::__private::Err(#mismatch);
+                    }
+                    self.__reading_tag = false;
+                    self.__tag = serde::__private::Some(__tag_of(__s)?);
+                    #replay_buffered
+                    serde::__private::Ok(())
+                }
+
+                #content_visitor
+            }
+
+            struct __Elements #content_impl_generics #where_clause {
+                __state: &'__b mut __State #wrapper_ty_generics,
+                __index: serde::__private::usize,
+            }
+
+            impl #de_content_impl_generics serde::de::Seq<'de> for __Elements #content_ty_generics #bounded_where_clause {
+                fn element(&mut self) -> serde::Result<&mut dyn serde::de::Visitor<'de>> {
+                    let __index = self.__index;
+                    self.__index += 1;
+                    match (self.__state.__tag, __index) {
+                        #( #element_arms )*
+                        _ => serde::__private::Err(#mismatch),
+                    }
+                }
+
+                fn finish(&mut self) -> serde::Result<()> {
+                    serde::__private::Ok(())
+                }
+            }
+
+            struct __Fields #content_impl_generics #where_clause {
+                __state: &'__b mut __State #wrapper_ty_generics,
+            }
+
+            impl #de_content_impl_generics serde::de::Map<'de> for __Fields #content_ty_generics #bounded_where_clause {
+                fn key(&mut self, __k: &serde::__private::str) -> serde::Result<&mut dyn serde::de::Visitor<'de>> {
+                    self.__state.__field(__k)
+                }
+
+                fn finish(&mut self) -> serde::Result<()> {
                     serde::__private::Ok(())
                 }
             }
         };
     })
 }
+
+struct NamedFields<'a> {
+    slots: Vec<Ident>,
+    tys: Vec<&'a Type>,
+    key_arms: Vec<TokenStream>,
+    values: Vec<TokenStream>,
+    /// Types of the fields filled with `Default::default()`.
+    defaulted: Vec<&'a Type>,
+}
+
+/// Slot holding each deserialized field until the value is built, the arm handing out the slot for the field key,
+/// and the `field: value` initializer. Skipped fields have no slot and are initialized with their default.
+fn named_fields(
+    fields: &FieldsNamed,
+    rename_all: Option<RenameRule>,
+    slot_of: impl Fn(&Ident) -> Ident,
+) -> Result<NamedFields<'_>> {
+    let mut named = NamedFields {
+        slots: Vec::new(),
+        tys: Vec::new(),
+        key_arms: Vec::new(),
+        values: Vec::new(),
+        defaulted: Vec::new(),
+    };
+    for field in &fields.named {
+        let attrs = attr::field(field, rename_all)?;
+        let fieldname = field.ident.as_ref().unwrap();
+        let fieldstr = &attrs.name;
+        let slot = slot_of(fieldname);
+        let default = match &attrs.default {
+            attr::Default::None => None,
+            attr::Default::Trait => {
+                named.defaulted.push(&field.ty);
+                Some(quote!(serde::__private::Default::default))
+            }
+            attr::Default::Path(path) => Some(quote!(#path)),
+        };
+        let value = match (attrs.skip, default) {
+            (true, Some(default)) => quote!(#default()),
+            (false, Some(default)) => quote!(self.#slot.take().unwrap_or_else(#default)),
+            _ => quote!(self.#slot.take().ok_or_else(|| serde::Error::missing_field(#fieldstr))?),
+        };
+        named.values.push(quote!(#fieldname: #value));
+        if attrs.skip {
+            continue;
+        }
+        named.key_arms.push(quote! {
+            #fieldstr => serde::__private::Ok(serde::Deserialize::begin(&mut self.#slot)),
+        });
+        named.slots.push(slot);
+        named.tys.push(&field.ty);
+    }
+    Ok(named)
+}
+
+/// Outcome of an unknown key in a struct or struct variant.
+fn unknown_field(deny_unknown_fields: bool) -> TokenStream {
+    match deny_unknown_fields {
+        true => quote!(serde::__private::Err(serde::Error::unknown_field(__k))),
+        false => quote!(serde::__private::Ok(<dyn serde::de::Visitor<'de>>::ignore())),
+    }
+}
+
+/// Bound the type parameters used by deserialized fields with `Deserialize`, and the types of the fields filled with
+/// `Default::default()` with `Default`.
+fn bounded_where_clause<'a>(
+    generics: &Generics,
+    deserialized: impl IntoIterator<Item = &'a Type>,
+    defaulted: &[&Type],
+) -> WhereClause {
+    let mut where_clause = bound::where_clause_with_bound(generics, deserialized, quote!(serde::Deserialize<'de>));
+    where_clause.predicates.extend(
+        defaulted
+            .iter()
+            .filter(|ty| bound::mentions_type_params(generics, ty))
+            .map::<WherePredicate, _>(|ty| parse_quote!(#ty: serde::__private::Default)),
+    );
+    where_clause
+}
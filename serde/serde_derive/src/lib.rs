@@ -2,6 +2,7 @@
 //! Info about procedural macro here: https://doc.rust-lang.org/reference/procedural-macros.html 
 mod attr;
 mod bound;
+mod case;
 mod de;
 mod ser;
 
@@ -1,8 +1,10 @@
-use crate::{attr, bound};
+use crate::attr::{self, Tagging};
+use crate::bound;
 use proc_macro2::{Span, TokenStream};
-use quote::quote;
+use quote::{format_ident, quote};
 use syn::{
-    parse_quote, Data, DataEnum, DataStruct, DeriveInput, Error, Fields, FieldsNamed, Ident, Result,
+    parse_quote, Data, DataEnum, DataStruct, DeriveInput, Error, Fields, FieldsNamed, Index, Result,
+    Variant,
 };
 
 pub fn derive(input: DeriveInput) -> Result<TokenStream> {
@@ -11,10 +13,11 @@ pub fn derive(input: DeriveInput) -> Result<TokenStream> {
             fields: Fields::Named(fields),
             ..
         }) => derive_struct(&input, fields),
+        Data::Struct(DataStruct { fields, .. }) => derive_tuple_struct(&input, fields),
         Data::Enum(enumeration) => derive_enum(&input, enumeration),
-        _ => Err(Error::new(
+        Data::Union(_) => Err(Error::new(
             Span::call_site(),
-            "only named fields structs and variants enums are supported"
+            "only structs and enums are supported"
         ))
     }
 }
@@ -22,27 +25,39 @@ pub fn derive(input: DeriveInput) -> Result<TokenStream> {
 fn derive_struct(input: &DeriveInput, fields: &FieldsNamed) -> Result<TokenStream> {
     let ident = &input.ident;
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
-    let dummy_indent = Ident::new(
-        &format!("_IMPL_SERIALIZE_FOR_{}", ident),
-        Span::call_site(),
-    );
-
-    let fieldname = &fields.named.iter().map(|f| &f.ident).collect::<Vec<_>>();
-    let fieldstr = fields
-        .named
-        .iter()
-        .map(attr::name_of_field)
-        .collect::<Result<Vec<_>>>()?;
+    let container = attr::container_of_struct(input)?;
+
+    let mut entries = Vec::new();
+    let mut serialized_types = Vec::new();
+    for field in &fields.named {
+        let attrs = attr::field(field, container.rename_all)?;
+        if attrs.skip {
+            continue;
+        }
+        serialized_types.push(&field.ty);
+        let fieldname = &field.ident;
+        let fieldstr = &attrs.name;
+        let entry = quote! {
+            return serde::__private::Some((
+                serde::__private::Cow::Borrowed(#fieldstr),
+                &self.data.#fieldname,
+            ))
+        };
+        entries.push(match attrs.skip_serializing_if {
+            Some(predicate) => quote!(if !#predicate(&self.data.#fieldname) { #entry }),
+            None => entry,
+        });
+    }
     let index = 0usize..;
 
     let wrapper_generics = bound::with_lifetime_bound(&input.generics, "'__a");
     let (wrapper_impl_generics, wrapper_ty_generics, _) = wrapper_generics.split_for_impl();
     let bound = parse_quote!(serde::Serialize);
-    let bounded_where_clause = bound::where_clause_with_bound(&input.generics, bound);
+    let bounded_where_clause = bound::where_clause_with_bound(&input.generics, serialized_types, bound);
 
     Ok(quote! {
         #[allow(non_upper_case_globals)]
-        const #dummy_indent: () = {
+        const _: () = {
             impl #impl_generics serde::Serialize for #ident #ty_generics #bounded_where_clause {
                 fn begin(&self) -> serde::ser::Fragment {
                     serde::ser::Fragment::Map(serde::__private::Box::new(__Map {
@@ -59,16 +74,15 @@ fn derive_struct(input: &DeriveInput, fields: &FieldsNamed) -> Result<TokenStrea
 
             impl #wrapper_impl_generics serde::ser::Map for __Map #wrapper_ty_generics #bounded_where_clause {
                 fn next(&mut self) -> serde::__private::Option<(serde::__private::Cow<serde::__private::str>, &dyn serde::Serialize)> {
-                    let __state = self.state;
-                    self.state = __state + 1;
-                    match __state {
-                        #(
-                            #index => serde::__private::Some((
-                                serde::__private::Cow::Borrowed(#fieldstr),
-                                &self.data.#fieldname,
-                            )),
-                        )*
-                        _ => serde::__private::None,
+                    loop {
+                        let __state = self.state;
+                        self.state = __state + 1;
+                        match __state {
+                            #(
+                                #index => #entries,
+                            )*
+                            _ => return serde::__private::None,
+                        }
                     }
                 }
             }
@@ -76,51 +90,141 @@ fn derive_struct(input: &DeriveInput, fields: &FieldsNamed) -> Result<TokenStrea
     })
 }
 
-fn derive_enum(input: &DeriveInput, enumeration: &DataEnum) -> Result<TokenStream> {
-    if input.generics.lt_token.is_some() || input.generics.where_clause.is_some() {
-        return Err(Error::new(
-            Span::call_site(),
-            "Enums with generics are not supported",
-        ));
-    }
+/// Newtype structs are serialized as their field, tuple structs as a sequence and unit structs as null.
+fn derive_tuple_struct(input: &DeriveInput, fields: &Fields) -> Result<TokenStream> {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, _) = input.generics.split_for_impl();
+    attr::container_of_struct(input)?;
+    attr::reject_unnamed_field_attrs(fields)?;
 
+    let body = match fields {
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => quote!(serde::Serialize::begin(&self.0)),
+        Fields::Unnamed(fields) => {
+            let index = (0..fields.unnamed.len()).map(Index::from);
+            quote! {
+                serde::__private::serialize_tuple(serde::__private::Vec::from([
+                    #( &self.#index as &dyn serde::Serialize, )*
+                ]))
+            }
+        }
+        _ => quote!(serde::ser::Fragment::Null),
+    };
+
+    let bound = parse_quote!(serde::Serialize);
+    let bounded_where_clause = bound::where_clause_with_bound(&input.generics, fields.iter().map(|f| &f.ty), bound);
+
+    Ok(quote! {
+        const _: () = {
+            impl #impl_generics serde::Serialize for #ident #ty_generics #bounded_where_clause {
+                fn begin(&self) -> serde::ser::Fragment {
+                    #body
+                }
+            }
+        };
+    })
+}
+
+fn derive_enum(input: &DeriveInput, enumeration: &DataEnum) -> Result<TokenStream> {
     let ident = &input.ident;
-    let dummy = Ident::new(
-        &format!("_IMPL_SERIALIZE_FOR_{}", ident),
-        Span::call_site(),
-    );
+    let (impl_generics, ty_generics, _) = input.generics.split_for_impl();
+
+    let container = attr::container(input)?;
+    let internal = matches!(container.tagging, Tagging::Internal(_));
+    let tagging = match &container.tagging {
+        Tagging::External => quote!(serde::__private::Tagging::External),
+        Tagging::Internal(tag) => quote!(serde::__private::Tagging::Internal(#tag)),
+        Tagging::Adjacent(tag, content) => {
+            quote!(serde::__private::Tagging::Adjacent(#tag, #content))
+        }
+    };
 
-    let var_idents = enumeration
+    let mut serialized_types = Vec::new();
+    let arms = enumeration
         .variants
         .iter()
-        .map(|variant| match variant.fields {
-            Fields::Unit => Ok(&variant.ident),
-            _ => Err(Error::new_spanned(
-                variant,
-                "Invalid variant: only simple enum variants without fields are supported",
-            )),
+        .map(|variant| {
+            let var_ident = &variant.ident;
+            let name = attr::name_of_variant(variant, container.rename_all)?;
+            attr::reject_unnamed_field_attrs(&variant.fields)?;
+            let binding = (0..variant.fields.len())
+                .map(|i| format_ident!("__f{}", i))
+                .collect::<Vec<_>>();
+            if let Fields::Unnamed(fields) = &variant.fields {
+                serialized_types.extend(fields.unnamed.iter().map(|f| &f.ty));
+            }
+            let (pattern, content) = match &variant.fields {
+                Fields::Unit => (quote!(), quote!(serde::__private::Content::Unit)),
+                Fields::Unnamed(fields) if internal && fields.unnamed.len() != 1 => {
+                    return Err(internal_variant_error(variant))
+                }
+                Fields::Unnamed(fields) if fields.unnamed.len() == 1 => (
+                    quote!((__f0)),
+                    quote!(serde::__private::Content::Newtype(__f0)),
+                ),
+                Fields::Unnamed(_) => (
+                    quote!(( #(#binding),* )),
+                    quote! {
+                        serde::__private::Content::Tuple(serde::__private::Vec::from([
+                            #( #binding as &dyn serde::Serialize, )*
+                        ]))
+                    },
+                ),
+                Fields::Named(fields) => {
+                    let mut bound = Vec::new();
+                    let mut pushes = Vec::new();
+                    for (field, binding) in fields.named.iter().zip(&binding) {
+                        let attrs = attr::field(field, None)?;
+                        if attrs.skip {
+                            continue;
+                        }
+                        serialized_types.push(&field.ty);
+                        let fieldname = &field.ident;
+                        let fieldstr = &attrs.name;
+                        bound.push(quote!(#fieldname: #binding));
+                        let push = quote!(__fields.push((#fieldstr, #binding as &dyn serde::Serialize)););
+                        pushes.push(match attrs.skip_serializing_if {
+                            Some(predicate) => quote!(if !#predicate(#binding) { #push }),
+                            None => push,
+                        });
+                    }
+                    (
+                        quote!({ #( #bound, )* .. }),
+                        quote!({
+                            let mut __fields = serde::__private::Vec::new();
+                            #( #pushes )*
+                            serde::__private::Content::Struct(__fields)
+                        }),
+                    )
+                }
+            };
+            Ok(quote! {
+                #ident::#var_ident #pattern => {
+                    serde::__private::serialize_variant(#tagging, #name, #content)
+                }
+            })
         })
         .collect::<Result<Vec<_>>>()?;
-    let names = enumeration
-        .variants
-        .iter()
-        .map(attr::name_of_variant)
-        .collect::<Result<Vec<_>>>()?;
+
+    let bound = parse_quote!(serde::Serialize);
+    let bounded_where_clause = bound::where_clause_with_bound(&input.generics, serialized_types, bound);
 
     Ok(quote! {
         #[allow(non_upper_case_globals)]
-        const #dummy: () = {
-            impl serde::Serialize for #ident {
+        const _: () = {
+            impl #impl_generics serde::Serialize for #ident #ty_generics #bounded_where_clause {
                 fn begin(&self) -> serde::ser::Fragment {
                     match self {
-                        #(
-                            #ident::#var_idents => {
-                                serde::ser::Fragment::Str(serde::__private::Cow::Borrowed(#names))
-                            }
-                        )*
+                        #( #arms )*
                     }
                 }
             }
         };
     })
 }
+
+pub fn internal_variant_error(variant: &Variant) -> Error {
+    Error::new_spanned(
+        variant,
+        "Invalid variant: internally tagged enums only support unit, newtype and struct variants",
+    )
+}
@@ -0,0 +1,276 @@
+use self::Event::*; // hoisting, being explicit
+use super::{F64, FALSE, I128, I16, I32, I64, I8, MAP, NULL, SEQ, STR, TRUE, U128, U16, U32, U64, U8};
+use crate::de::{Deserialize, Map, Seq, Visitor};
+use crate::json::DeserializerConfig;
+use crate::non_unique_box::NonuniqueBox;
+use crate::path::Path;
+use crate::{extend_lifetime, extend_lifetime_impl};
+use crate::{Error, ErrorKind, Limit, Result};
+
+use core::ptr::NonNull;
+use core::str;
+use std::vec::Vec;
+
+enum Event<'a> {
+    Null,
+    Bool(bool),
+    Str(&'a str),
+    Negative(i64),
+    Nonnegative(u64),
+    BigNegative(i128),
+    BigNonnegative(u128),
+    Float(f64),
+    SeqStart(usize),
+    MapStart(usize),
+}
+
+/// Deserialize bytes in the compact binary format into any deserializable type.
+/// Non recursive impls using a stack. Strings are borrowed from `bytes`.
+pub fn from_bytes<'de, T: Deserialize<'de>>(bytes: &'de [u8]) -> Result<T> {
+    from_bytes_with(bytes, &DeserializerConfig::new())
+}
+
+/// Same as [`from_bytes`] within the limits of `config`, see [`DeserializerConfig`]. Leniency options only apply to
+/// JSON and are ignored.
+pub fn from_bytes_with<'de, T: Deserialize<'de>>(bytes: &'de [u8], config: &DeserializerConfig) -> Result<T> {
+    let mut out = None;
+    internal_from_bytes(bytes, *config, T::begin(&mut out))?;
+    out.ok_or_else(|| Error::new(ErrorKind::TypeMismatch))
+}
+
+struct Deserializer<'de> {
+    input: &'de [u8],
+    pos: usize,
+    config: DeserializerConfig,
+    stack: Vec<(NonNull<dyn Visitor<'de>>, Layer<'de>)>,
+}
+
+/// A collection being read along with its number of elements left.
+enum Layer<'de> {
+    Seq(NonuniqueBox<dyn Seq<'de> + 'de>, usize),
+    Map(NonuniqueBox<dyn Map<'de> + 'de>, usize),
+}
+
+impl<'de> Drop for Deserializer<'de> {
+    fn drop(&mut self) {
+        while self.stack.pop().is_some() {}
+    }
+}
+
+fn internal_from_bytes<'de>(bytes: &'de [u8], config: DeserializerConfig, visitor: &mut dyn Visitor<'de>) -> Result<()> {
+    if let Some(max_input_len) = config.max_input_len.filter(|&max| bytes.len() > max) {
+        return Err(Error::new(ErrorKind::LimitExceeded(Limit::InputLength)).at_offset(max_input_len));
+    }
+    let mut deserializer = Deserializer {
+        input: bytes,
+        pos: 0,
+        config,
+        stack: Vec::new(),
+    };
+    let mut path = Path::new();
+
+    deserialize(&mut deserializer, &mut path, visitor)
+        .map_err(|error| error.with_path(path.to_string()).at_offset(deserializer.pos))
+}
+
+fn deserialize<'de>(deserializer: &mut Deserializer<'de>, path: &mut Path, visitor: &mut dyn Visitor<'de>) -> Result<()> {
+    let visitor = NonNull::from(visitor);
+    let mut visitor = unsafe { extend_lifetime!(visitor as NonNull<dyn Visitor<'de>>) };
+
+    'outer_loop: loop {
+        let visitor_mut = unsafe { &mut *visitor.as_ptr() };
+        let layer = match deserializer.get_event()? {
+            Null => {
+                visitor_mut.null()?;
+                None
+            }
+            Bool(b) => {
+                visitor_mut.boolean(b)?;
+                None
+            }
+            Negative(n) => {
+                visitor_mut.negative(n)?;
+                None
+            }
+            Nonnegative(n) => {
+                visitor_mut.nonnegative(n)?;
+                None
+            }
+            BigNegative(n) => {
+                visitor_mut.big_negative(n)?;
+                None
+            }
+            BigNonnegative(n) => {
+                visitor_mut.big_nonnegative(n)?;
+                None
+            }
+            Float(n) => {
+                visitor_mut.float(n)?;
+                None
+            }
+            Str(s) => {
+                visitor_mut.borrowed_string(s)?;
+                None
+            }
+            SeqStart(len) => {
+                deserializer.open_collection(len)?;
+                let seq = visitor_mut.seq()?;
+                path.push_seq();
+                Some(Layer::Seq(NonuniqueBox::from(seq), len))
+            }
+            MapStart(len) => {
+                deserializer.open_collection(len)?;
+                let map = visitor_mut.map()?;
+                path.push_map();
+                Some(Layer::Map(NonuniqueBox::from(map), len))
+            }
+        };
+
+        let mut layer = match layer {
+            Some(layer) => layer,
+            None => match deserializer.stack.pop() {
+                Some(frame) => {
+                    visitor = frame.0;
+                    frame.1
+                }
+                None => break 'outer_loop,
+            },
+        };
+
+        // Finish every collection whose elements have all been read.
+        loop {
+            match &mut layer {
+                Layer::Seq(seq, 0) => {
+                    path.pop();
+                    seq.finish()?
+                }
+                Layer::Map(map, 0) => {
+                    path.pop();
+                    map.finish()?
+                }
+                _ => break,
+            };
+            let frame = match deserializer.stack.pop() {
+                Some(frame) => frame,
+                None => break 'outer_loop,
+            };
+            visitor = frame.0;
+            layer = frame.1;
+        }
+
+        let outer = visitor;
+        match layer {
+            Layer::Seq(mut seq, remaining) => {
+                path.next_element();
+                let element = seq.element()?;
+                let next = NonNull::from(element);
+                visitor = unsafe { extend_lifetime!(next as NonNull<dyn Visitor<'de>>) };
+                deserializer.stack.push((outer, Layer::Seq(seq, remaining - 1)));
+            }
+            Layer::Map(mut map, remaining) => {
+                let key = deserializer.parse_str()?;
+                path.set_key(key);
+                let entry = map.key(key)?;
+                let next = NonNull::from(entry);
+                visitor = unsafe { extend_lifetime!(next as NonNull<dyn Visitor<'de>>) };
+                deserializer.stack.push((outer, Layer::Map(map, remaining - 1)));
+            }
+        }
+    }
+
+    // Raise error if there are bytes left after the value
+    match deserializer.pos == deserializer.input.len() {
+        true => Ok(()),
+        false => Err(Error::new(ErrorKind::TrailingBytes)),
+    }
+}
+
+impl<'de> Deserializer<'de> {
+    /// Check the limits before reading a sequence or map of `len` elements, its header was just read.
+    fn open_collection(&self, len: usize) -> Result<()> {
+        if self.config.max_depth.is_some_and(|max| self.stack.len() >= max) {
+            return Err(Error::new(ErrorKind::LimitExceeded(Limit::Depth)));
+        }
+        if self.config.max_collection_len.is_some_and(|max| len > max) {
+            return Err(Error::new(ErrorKind::LimitExceeded(Limit::CollectionLength)));
+        }
+        Ok(())
+    }
+
+    fn take<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let bytes = self.take_slice(N)?;
+        Ok(bytes.try_into().unwrap())
+    }
+
+    fn take_slice(&mut self, len: usize) -> Result<&'de [u8]> {
+        let end = match self.pos.checked_add(len) {
+            Some(end) if end <= self.input.len() => end,
+            _ => return Err(Error::new(ErrorKind::Eof).at_offset(self.input.len())),
+        };
+        let bytes = &self.input[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn parse_len(&mut self) -> Result<usize> {
+        Ok(u32::from_be_bytes(self.take()?) as usize)
+    }
+
+    fn parse_str(&mut self) -> Result<&'de str> {
+        let len = self.parse_len()?;
+        if self.config.max_string_len.is_some_and(|max| len > max) {
+            return Err(Error::new(ErrorKind::LimitExceeded(Limit::StringLength)));
+        }
+        let start = self.pos;
+        let bytes = self.take_slice(len)?;
+        str::from_utf8(bytes).map_err(|e| Error::new(ErrorKind::InvalidUtf8).at_offset(start + e.valid_up_to()))
+    }
+
+    fn get_event(&mut self) -> Result<Event<'de>> {
+        let [tag] = self.take()?;
+        let event = match tag {
+            NULL => Null,
+            FALSE => Bool(false),
+            TRUE => Bool(true),
+            U8 => Nonnegative(u8::from_be_bytes(self.take()?) as u64),
+            U16 => Nonnegative(u16::from_be_bytes(self.take()?) as u64),
+            U32 => Nonnegative(u32::from_be_bytes(self.take()?) as u64),
+            U64 => Nonnegative(u64::from_be_bytes(self.take()?)),
+            I8 => signed(i8::from_be_bytes(self.take()?) as i64),
+            I16 => signed(i16::from_be_bytes(self.take()?) as i64),
+            I32 => signed(i32::from_be_bytes(self.take()?) as i64),
+            I64 => signed(i64::from_be_bytes(self.take()?)),
+            U128 => unsigned128(u128::from_be_bytes(self.take()?)),
+            I128 => signed128(i128::from_be_bytes(self.take()?)),
+            F64 => Float(f64::from_be_bytes(self.take()?)),
+            STR => Str(self.parse_str()?),
+            SEQ => SeqStart(self.parse_len()?),
+            MAP => MapStart(self.parse_len()?),
+            _ => return Err(Error::new(ErrorKind::InvalidTag(tag)).at_offset(self.pos - 1)),
+        };
+        Ok(event)
+    }
+}
+
+fn signed<'a>(i: i64) -> Event<'a> {
+    match u64::try_from(i) {
+        Ok(u) => Nonnegative(u),
+        Err(_) => Negative(i),
+    }
+}
+
+/// 128-bit integers within the 64-bit range are visited as such, whatever their tag.
+fn unsigned128<'a>(u: u128) -> Event<'a> {
+    match u64::try_from(u) {
+        Ok(u) => Nonnegative(u),
+        Err(_) => BigNonnegative(u),
+    }
+}
+
+fn signed128<'a>(i: i128) -> Event<'a> {
+    match (u128::try_from(i), i64::try_from(i)) {
+        (Ok(u), _) => unsigned128(u),
+        (Err(_), Ok(i)) => Negative(i),
+        (Err(_), Err(_)) => BigNegative(i),
+    }
+}
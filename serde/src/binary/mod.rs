@@ -0,0 +1,97 @@
+//! Compact binary format.
+//!
+//! Every value starts with a one byte tag, followed by its payload:
+//! - integers are written big-endian in the smallest of 1, 2, 4, 8 or 16 bytes that fits them, floats as 8 bytes
+//!   big-endian,
+//! - strings are prefixed with their byte length as a `u32` big-endian,
+//! - sequences and maps are prefixed with their number of elements as a `u32` big-endian, map keys are written as strings without tag.
+//!
+//! The format is self-describing so any type implementing `Serialize`/`Deserialize` for JSON works as is.
+mod ser;
+pub use self::ser::to_bytes;
+
+mod de;
+pub use self::de::{from_bytes, from_bytes_with};
+
+const NULL: u8 = 0x00;
+const FALSE: u8 = 0x01;
+const TRUE: u8 = 0x02;
+const U8: u8 = 0x03;
+const U16: u8 = 0x04;
+const U32: u8 = 0x05;
+const U64: u8 = 0x06;
+const I8: u8 = 0x07;
+const I16: u8 = 0x08;
+const I32: u8 = 0x09;
+const I64: u8 = 0x0A;
+const F64: u8 = 0x0B;
+const STR: u8 = 0x0C;
+const SEQ: u8 = 0x0D;
+const MAP: u8 = 0x0E;
+const U128: u8 = 0x0F;
+const I128: u8 = 0x10;
+
+#[cfg(test)]
+mod tests {
+    use super::{from_bytes, from_bytes_with, to_bytes};
+    use crate::json::{self, DeserializerConfig, Value};
+    use crate::{ErrorKind, Limit};
+
+    #[test]
+    fn primitives_should_round_trip() {
+        let decoded: String = from_bytes(&to_bytes("LAS\n\"HAN\"")).unwrap();
+        assert_eq!(decoded, "LAS\n\"HAN\"");
+
+        let value = vec![Some(-1), None, Some(i64::MIN), Some(i64::MAX), Some(300)];
+        let decoded: Vec<Option<i64>> = from_bytes(&to_bytes(&value)).unwrap();
+        assert_eq!(decoded, value);
+
+        let decoded: Vec<f64> = from_bytes(&to_bytes(&vec![150.99, -0.5, 1e300])).unwrap();
+        assert_eq!(decoded, [150.99, -0.5, 1e300]);
+    }
+
+    #[test]
+    fn nested_values_should_round_trip_and_be_smaller_than_json() {
+        let text = r#"{"flights":[{"id":1,"source":"LAS","airfare":150.5,"full":false},{"id":2,"source":"HAN","airfare":null}],"count":2}"#;
+        let value: Value = json::from_str(text).unwrap();
+        let bytes = to_bytes(&value);
+        let decoded: Value = from_bytes(&bytes).unwrap();
+        assert_eq!(json::to_string(&decoded), json::to_string(&value));
+
+        let body: Vec<u8> = vec![123, 34, 102, 108, 105, 103, 104, 116, 95, 105, 100, 34, 58, 49, 125];
+        assert!(to_bytes(&body).len() < json::to_string(&body).len());
+    }
+
+    #[test]
+    fn malformed_input_should_be_rejected() {
+        let bytes = to_bytes(&vec!["LAS".to_string(), "HAN".to_string()]);
+        assert!(from_bytes::<Vec<String>>(&bytes[..bytes.len() - 1]).is_err(), "Truncated input");
+        assert!(from_bytes::<Vec<u32>>(&bytes).is_err(), "Type mismatch");
+
+        let mut trailing = bytes.clone();
+        trailing.push(super::NULL);
+        assert!(from_bytes::<Vec<String>>(&trailing).is_err(), "Trailing bytes");
+
+        let invalid_utf8 = [super::STR, 0, 0, 0, 2, 0xC3, 0x28];
+        assert!(from_bytes::<String>(&invalid_utf8).is_err(), "Invalid UTF-8");
+    }
+
+    #[test]
+    fn limits_should_apply_to_binary_input() {
+        // 64 nested sequences of one element each, closed by a null
+        let mut nested = [super::SEQ, 0, 0, 0, 1].repeat(64);
+        nested.push(super::NULL);
+        let limits = DeserializerConfig::new().max_depth(8);
+        let error = from_bytes_with::<Value>(&nested, &limits).unwrap_err();
+        assert_eq!((error.kind(), error.offset()), (&ErrorKind::LimitExceeded(Limit::Depth), Some(45)));
+        assert!(from_bytes::<Value>(&nested).is_ok());
+
+        let bytes = to_bytes(&vec!["LAS".to_string(), "HAN".to_string(), "SIN".to_string()]);
+        let error = from_bytes_with::<Value>(&bytes, &DeserializerConfig::new().max_collection_len(2)).unwrap_err();
+        assert_eq!(error.kind(), &ErrorKind::LimitExceeded(Limit::CollectionLength));
+        let error = from_bytes_with::<Value>(&bytes, &DeserializerConfig::new().max_string_len(2)).unwrap_err();
+        assert_eq!((error.kind(), error.path()), (&ErrorKind::LimitExceeded(Limit::StringLength), "[0]"));
+        let error = from_bytes_with::<Value>(&bytes, &DeserializerConfig::new().max_input_len(8)).unwrap_err();
+        assert_eq!(error.kind(), &ErrorKind::LimitExceeded(Limit::InputLength));
+    }
+}
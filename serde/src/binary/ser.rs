@@ -0,0 +1,176 @@
+use super::{F64, FALSE, I128, I16, I32, I64, I8, MAP, NULL, SEQ, STR, TRUE, U128, U16, U32, U64, U8};
+use crate::ser::{Fragment, Map, Seq, Serialize};
+use crate::{extend_lifetime, extend_lifetime_impl};
+
+use std::borrow::Cow;
+use std::boxed::Box;
+use std::vec::Vec;
+
+/// Serialize any serializable type into the compact binary format.
+pub fn to_bytes<T: ?Sized + Serialize>(value: &T) -> Vec<u8> {
+    to_bytes_impl(&value)
+}
+
+fn to_bytes_impl(value: &dyn Serialize) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut serializer = Serializer { stack: Vec::new() };
+    let mut fragment = value.begin();
+
+    loop {
+        match fragment {
+            Fragment::Null => out.push(NULL),
+            Fragment::Bool(b) => out.push(if b { TRUE } else { FALSE }),
+            Fragment::Str(s) => {
+                out.push(STR);
+                write_str(&s, &mut out);
+            }
+            Fragment::U64(u) => write_unsigned(u, &mut out),
+            Fragment::I64(i) => write_signed(i, &mut out),
+            Fragment::U128(u) => {
+                out.push(U128);
+                out.extend_from_slice(&u.to_be_bytes());
+            }
+            Fragment::I128(i) => {
+                out.push(I128);
+                out.extend_from_slice(&i.to_be_bytes());
+            }
+            Fragment::F32(f) => {
+                out.push(F64);
+                out.extend_from_slice(&(f as f64).to_be_bytes());
+            }
+            Fragment::F64(f) => {
+                out.push(F64);
+                out.extend_from_slice(&f.to_be_bytes());
+            }
+            Fragment::Seq(mut seq) => {
+                out.push(SEQ);
+                let len_pos = reserve_len(&mut out);
+                match unsafe { extend_lifetime!(seq.next() as Option<&dyn Serialize>) } {
+                    Some(first) => {
+                        serializer.stack.push(Frame { layer: Layer::Seq(seq), len_pos, len: 1 });
+                        fragment = first.begin();
+                        continue;
+                    }
+                    None => write_len(&mut out, len_pos, 0),
+                }
+            }
+            Fragment::Map(mut map) => {
+                out.push(MAP);
+                let len_pos = reserve_len(&mut out);
+                match unsafe { extend_lifetime!(map.next() as Option<(Cow<str>, &dyn Serialize)>) } {
+                    Some((key, first)) => {
+                        write_str(&key, &mut out);
+                        serializer.stack.push(Frame { layer: Layer::Map(map), len_pos, len: 1 });
+                        fragment = first.begin();
+                        continue;
+                    }
+                    None => write_len(&mut out, len_pos, 0),
+                }
+            }
+        }
+
+        loop {
+            match serializer.stack.last_mut() {
+                Some(frame) => {
+                    let next = match &mut frame.layer {
+                        Layer::Seq(seq) => unsafe { extend_lifetime!(seq.next() as Option<&dyn Serialize>) },
+                        Layer::Map(map) => match unsafe { extend_lifetime!(map.next() as Option<(Cow<str>, &dyn Serialize)>) } {
+                            Some((key, next)) => {
+                                write_str(&key, &mut out);
+                                Some(next)
+                            }
+                            None => None,
+                        },
+                    };
+                    match next {
+                        Some(next) => {
+                            frame.len += 1;
+                            fragment = next.begin();
+                            break;
+                        }
+                        None => write_len(&mut out, frame.len_pos, frame.len),
+                    }
+                }
+                None => return out,
+            }
+            serializer.stack.pop();
+        }
+    }
+}
+
+struct Serializer<'a> {
+    stack: Vec<Frame<'a>>,
+}
+
+/// A collection being written, `len_pos` is where its element count is patched in once it is exhausted.
+struct Frame<'a> {
+    layer: Layer<'a>,
+    len_pos: usize,
+    len: usize,
+}
+
+enum Layer<'a> {
+    Seq(Box<dyn Seq + 'a>),
+    Map(Box<dyn Map + 'a>),
+}
+
+impl<'a> Drop for Serializer<'a> {
+    fn drop(&mut self) {
+        // Drop layers in reverse order.
+        while !self.stack.is_empty() {
+            self.stack.pop();
+        }
+    }
+}
+
+fn write_unsigned(u: u64, out: &mut Vec<u8>) {
+    if let Ok(u) = u8::try_from(u) {
+        out.push(U8);
+        out.push(u);
+    } else if let Ok(u) = u16::try_from(u) {
+        out.push(U16);
+        out.extend_from_slice(&u.to_be_bytes());
+    } else if let Ok(u) = u32::try_from(u) {
+        out.push(U32);
+        out.extend_from_slice(&u.to_be_bytes());
+    } else {
+        out.push(U64);
+        out.extend_from_slice(&u.to_be_bytes());
+    }
+}
+
+fn write_signed(i: i64, out: &mut Vec<u8>) {
+    if i >= 0 {
+        // same as JSON, non negative numbers are read back as unsigned
+        write_unsigned(i as u64, out);
+    } else if let Ok(i) = i8::try_from(i) {
+        out.push(I8);
+        out.extend_from_slice(&i.to_be_bytes());
+    } else if let Ok(i) = i16::try_from(i) {
+        out.push(I16);
+        out.extend_from_slice(&i.to_be_bytes());
+    } else if let Ok(i) = i32::try_from(i) {
+        out.push(I32);
+        out.extend_from_slice(&i.to_be_bytes());
+    } else {
+        out.push(I64);
+        out.extend_from_slice(&i.to_be_bytes());
+    }
+}
+
+fn write_str(value: &str, out: &mut Vec<u8>) {
+    let len = u32::try_from(value.len()).expect("string longer than u32::MAX bytes");
+    out.extend_from_slice(&len.to_be_bytes());
+    out.extend_from_slice(value.as_bytes());
+}
+
+fn reserve_len(out: &mut Vec<u8>) -> usize {
+    let len_pos = out.len();
+    out.extend_from_slice(&[0; 4]);
+    len_pos
+}
+
+fn write_len(out: &mut [u8], len_pos: usize, len: usize) {
+    let len = u32::try_from(len).expect("collection longer than u32::MAX elements");
+    out[len_pos..len_pos + 4].copy_from_slice(&len.to_be_bytes());
+}
@@ -0,0 +1,36 @@
+//! Entries of internally tagged enums read before their variant is known, used by the `Deserialize` derive.
+
+use super::{Deserialize, Map, Visitor};
+use crate::json::{visit, Object, Value};
+use crate::path::Path;
+use crate::Result;
+
+use std::borrow::ToOwned;
+use std::string::String;
+use std::vec::Vec;
+
+/// Entries read before the tag, or every entry of a newtype variant, in input order.
+pub type Buffered = Vec<(String, Option<Value>)>;
+
+/// Visitor reading the value of `key` at the end of `buffered`.
+pub fn buffer_entry<'a, 'de>(buffered: &'a mut Buffered, key: &str) -> &'a mut dyn Visitor<'de> {
+    buffered.push((key.to_owned(), None));
+    let (_, value) = buffered.last_mut().unwrap();
+    Value::begin(value)
+}
+
+/// Read the buffered entries again as entries of `map`, once the variant is known.
+pub fn replay_entries<'de>(buffered: Buffered, map: &mut dyn Map<'de>) -> Result<()> {
+    for (key, value) in buffered {
+        if let Some(value) = value {
+            visit(value, &mut Path::new(), map.key(&key)?)?;
+        }
+    }
+    Ok(())
+}
+
+/// Read the buffered entries again as a map into `visitor`, for the content of a newtype variant.
+pub fn replay_map<'de>(buffered: Buffered, visitor: &mut dyn Visitor<'de>) -> Result<()> {
+    let object = buffered.into_iter().filter_map(|(key, value)| Some((key, value?))).collect::<Object>();
+    visit(Value::Object(object), &mut Path::new(), visitor)
+}
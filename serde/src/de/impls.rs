@@ -1,14 +1,29 @@
-use super::{Deserialize, Map, Seq, Visitor};
+use super::wrapper::{begin_wrapper, default_wrapper, Wrapper};
+use super::{Deserialize, Key, Map, Seq, Visitor};
 use crate::Place;
-use crate::{Error, Result};
+use crate::{extend_lifetime, extend_lifetime_impl};
+use crate::{Error, ErrorKind, Result};
 
+use core::hash::{BuildHasher, Hash};
+use core::iter;
+use core::marker::PhantomData;
 use core::mem;
+use core::num::{
+    IntErrorKind, NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128, NonZeroU16,
+    NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize, ParseIntError,
+};
 use core::str;
+use std::borrow::{Cow, ToOwned};
 use std::boxed::Box;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::vec::Vec;
 
-impl Deserialize for () {
-    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor {
-        impl Visitor for Place<()> {
+impl<'de> Deserialize<'de> for () {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+        impl<'de> Visitor<'de> for Place<()> {
             fn null(&mut self) -> Result<()> {
                 self.out = Some(());
                 Ok(())
@@ -18,9 +33,9 @@ impl Deserialize for () {
     }
 }
 
-impl Deserialize for bool {
-    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor {
-        impl Visitor for Place<bool> {
+impl<'de> Deserialize<'de> for bool {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+        impl<'de> Visitor<'de> for Place<bool> {
             fn boolean(&mut self, b: bool) -> Result<()> {
                 self.out = Some(b);
                 Ok(())
@@ -30,9 +45,9 @@ impl Deserialize for bool {
     }
 }
 
-impl Deserialize for String {
-    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor {
-        impl Visitor for Place<String> {
+impl<'de> Deserialize<'de> for String {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+        impl<'de> Visitor<'de> for Place<String> {
             fn string(&mut self, s: &str) -> Result<()> {
                 self.out = Some(s.to_owned());
                 Ok(())
@@ -42,32 +57,62 @@ impl Deserialize for String {
     }
 }
 
+impl Key for String {
+    fn from_key(k: &str) -> Result<Self> {
+        Ok(k.to_owned())
+    }
+}
+
+/// Integer keys are written in decimal.
+fn int_key_error(error: ParseIntError) -> Error {
+    match error.kind() {
+        IntErrorKind::PosOverflow | IntErrorKind::NegOverflow | IntErrorKind::Zero => {
+            Error::new(ErrorKind::NumberOverflow)
+        }
+        _ => Error::new(ErrorKind::TypeMismatch),
+    }
+}
+
 macro_rules! signed {
     ($ty:ident) => {
-        impl Deserialize for $ty {
-            fn begin(out: &mut Option<Self>) -> &mut dyn Visitor {
-                impl Visitor for Place<$ty> {
+        impl<'de> Deserialize<'de> for $ty {
+            fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+                impl<'de> Visitor<'de> for Place<$ty> {
                     fn negative(&mut self, n: i64) -> Result<()> {
                         if n >= $ty::min_value() as i64 {
                             self.out = Some(n as $ty);
                             Ok(())
                         } else {
-                            Err(Error)
+                            Err(Error::new(ErrorKind::NumberOverflow))
                         }
                     }
 
+                    fn big_negative(&mut self, _: i128) -> Result<()> {
+                        Err(Error::new(ErrorKind::NumberOverflow))
+                    }
+
                     fn nonnegative(&mut self, n: u64) -> Result<()> {
                         if n <= $ty::max_value() as u64 {
                             self.out = Some(n as $ty);
                             Ok(())
                         } else {
-                            Err(Error)
+                            Err(Error::new(ErrorKind::NumberOverflow))
                         }
                     }
+
+                    fn big_nonnegative(&mut self, _: u128) -> Result<()> {
+                        Err(Error::new(ErrorKind::NumberOverflow))
+                    }
                 }
                 Place::new(out)
             }
         }
+
+        impl Key for $ty {
+            fn from_key(k: &str) -> Result<Self> {
+                k.parse().map_err(int_key_error)
+            }
+        }
     };
 }
 signed!(i8);
@@ -78,21 +123,31 @@ signed!(isize);
 
 macro_rules! unsigned {
     ($ty:ident) => {
-        impl Deserialize for $ty {
-            fn begin(out: &mut Option<Self>) -> &mut dyn Visitor {
-                impl Visitor for Place<$ty> {
+        impl<'de> Deserialize<'de> for $ty {
+            fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+                impl<'de> Visitor<'de> for Place<$ty> {
                     fn nonnegative(&mut self, n: u64) -> Result<()> {
                         if n <= $ty::max_value() as u64 {
                             self.out = Some(n as $ty);
                             Ok(())
                         } else {
-                            Err(Error)
+                            Err(Error::new(ErrorKind::NumberOverflow))
                         }
                     }
+
+                    fn big_nonnegative(&mut self, _: u128) -> Result<()> {
+                        Err(Error::new(ErrorKind::NumberOverflow))
+                    }
                 }
                 Place::new(out)
             }
         }
+
+        impl Key for $ty {
+            fn from_key(k: &str) -> Result<Self> {
+                k.parse().map_err(int_key_error)
+            }
+        }
     };
 }
 unsigned!(u8);
@@ -101,125 +156,755 @@ unsigned!(u32);
 unsigned!(u64);
 unsigned!(usize);
 
-macro_rules! float {
-    ($ty:ident) => {
-        impl Deserialize for $ty {
-            fn begin(out: &mut Option<Self>) -> &mut dyn Visitor {
-                impl Visitor for Place<$ty> {
-                    fn negative(&mut self, n: i64) -> Result<()> {
-                        self.out = Some(n as $ty);
-                        Ok(())
-                    }
+/// Only integers outside of the 64-bit range are visited as 128-bit.
+impl<'de> Deserialize<'de> for i128 {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+        Place::new(out)
+    }
+}
 
-                    fn nonnegative(&mut self, n: u64) -> Result<()> {
-                        self.out = Some(n as $ty);
-                        Ok(())
-                    }
+impl<'de> Visitor<'de> for Place<i128> {
+    fn negative(&mut self, n: i64) -> Result<()> {
+        self.out = Some(n as i128);
+        Ok(())
+    }
 
-                    fn float(&mut self, n: f64) -> Result<()> {
-                        self.out = Some(n as $ty);
-                        Ok(())
-                    }
-                }
+    fn nonnegative(&mut self, n: u64) -> Result<()> {
+        self.out = Some(n as i128);
+        Ok(())
+    }
+
+    fn big_negative(&mut self, n: i128) -> Result<()> {
+        self.out = Some(n);
+        Ok(())
+    }
+
+    fn big_nonnegative(&mut self, n: u128) -> Result<()> {
+        self.out = Some(i128::try_from(n).map_err(|_| Error::new(ErrorKind::NumberOverflow))?);
+        Ok(())
+    }
+}
+
+impl Key for i128 {
+    fn from_key(k: &str) -> Result<Self> {
+        k.parse().map_err(int_key_error)
+    }
+}
+
+impl<'de> Deserialize<'de> for u128 {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+        Place::new(out)
+    }
+}
+
+impl<'de> Visitor<'de> for Place<u128> {
+    fn nonnegative(&mut self, n: u64) -> Result<()> {
+        self.out = Some(n as u128);
+        Ok(())
+    }
+
+    fn big_nonnegative(&mut self, n: u128) -> Result<()> {
+        self.out = Some(n);
+        Ok(())
+    }
+}
+
+impl Key for u128 {
+    fn from_key(k: &str) -> Result<Self> {
+        k.parse().map_err(int_key_error)
+    }
+}
+
+/// Zero is out of range of the `NonZero` integers.
+fn nonzero<'de, T: Deserialize<'de>, N>(
+    new: fn(T) -> Option<N>,
+    visit: impl FnOnce(&mut dyn Visitor<'de>) -> Result<()>,
+) -> Result<N> {
+    let mut int = None;
+    visit(T::begin(&mut int))?;
+    int.and_then(new).ok_or_else(|| Error::new(ErrorKind::NumberOverflow))
+}
+
+macro_rules! nonzero {
+    ($ty:ident $int:ident) => {
+        impl<'de> Deserialize<'de> for $ty {
+            fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
                 Place::new(out)
             }
         }
-    };
-}
-float!(f32);
-float!(f64);
 
-impl<T: Deserialize> Deserialize for Option<T> {
-    #[inline]
-    fn default() -> Option<Self> {
-        Some(None)
-    }
-    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor {
-        impl<T: Deserialize> Visitor for Place<Option<T>> {
-            fn null(&mut self) -> Result<()> {
-                self.out = Some(None);
+        impl<'de> Visitor<'de> for Place<$ty> {
+            fn negative(&mut self, n: i64) -> Result<()> {
+                self.out = Some(nonzero::<$int, _>($ty::new, |int| int.negative(n))?);
                 Ok(())
             }
 
-            fn boolean(&mut self, b: bool) -> Result<()> {
-                self.out = Some(None);
-                Deserialize::begin(self.out.as_mut().unwrap()).boolean(b)
+            fn nonnegative(&mut self, n: u64) -> Result<()> {
+                self.out = Some(nonzero::<$int, _>($ty::new, |int| int.nonnegative(n))?);
+                Ok(())
             }
 
-            fn string(&mut self, s: &str) -> Result<()> {
-                self.out = Some(None);
-                Deserialize::begin(self.out.as_mut().unwrap()).string(s)
+            fn big_negative(&mut self, n: i128) -> Result<()> {
+                self.out = Some(nonzero::<$int, _>($ty::new, |int| int.big_negative(n))?);
+                Ok(())
+            }
+
+            fn big_nonnegative(&mut self, n: u128) -> Result<()> {
+                self.out = Some(nonzero::<$int, _>($ty::new, |int| int.big_nonnegative(n))?);
+                Ok(())
+            }
+        }
+
+        impl Key for $ty {
+            fn from_key(k: &str) -> Result<Self> {
+                k.parse().map_err(int_key_error)
+            }
+        }
+    };
+}
+nonzero!(NonZeroU8 u8);
+nonzero!(NonZeroU16 u16);
+nonzero!(NonZeroU32 u32);
+nonzero!(NonZeroU64 u64);
+nonzero!(NonZeroU128 u128);
+nonzero!(NonZeroUsize usize);
+nonzero!(NonZeroI8 i8);
+nonzero!(NonZeroI16 i16);
+nonzero!(NonZeroI32 i32);
+nonzero!(NonZeroI64 i64);
+nonzero!(NonZeroI128 i128);
+nonzero!(NonZeroIsize isize);
+
+macro_rules! float {
+    ($ty:ident) => {
+        impl<'de> Deserialize<'de> for $ty {
+            fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+                Place::new(out)
             }
+        }
 
+        impl<'de> Visitor<'de> for Place<$ty> {
             fn negative(&mut self, n: i64) -> Result<()> {
-                self.out = Some(None);
-                Deserialize::begin(self.out.as_mut().unwrap()).negative(n)
+                self.out = Some(n as $ty);
+                Ok(())
             }
 
             fn nonnegative(&mut self, n: u64) -> Result<()> {
-                self.out = Some(None);
-                Deserialize::begin(self.out.as_mut().unwrap()).nonnegative(n)
+                self.out = Some(n as $ty);
+                Ok(())
             }
 
-            fn float(&mut self, n: f64) -> Result<()> {
-                self.out = Some(None);
-                Deserialize::begin(self.out.as_mut().unwrap()).float(n)
+            fn big_negative(&mut self, n: i128) -> Result<()> {
+                self.out = Some(n as $ty);
+                Ok(())
             }
 
-            fn seq(&mut self) -> Result<Box<dyn Seq + '_>> {
-                self.out = Some(None);
-                Deserialize::begin(self.out.as_mut().unwrap()).seq()
+            fn big_nonnegative(&mut self, n: u128) -> Result<()> {
+                let f = n as $ty;
+                if f.is_infinite() {
+                    return Err(Error::new(ErrorKind::NumberOverflow));
+                }
+                self.out = Some(f);
+                Ok(())
             }
 
-            fn map(&mut self) -> Result<Box<dyn Map + '_>> {
-                self.out = Some(None);
-                Deserialize::begin(self.out.as_mut().unwrap()).map()
+            fn float(&mut self, n: f64) -> Result<()> {
+                let f = n as $ty;
+                if f.is_infinite() && n.is_finite() {
+                    return Err(Error::new(ErrorKind::NumberOverflow));
+                }
+                self.out = Some(f);
+                Ok(())
             }
         }
+    };
+}
+float!(f32);
+float!(f64);
 
+/// A string of exactly one character.
+impl<'de> Deserialize<'de> for char {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
         Place::new(out)
     }
 }
 
-impl<T: Deserialize> Deserialize for Vec<T> {
-    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor {
-        impl<T: Deserialize> Visitor for Place<Vec<T>> {
-            fn seq(&mut self) -> Result<Box<dyn Seq + '_>> {
-                Ok(Box::new(VecBuilder {
-                    out: &mut self.out,
-                    vec: Vec::new(),
-                    element: None,
-                }))
-            }
-        }
+impl<'de> Visitor<'de> for Place<char> {
+    fn string(&mut self, s: &str) -> Result<()> {
+        self.out = Some(char::from_key(s)?);
+        Ok(())
+    }
+}
 
-        struct VecBuilder<'a, T: 'a> {
-            out: &'a mut Option<Vec<T>>,
-            vec: Vec<T>,
-            element: Option<T>,
+impl Key for char {
+    fn from_key(k: &str) -> Result<Self> {
+        let mut chars = k.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(c),
+            _ => Err(Error::new(ErrorKind::TypeMismatch)),
         }
+    }
+}
 
-        impl<'a, T> VecBuilder<'a, T> {
-            fn shift(&mut self) {
-                if let Some(e) = self.element.take() {
-                    self.vec.push(e);
-                }
+impl<'de> Deserialize<'de> for PathBuf {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+        Place::new(out)
+    }
+}
+
+impl<'de> Visitor<'de> for Place<PathBuf> {
+    fn string(&mut self, s: &str) -> Result<()> {
+        self.out = Some(PathBuf::from(s));
+        Ok(())
+    }
+}
+
+/// Addresses are parsed from their usual text form, a string that does not parse is a type mismatch.
+macro_rules! address {
+    ($ty:ident) => {
+        impl<'de> Deserialize<'de> for $ty {
+            fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+                Place::new(out)
             }
         }
 
-        impl<'a, T: Deserialize> Seq for VecBuilder<'a, T> {
-            fn element(&mut self) -> Result<&mut dyn Visitor> {
-                self.shift();
-                Ok(Deserialize::begin(&mut self.element))
+        impl<'de> Visitor<'de> for Place<$ty> {
+            fn string(&mut self, s: &str) -> Result<()> {
+                self.out = Some($ty::from_key(s)?);
+                Ok(())
             }
+        }
 
-            fn finish(&mut self) -> Result<()> {
-                self.shift();
-                *self.out = Some(mem::take(&mut self.vec));
-                Ok(())
+        impl Key for $ty {
+            fn from_key(k: &str) -> Result<Self> {
+                k.parse().map_err(|_| Error::new(ErrorKind::TypeMismatch))
             }
         }
+    };
+}
+address!(IpAddr);
+address!(Ipv4Addr);
+address!(Ipv6Addr);
+address!(SocketAddr);
+address!(SocketAddrV4);
+address!(SocketAddrV6);
+
+/// Reads the seconds and nanoseconds entries of a `Duration` or `SystemTime`, other keys are ignored.
+struct TimeBuilder<'a, T> {
+    out: &'a mut Option<T>,
+    names: [&'static str; 2],
+    secs: Option<i128>,
+    nanos: Option<u32>,
+    build: fn(i128, u32) -> Option<T>,
+}
+
+impl<'a, 'de, T> Map<'de> for TimeBuilder<'a, T> {
+    fn key(&mut self, k: &str) -> Result<&mut dyn Visitor<'de>> {
+        if k == self.names[0] {
+            Ok(Deserialize::begin(&mut self.secs))
+        } else if k == self.names[1] {
+            Ok(Deserialize::begin(&mut self.nanos))
+        } else {
+            Ok(<dyn Visitor<'de>>::ignore())
+        }
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        let secs = self.secs.ok_or_else(|| Error::new(ErrorKind::MissingField(self.names[0])))?;
+        let nanos = self.nanos.ok_or_else(|| Error::new(ErrorKind::MissingField(self.names[1])))?;
+        if nanos >= 1_000_000_000 {
+            return Err(Error::new(ErrorKind::NumberOverflow));
+        }
+        *self.out = Some((self.build)(secs, nanos).ok_or_else(|| Error::new(ErrorKind::NumberOverflow))?);
+        Ok(())
+    }
+}
+
+/// Read from `{"secs": u64, "nanos": u32}`.
+impl<'de> Deserialize<'de> for Duration {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+        Place::new(out)
+    }
+}
+
+impl<'de> Visitor<'de> for Place<Duration> {
+    fn map(&mut self) -> Result<Box<dyn Map<'de> + '_>> {
+        Ok(Box::new(TimeBuilder {
+            out: &mut self.out,
+            names: ["secs", "nanos"],
+            secs: None,
+            nanos: None,
+            build: |secs, nanos| Some(Duration::new(u64::try_from(secs).ok()?, nanos)),
+        }))
+    }
+}
 
+/// Read from `{"secs_since_epoch": i64, "nanos_since_epoch": u32}`, negative seconds are before the epoch.
+impl<'de> Deserialize<'de> for SystemTime {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
         Place::new(out)
     }
 }
+
+impl<'de> Visitor<'de> for Place<SystemTime> {
+    fn map(&mut self) -> Result<Box<dyn Map<'de> + '_>> {
+        Ok(Box::new(TimeBuilder {
+            out: &mut self.out,
+            names: ["secs_since_epoch", "nanos_since_epoch"],
+            secs: None,
+            nanos: None,
+            build: |secs, nanos| {
+                let whole = Duration::from_secs(u64::try_from(secs.unsigned_abs()).ok()?);
+                let time = if secs < 0 {
+                    UNIX_EPOCH.checked_sub(whole)?
+                } else {
+                    UNIX_EPOCH.checked_add(whole)?
+                };
+                time.checked_add(Duration::from_nanos(nanos as u64))
+            },
+        }))
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Option<T> {
+    #[inline]
+    fn default() -> Option<Self> {
+        Some(None)
+    }
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+        Place::new(out)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Visitor<'de> for Place<Option<T>> {
+    fn null(&mut self) -> Result<()> {
+        self.out = Some(None);
+        Ok(())
+    }
+
+    fn boolean(&mut self, b: bool) -> Result<()> {
+        self.out = Some(None);
+        Deserialize::begin(self.out.as_mut().unwrap()).boolean(b)
+    }
+
+    fn string(&mut self, s: &str) -> Result<()> {
+        self.out = Some(None);
+        Deserialize::begin(self.out.as_mut().unwrap()).string(s)
+    }
+
+    fn borrowed_string(&mut self, s: &'de str) -> Result<()> {
+        self.out = Some(None);
+        Deserialize::begin(self.out.as_mut().unwrap()).borrowed_string(s)
+    }
+
+    fn negative(&mut self, n: i64) -> Result<()> {
+        self.out = Some(None);
+        Deserialize::begin(self.out.as_mut().unwrap()).negative(n)
+    }
+
+    fn nonnegative(&mut self, n: u64) -> Result<()> {
+        self.out = Some(None);
+        Deserialize::begin(self.out.as_mut().unwrap()).nonnegative(n)
+    }
+
+    fn big_negative(&mut self, n: i128) -> Result<()> {
+        self.out = Some(None);
+        Deserialize::begin(self.out.as_mut().unwrap()).big_negative(n)
+    }
+
+    fn big_nonnegative(&mut self, n: u128) -> Result<()> {
+        self.out = Some(None);
+        Deserialize::begin(self.out.as_mut().unwrap()).big_nonnegative(n)
+    }
+
+    fn float(&mut self, n: f64) -> Result<()> {
+        self.out = Some(None);
+        Deserialize::begin(self.out.as_mut().unwrap()).float(n)
+    }
+
+    fn seq(&mut self) -> Result<Box<dyn Seq<'de> + '_>> {
+        self.out = Some(None);
+        let seq = Deserialize::begin(self.out.as_mut().unwrap()).seq()?;
+        // The input outlives every visitor reading it, which the borrow of `self` cannot tell.
+        Ok(unsafe { extend_lifetime!(seq as Box<dyn Seq<'de> + '_>) })
+    }
+
+    fn map(&mut self) -> Result<Box<dyn Map<'de> + '_>> {
+        self.out = Some(None);
+        let map = Deserialize::begin(self.out.as_mut().unwrap()).map()?;
+        // The input outlives every visitor reading it, which the borrow of `self` cannot tell.
+        Ok(unsafe { extend_lifetime!(map as Box<dyn Map<'de> + '_>) })
+    }
+}
+
+impl<'de, T: ?Sized> Deserialize<'de> for PhantomData<T> {
+    #[inline]
+    fn default() -> Option<Self> {
+        Some(PhantomData)
+    }
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+        Place::new(out)
+    }
+}
+
+impl<'de, T: ?Sized> Visitor<'de> for Place<PhantomData<T>> {
+    fn null(&mut self) -> Result<()> {
+        self.out = Some(PhantomData);
+        Ok(())
+    }
+}
+
+/// Borrows strings that appear as is in the input, the ones containing escapes are unescaped into a `Cow::Owned`.
+impl<'de: 'a, 'a> Deserialize<'de> for Cow<'a, str> {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+        Place::new(out)
+    }
+}
+
+impl<'de: 'a, 'a> Visitor<'de> for Place<Cow<'a, str>> {
+    fn string(&mut self, s: &str) -> Result<()> {
+        self.out = Some(Cow::Owned(s.to_owned()));
+        Ok(())
+    }
+
+    fn borrowed_string(&mut self, s: &'de str) -> Result<()> {
+        self.out = Some(Cow::Borrowed(s));
+        Ok(())
+    }
+}
+
+/// Only strings that appear as is in the input can be borrowed, strings containing escapes fail with
+/// [`ErrorKind::EscapedBorrow`]. Use `Cow<str>` to accept both.
+impl<'de: 'a, 'a> Deserialize<'de> for &'a str {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+        Place::new(out)
+    }
+}
+
+impl<'de: 'a, 'a> Visitor<'de> for Place<&'a str> {
+    fn string(&mut self, _: &str) -> Result<()> {
+        Err(Error::new(ErrorKind::EscapedBorrow))
+    }
+
+    fn borrowed_string(&mut self, s: &'de str) -> Result<()> {
+        self.out = Some(s);
+        Ok(())
+    }
+}
+
+/// Borrows the UTF-8 bytes of a string, with the same restriction on escapes as `&str`. Byte slices serialize as
+/// sequences of numbers, which read back into `Vec<u8>` only.
+impl<'de: 'a, 'a> Deserialize<'de> for &'a [u8] {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+        Place::new(out)
+    }
+}
+
+impl<'de: 'a, 'a> Visitor<'de> for Place<&'a [u8]> {
+    fn string(&mut self, _: &str) -> Result<()> {
+        Err(Error::new(ErrorKind::EscapedBorrow))
+    }
+
+    fn borrowed_string(&mut self, s: &'de str) -> Result<()> {
+        self.out = Some(s.as_bytes());
+        Ok(())
+    }
+}
+
+/// Always deserialized into `Cow::Owned`.
+impl<'a, 'de, T: Clone> Wrapper<'de> for Cow<'a, [T]>
+where
+    Vec<T>: Deserialize<'de>,
+{
+    type Inner = Vec<T>;
+
+    fn wrap(inner: Vec<T>) -> Self {
+        Cow::Owned(inner)
+    }
+}
+
+impl<'a, 'de, T: Clone> Deserialize<'de> for Cow<'a, [T]>
+where
+    Vec<T>: Deserialize<'de>,
+{
+    #[inline]
+    fn default() -> Option<Self> {
+        default_wrapper()
+    }
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+        begin_wrapper(out)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Wrapper<'de> for Box<T> {
+    type Inner = T;
+
+    fn wrap(inner: T) -> Self {
+        Box::new(inner)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Box<T> {
+    #[inline]
+    fn default() -> Option<Self> {
+        default_wrapper()
+    }
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+        begin_wrapper(out)
+    }
+}
+
+/// Collects the elements of a sequence into any collection that can be extended with them.
+struct SeqBuilder<'a, C, T> {
+    out: &'a mut Option<C>,
+    collection: C,
+    element: Option<T>,
+}
+
+impl<'a, C: Default + Extend<T>, T> SeqBuilder<'a, C, T> {
+    fn new(out: &'a mut Option<C>) -> Box<Self> {
+        Box::new(SeqBuilder {
+            out,
+            collection: C::default(),
+            element: None,
+        })
+    }
+
+    fn shift(&mut self) {
+        if let Some(e) = self.element.take() {
+            self.collection.extend(iter::once(e));
+        }
+    }
+}
+
+impl<'a, 'de, C: Default + Extend<T>, T: Deserialize<'de>> Seq<'de> for SeqBuilder<'a, C, T> {
+    fn element(&mut self) -> Result<&mut dyn Visitor<'de>> {
+        self.shift();
+        Ok(Deserialize::begin(&mut self.element))
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.shift();
+        *self.out = Some(mem::take(&mut self.collection));
+        Ok(())
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Vec<T> {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+        Place::new(out)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Visitor<'de> for Place<Vec<T>> {
+    fn seq(&mut self) -> Result<Box<dyn Seq<'de> + '_>> {
+        Ok(SeqBuilder::<_, T>::new(&mut self.out))
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for VecDeque<T> {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+        Place::new(out)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Visitor<'de> for Place<VecDeque<T>> {
+    fn seq(&mut self) -> Result<Box<dyn Seq<'de> + '_>> {
+        Ok(SeqBuilder::<_, T>::new(&mut self.out))
+    }
+}
+
+impl<'de, T: Deserialize<'de> + Ord> Deserialize<'de> for BTreeSet<T> {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+        Place::new(out)
+    }
+}
+
+impl<'de, T: Deserialize<'de> + Ord> Visitor<'de> for Place<BTreeSet<T>> {
+    fn seq(&mut self) -> Result<Box<dyn Seq<'de> + '_>> {
+        Ok(SeqBuilder::<_, T>::new(&mut self.out))
+    }
+}
+
+impl<'de, T: Deserialize<'de> + Eq + Hash, S: BuildHasher + Default> Deserialize<'de> for HashSet<T, S> {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+        Place::new(out)
+    }
+}
+
+impl<'de, T: Deserialize<'de> + Eq + Hash, S: BuildHasher + Default> Visitor<'de> for Place<HashSet<T, S>> {
+    fn seq(&mut self) -> Result<Box<dyn Seq<'de> + '_>> {
+        Ok(SeqBuilder::<_, T>::new(&mut self.out))
+    }
+}
+
+impl<'de, T: Deserialize<'de>, const N: usize> Deserialize<'de> for [T; N] {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+        Place::new(out)
+    }
+}
+
+impl<'de, T: Deserialize<'de>, const N: usize> Visitor<'de> for Place<[T; N]> {
+    fn seq(&mut self) -> Result<Box<dyn Seq<'de> + '_>> {
+        Ok(Box::new(ArrayBuilder {
+            out: &mut self.out,
+            elements: Vec::with_capacity(N),
+            element: None,
+        }))
+    }
+}
+
+/// Fails as soon as the input has more than `N` elements, and on finish if it has fewer.
+struct ArrayBuilder<'a, T, const N: usize> {
+    out: &'a mut Option<[T; N]>,
+    elements: Vec<T>,
+    element: Option<T>,
+}
+
+impl<'a, T, const N: usize> ArrayBuilder<'a, T, N> {
+    fn shift(&mut self) {
+        if let Some(e) = self.element.take() {
+            self.elements.push(e);
+        }
+    }
+}
+
+impl<'a, 'de, T: Deserialize<'de>, const N: usize> Seq<'de> for ArrayBuilder<'a, T, N> {
+    fn element(&mut self) -> Result<&mut dyn Visitor<'de>> {
+        self.shift();
+        if self.elements.len() == N {
+            return Err(Error::new(ErrorKind::TypeMismatch));
+        }
+        Ok(Deserialize::begin(&mut self.element))
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.shift();
+        let elements = mem::take(&mut self.elements);
+        *self.out = Some(elements.try_into().map_err(|_| Error::new(ErrorKind::TypeMismatch))?);
+        Ok(())
+    }
+}
+
+/// Collects the entries of a map into any map that can be extended with them.
+struct MapBuilder<'a, M, K, V> {
+    out: &'a mut Option<M>,
+    map: M,
+    key: Option<K>,
+    value: Option<V>,
+}
+
+impl<'a, M: Default + Extend<(K, V)>, K: Key, V> MapBuilder<'a, M, K, V> {
+    fn new(out: &'a mut Option<M>) -> Box<Self> {
+        Box::new(MapBuilder {
+            out,
+            map: M::default(),
+            key: None,
+            value: None,
+        })
+    }
+
+    fn shift(&mut self) {
+        if let (Some(k), Some(v)) = (self.key.take(), self.value.take()) {
+            self.map.extend(iter::once((k, v)));
+        }
+    }
+}
+
+impl<'a, 'de, M: Default + Extend<(K, V)>, K: Key, V: Deserialize<'de>> Map<'de> for MapBuilder<'a, M, K, V> {
+    fn key(&mut self, k: &str) -> Result<&mut dyn Visitor<'de>> {
+        self.shift();
+        self.key = Some(K::from_key(k)?);
+        Ok(Deserialize::begin(&mut self.value))
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.shift();
+        *self.out = Some(mem::take(&mut self.map));
+        Ok(())
+    }
+}
+
+impl<'de, K: Key + Ord, V: Deserialize<'de>> Deserialize<'de> for BTreeMap<K, V> {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+        Place::new(out)
+    }
+}
+
+impl<'de, K: Key + Ord, V: Deserialize<'de>> Visitor<'de> for Place<BTreeMap<K, V>> {
+    fn map(&mut self) -> Result<Box<dyn Map<'de> + '_>> {
+        Ok(MapBuilder::<_, K, V>::new(&mut self.out))
+    }
+}
+
+impl<'de, K: Key + Eq + Hash, V: Deserialize<'de>, S: BuildHasher + Default> Deserialize<'de> for HashMap<K, V, S> {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+        Place::new(out)
+    }
+}
+
+impl<'de, K: Key + Eq + Hash, V: Deserialize<'de>, S: BuildHasher + Default> Visitor<'de> for Place<HashMap<K, V, S>> {
+    fn map(&mut self) -> Result<Box<dyn Map<'de> + '_>> {
+        Ok(MapBuilder::<_, K, V>::new(&mut self.out))
+    }
+}
+
+/// Fills one slot per element, the input must have exactly as many elements as the tuple.
+struct TupleBuilder<'a, T, E> {
+    out: &'a mut Option<T>,
+    elements: E,
+    index: usize,
+}
+
+macro_rules! tuple {
+    ($($n:tt $t:ident $v:ident)+) => {
+        impl<'de, $($t: Deserialize<'de>),+> Deserialize<'de> for ($($t,)+) {
+            fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+                Place::new(out)
+            }
+        }
+
+        impl<'de, $($t: Deserialize<'de>),+> Visitor<'de> for Place<($($t,)+)> {
+            fn seq(&mut self) -> Result<Box<dyn Seq<'de> + '_>> {
+                Ok(Box::new(TupleBuilder {
+                    out: &mut self.out,
+                    elements: ($(None::<$t>,)+),
+                    index: 0,
+                }))
+            }
+        }
+
+        impl<'a, 'de, $($t: Deserialize<'de>),+> Seq<'de> for TupleBuilder<'a, ($($t,)+), ($(Option<$t>,)+)> {
+            fn element(&mut self) -> Result<&mut dyn Visitor<'de>> {
+                let index = self.index;
+                self.index += 1;
+                match index {
+                    $($n => Ok(Deserialize::begin(&mut self.elements.$n)),)+
+                    _ => Err(Error::new(ErrorKind::TypeMismatch)),
+                }
+            }
+
+            fn finish(&mut self) -> Result<()> {
+                match ($(self.elements.$n.take(),)+) {
+                    ($(Some($v),)+) => {
+                        *self.out = Some(($($v,)+));
+                        Ok(())
+                    }
+                    _ => Err(Error::new(ErrorKind::TypeMismatch)),
+                }
+            }
+        }
+    };
+}
+tuple!(0 A a);
+tuple!(0 A a 1 B b);
+tuple!(0 A a 1 B b 2 C c);
+tuple!(0 A a 1 B b 2 C c 3 D d);
+tuple!(0 A a 1 B b 2 C c 3 D d 4 E e);
+tuple!(0 A a 1 B b 2 C c 3 D d 4 E e 5 F f);
+tuple!(0 A a 1 B b 2 C c 3 D d 4 E e 5 F f 6 G g);
+tuple!(0 A a 1 B b 2 C c 3 D d 4 E e 5 F f 6 G g 7 H h);
+tuple!(0 A a 1 B b 2 C c 3 D d 4 E e 5 F f 6 G g 7 H h 8 I i);
+tuple!(0 A a 1 B b 2 C c 3 D d 4 E e 5 F f 6 G g 7 H h 8 I i 9 J j);
+tuple!(0 A a 1 B b 2 C c 3 D d 4 E e 5 F f 6 G g 7 H h 8 I i 9 J j 10 K k);
+tuple!(0 A a 1 B b 2 C c 3 D d 4 E e 5 F f 6 G g 7 H h 8 I i 9 J j 10 K k 11 L l);
@@ -1,10 +1,14 @@
 pub mod impls;
+pub(crate) mod buffer;
+pub(crate) mod wrapper;
 
-use super::{Error, Result};
+use super::{Error, ErrorKind, Result};
 use std::boxed::Box;
 
-pub trait Deserialize: Sized {
-    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor;
+/// Trait for data structures that can be deserialized. `'de` is the lifetime of the input, which types such as `&'de str`
+/// borrow from instead of copying.
+pub trait Deserialize<'de>: Sized {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de>;
 
     /// For Option<T>
     #[inline]
@@ -13,49 +17,76 @@ pub trait Deserialize: Sized {
     }
 }
 
+/// Types that can be deserialized from an input of any lifetime, i.e. that never borrow from it. Required to read
+/// from a buffer that is dropped before the value, e.g. by `json::from_reader`.
+pub trait DeserializeOwned: for<'de> Deserialize<'de> {}
+
+impl<T: for<'de> Deserialize<'de>> DeserializeOwned for T {}
+
 /// Trait that write data into an output place
-pub trait Visitor {
+pub trait Visitor<'de> {
     fn null(&mut self) -> Result<()> {
-        Err(Error)
+        Err(Error::new(ErrorKind::TypeMismatch))
     }
 
     fn boolean(&mut self, _: bool) -> Result<()> {
-        Err(Error)
+        Err(Error::new(ErrorKind::TypeMismatch))
     }
 
     fn string(&mut self, _: &str) -> Result<()> {
-        Err(Error)
+        Err(Error::new(ErrorKind::TypeMismatch))
+    }
+
+    /// A string that appears as is in the input, so it can be borrowed for `'de`. Strings that had to be unescaped are
+    /// given to [`string`](Visitor::string) instead, which this forwards to by default.
+    fn borrowed_string(&mut self, s: &'de str) -> Result<()> {
+        self.string(s)
     }
 
     fn negative(&mut self, _: i64) -> Result<()> {
-        Err(Error)
+        Err(Error::new(ErrorKind::TypeMismatch))
     }
 
     fn nonnegative(&mut self, _: u64) -> Result<()> {
-        Err(Error)
+        Err(Error::new(ErrorKind::TypeMismatch))
+    }
+
+    /// An integer below `i64::MIN`, smaller ones are given to [`negative`](Visitor::negative).
+    fn big_negative(&mut self, _: i128) -> Result<()> {
+        Err(Error::new(ErrorKind::TypeMismatch))
+    }
+
+    /// An integer above `u64::MAX`, smaller ones are given to [`nonnegative`](Visitor::nonnegative).
+    fn big_nonnegative(&mut self, _: u128) -> Result<()> {
+        Err(Error::new(ErrorKind::TypeMismatch))
     }
 
     fn float(&mut self, _: f64) -> Result<()> {
-        Err(Error)
+        Err(Error::new(ErrorKind::TypeMismatch))
     }
 
-    fn seq(&mut self) -> Result<Box<dyn Seq + '_>> {
-        Err(Error)
+    fn seq(&mut self) -> Result<Box<dyn Seq<'de> + '_>> {
+        Err(Error::new(ErrorKind::TypeMismatch))
     }
 
-    fn map(&mut self) -> Result<Box<dyn Map + '_>> {
-        Err(Error)
+    fn map(&mut self) -> Result<Box<dyn Map<'de> + '_>> {
+        Err(Error::new(ErrorKind::TypeMismatch))
     }
 }
 
 /// Trait that can hand out places to write sequence elements.
-pub trait Seq {
-    fn element(&mut self) -> Result<&mut dyn Visitor>;
+pub trait Seq<'de> {
+    fn element(&mut self) -> Result<&mut dyn Visitor<'de>>;
     fn finish(&mut self) -> Result<()>;
 }
 
 /// Trait that can hand out places to write values of a map.
-pub trait Map {
-    fn key(&mut self, k: &str) -> Result<&mut dyn Visitor>;
+pub trait Map<'de> {
+    fn key(&mut self, k: &str) -> Result<&mut dyn Visitor<'de>>;
     fn finish(&mut self) -> Result<()>;
 }
+
+/// Trait for types that can be read back from the key of a map entry.
+pub trait Key: Sized {
+    fn from_key(k: &str) -> Result<Self>;
+}
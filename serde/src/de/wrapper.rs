@@ -0,0 +1,135 @@
+//! Deserialization of types holding a single value, used by the `Deserialize` derive for newtype structs.
+
+use super::{Deserialize, Map, Seq, Visitor};
+use crate::non_unique_box::NonuniqueBox;
+use crate::{extend_lifetime, extend_lifetime_impl};
+use crate::Result;
+
+use std::boxed::Box;
+
+/// A type deserialized as its `Inner` value.
+pub trait Wrapper<'de>: Sized {
+    type Inner: Deserialize<'de>;
+
+    fn wrap(inner: Self::Inner) -> Self;
+}
+
+/// Visitor deserializing the inner value of `W`, for [`Deserialize::begin`].
+pub fn begin_wrapper<'de, W: Wrapper<'de>>(out: &mut Option<W>) -> &mut dyn Visitor<'de> {
+    unsafe { &mut *(out as *mut Option<W> as *mut WrapperPlace<W>) }
+}
+
+/// Same as [`Deserialize::default`] for the inner value.
+pub fn default_wrapper<'de, W: Wrapper<'de>>() -> Option<W> {
+    W::Inner::default().map(W::wrap)
+}
+
+struct WrapperPlace<W> {
+    out: Option<W>,
+}
+
+impl<W> WrapperPlace<W> {
+    fn visit<'de>(&mut self, visit: impl FnOnce(&mut dyn Visitor<'de>) -> Result<()>) -> Result<()>
+    where
+        W: Wrapper<'de>,
+    {
+        let mut inner = None;
+        visit(W::Inner::begin(&mut inner))?;
+        self.out = inner.map(W::wrap);
+        Ok(())
+    }
+}
+
+impl<'de, W: Wrapper<'de>> Visitor<'de> for WrapperPlace<W> {
+    fn null(&mut self) -> Result<()> {
+        self.visit(|inner| inner.null())
+    }
+
+    fn boolean(&mut self, b: bool) -> Result<()> {
+        self.visit(|inner| inner.boolean(b))
+    }
+
+    fn string(&mut self, s: &str) -> Result<()> {
+        self.visit(|inner| inner.string(s))
+    }
+
+    fn borrowed_string(&mut self, s: &'de str) -> Result<()> {
+        self.visit(|inner| inner.borrowed_string(s))
+    }
+
+    fn negative(&mut self, n: i64) -> Result<()> {
+        self.visit(|inner| inner.negative(n))
+    }
+
+    fn nonnegative(&mut self, n: u64) -> Result<()> {
+        self.visit(|inner| inner.nonnegative(n))
+    }
+
+    fn big_negative(&mut self, n: i128) -> Result<()> {
+        self.visit(|inner| inner.big_negative(n))
+    }
+
+    fn big_nonnegative(&mut self, n: u128) -> Result<()> {
+        self.visit(|inner| inner.big_nonnegative(n))
+    }
+
+    fn float(&mut self, n: f64) -> Result<()> {
+        self.visit(|inner| inner.float(n))
+    }
+
+    fn seq(&mut self) -> Result<Box<dyn Seq<'de> + '_>> {
+        let mut slot = NonuniqueBox::from(Box::new(None));
+        let seq = W::Inner::begin(&mut slot).seq()?;
+        let wrapped: Box<dyn Seq<'de> + '_> = Box::new(Wrapped {
+            inner: unsafe { extend_lifetime!(seq as Box<dyn Seq<'de> + '_>) },
+            slot,
+            out: &mut self.out,
+        });
+        // The input outlives every visitor reading it, which the borrow of `self` cannot tell.
+        Ok(unsafe { extend_lifetime!(wrapped as Box<dyn Seq<'de> + '_>) })
+    }
+
+    fn map(&mut self) -> Result<Box<dyn Map<'de> + '_>> {
+        let mut slot = NonuniqueBox::from(Box::new(None));
+        let map = W::Inner::begin(&mut slot).map()?;
+        let wrapped: Box<dyn Map<'de> + '_> = Box::new(Wrapped {
+            inner: unsafe { extend_lifetime!(map as Box<dyn Map<'de> + '_>) },
+            slot,
+            out: &mut self.out,
+        });
+        // The input outlives every visitor reading it, which the borrow of `self` cannot tell.
+        Ok(unsafe { extend_lifetime!(wrapped as Box<dyn Map<'de> + '_>) })
+    }
+}
+
+/// Collection of the inner value, wrapped into the output once it is finished.
+struct Wrapped<'a, W, I, C: ?Sized> {
+    // Declared first so that it is dropped before the slot it writes into.
+    inner: Box<C>,
+    slot: NonuniqueBox<Option<I>>,
+    out: &'a mut Option<W>,
+}
+
+impl<'a, 'de, W: Wrapper<'de>> Seq<'de> for Wrapped<'a, W, W::Inner, dyn Seq<'de> + 'a> {
+    fn element(&mut self) -> Result<&mut dyn Visitor<'de>> {
+        self.inner.element()
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.inner.finish()?;
+        *self.out = self.slot.take().map(W::wrap);
+        Ok(())
+    }
+}
+
+impl<'a, 'de, W: Wrapper<'de>> Map<'de> for Wrapped<'a, W, W::Inner, dyn Map<'de> + 'a> {
+    fn key(&mut self, k: &str) -> Result<&mut dyn Visitor<'de>> {
+        self.inner.key(k)
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.inner.finish()?;
+        *self.out = self.slot.take().map(W::wrap);
+        Ok(())
+    }
+}
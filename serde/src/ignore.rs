@@ -1,8 +1,8 @@
 use super::de::{Map, Seq, Visitor};
 use super::Result;
 
-impl dyn Visitor {
-    pub fn ignore() -> &'static mut dyn Visitor {
+impl<'de> dyn Visitor<'de> {
+    pub fn ignore<'a>() -> &'a mut dyn Visitor<'de> {
         static mut IGNORE: Ignore = Ignore;
         unsafe { &mut IGNORE }
     }
@@ -10,7 +10,7 @@ impl dyn Visitor {
 
 pub(crate) struct Ignore;
 
-impl Visitor for Ignore {
+impl<'de> Visitor<'de> for Ignore {
     fn null(&mut self) -> Result<()> {
         Ok(())
     }
@@ -31,22 +31,30 @@ impl Visitor for Ignore {
         Ok(())
     }
 
+    fn big_negative(&mut self, _n: i128) -> Result<()> {
+        Ok(())
+    }
+
+    fn big_nonnegative(&mut self, _n: u128) -> Result<()> {
+        Ok(())
+    }
+
     fn float(&mut self, _n: f64) -> Result<()> {
         Ok(())
     }
 
-    fn seq(&mut self) -> Result<Box<dyn Seq + '_>> {
+    fn seq(&mut self) -> Result<Box<dyn Seq<'de> + '_>> {
         Ok(Box::new(Ignore))
     }
 
-    fn map(&mut self) -> Result<Box<dyn Map + '_>> {
+    fn map(&mut self) -> Result<Box<dyn Map<'de> + '_>> {
         Ok(Box::new(Ignore))
     }
 }
 
-impl Seq for Ignore {
-    fn element(&mut self) -> Result<&mut dyn Visitor> {
-        Ok(<dyn Visitor>::ignore())
+impl<'de> Seq<'de> for Ignore {
+    fn element(&mut self) -> Result<&mut dyn Visitor<'de>> {
+        Ok(<dyn Visitor<'de>>::ignore())
     }
 
     fn finish(&mut self) -> Result<()> {
@@ -54,9 +62,9 @@ impl Seq for Ignore {
     }
 }
 
-impl Map for Ignore {
-    fn key(&mut self, _k: &str) -> Result<&mut dyn Visitor> {
-        Ok(<dyn Visitor>::ignore())
+impl<'de> Map<'de> for Ignore {
+    fn key(&mut self, _k: &str) -> Result<&mut dyn Visitor<'de>> {
+        Ok(<dyn Visitor<'de>>::ignore())
     }
 
     fn finish(&mut self) -> Result<()> {
@@ -1,59 +1,193 @@
 use self::Event::*; // hoisting, being explicit
-use crate::de::{Deserialize, Map, Seq, Visitor};
+use crate::de::{Deserialize, DeserializeOwned, Map, Seq, Visitor};
 use crate::non_unique_box::NonuniqueBox;
+use crate::path::Path;
 use crate::{extend_lifetime, extend_lifetime_impl};
-use crate::{Error, Result};
+use crate::{Error, ErrorKind, Limit, Result};
 
+use core::ops::Deref;
 use core::ptr::NonNull;
 use core::str;
+use std::io::Read;
 use std::vec::Vec;
 
-enum Event<'a> {
+enum Event<'de, 's> {
     Null,
     Bool(bool),
-    Str(&'a str),
+    Str(Reference<'de, 's>),
     Negative(i64),
     Nonnegative(u64),
+    BigNegative(i128),
+    BigNonnegative(u128),
     Float(f64),
     SeqStart,
     MapStart,
 }
 
+/// A string either borrowed from the input, or unescaped into the scratch space of the deserializer.
+enum Reference<'de, 's> {
+    Borrowed(&'de str),
+    Copied(&'s str),
+}
+
+impl<'de, 's> Deref for Reference<'de, 's> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        match *self {
+            Reference::Borrowed(s) => s,
+            Reference::Copied(s) => s,
+        }
+    }
+}
+
 /// Deserialize a JSON string into any deserializable type.
-/// Non recursive impls using a stack.
-pub fn from_str<T: Deserialize>(j: &str) -> Result<T> {
-    let mut out = None;
-    internal_from_str(j, T::begin(&mut out))?;
-    out.ok_or(Error)
+/// Non recursive impls using a stack. Strings without escapes can be borrowed from `j`, see [`Deserialize`].
+pub fn from_str<'de, T: Deserialize<'de>>(j: &'de str) -> Result<T> {
+    DeserializerConfig::new().from_str(j)
+}
+
+/// Deserialize JSON bytes into any deserializable type. Strings are validated while parsing, input that is not UTF-8
+/// fails with [`ErrorKind::InvalidUtf8`].
+pub fn from_slice<'de, T: Deserialize<'de>>(j: &'de [u8]) -> Result<T> {
+    DeserializerConfig::new().from_slice(j)
+}
+
+/// Deserialize JSON read from `reader` until its end, e.g. a file. The whole input is buffered before parsing, wrap
+/// unbuffered readers in a `BufReader` to avoid small reads.
+pub fn from_reader<T: DeserializeOwned, R: Read>(reader: R) -> Result<T> {
+    DeserializerConfig::new().from_reader(reader)
+}
+
+/// Limits on the inputs accepted by the JSON parser, none by default, and leniency options for peers writing numbers
+/// differently.
+///
+/// Inputs from untrusted sources should be bounded: without limits a deeply nested or very long document makes the
+/// parser allocate as much memory as the input asks for. Going over a limit fails with [`ErrorKind::LimitExceeded`].
+///
+/// ```
+/// use serde::json::DeserializerConfig;
+/// use serde::{ErrorKind, Limit};
+///
+/// let strict = DeserializerConfig::new().max_depth(2);
+/// assert_eq!(strict.from_str::<Vec<Vec<u32>>>("[[1]]").unwrap(), vec![vec![1]]);
+/// let error = strict.from_str::<Vec<Vec<Vec<u32>>>>("[[[1]]]").unwrap_err();
+/// assert_eq!(error.kind(), &ErrorKind::LimitExceeded(Limit::Depth));
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DeserializerConfig {
+    pub(crate) max_depth: Option<usize>,
+    pub(crate) max_string_len: Option<usize>,
+    pub(crate) max_collection_len: Option<usize>,
+    pub(crate) max_input_len: Option<usize>,
+    integral_floats: bool,
+}
+
+impl DeserializerConfig {
+    pub fn new() -> Self {
+        DeserializerConfig::default()
+    }
+
+    /// Maximum number of sequences and maps open at once, `0` only accepts scalars.
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Maximum length in bytes of every string and key, after unescaping.
+    pub fn max_string_len(mut self, max_string_len: usize) -> Self {
+        self.max_string_len = Some(max_string_len);
+        self
+    }
+
+    /// Maximum number of elements of every sequence and entries of every map.
+    pub fn max_collection_len(mut self, max_collection_len: usize) -> Self {
+        self.max_collection_len = Some(max_collection_len);
+        self
+    }
+
+    /// Maximum length in bytes of the whole input. Readers are not read further than this limit.
+    pub fn max_input_len(mut self, max_input_len: usize) -> Self {
+        self.max_input_len = Some(max_input_len);
+        self
+    }
+
+    /// Read floats holding a whole number, such as `5.0` or `-2e3`, as integers so that they deserialize into integer
+    /// types. Only magnitudes up to 2^53 are converted, above that a float no longer tells which integer was meant.
+    pub fn integral_floats(mut self, integral_floats: bool) -> Self {
+        self.integral_floats = integral_floats;
+        self
+    }
+
+    /// Same as [`from_str`] within these limits.
+    pub fn from_str<'de, T: Deserialize<'de>>(&self, j: &'de str) -> Result<T> {
+        self.from_slice(j.as_bytes())
+    }
+
+    /// Same as [`from_slice`] within these limits.
+    pub fn from_slice<'de, T: Deserialize<'de>>(&self, j: &'de [u8]) -> Result<T> {
+        let mut out = None;
+        internal_from_slice(j, *self, T::begin(&mut out))?;
+        out.ok_or_else(|| Error::new(ErrorKind::TypeMismatch))
+    }
+
+    /// Same as [`from_reader`] within these limits.
+    pub fn from_reader<T: DeserializeOwned, R: Read>(&self, reader: R) -> Result<T> {
+        let mut j = Vec::new();
+        match self.max_input_len {
+            // one byte more than allowed is enough to tell that the input is too long
+            Some(max_input_len) => reader.take(max_input_len as u64 + 1).read_to_end(&mut j)?,
+            None => { reader }.read_to_end(&mut j)?,
+        };
+        self.from_slice(&j)
+    }
 }
 
-struct Deserializer<'a, 'b> {
-    input: &'a [u8],
+struct Deserializer<'de> {
+    input: &'de [u8],
     pos: usize,
     buffer: Vec<u8>,
-    stack: Vec<(NonNull<dyn Visitor>, Layer<'b>)>,
+    stack: Vec<(NonNull<dyn Visitor<'de>>, Layer<'de>)>,
+    config: DeserializerConfig,
+    /// Number of elements or entries read so far in each collection currently open.
+    lengths: Vec<usize>,
 }
 
-enum Layer<'a> {
-    Seq(NonuniqueBox<dyn Seq + 'a>),
-    Map(NonuniqueBox<dyn Map + 'a>),
+enum Layer<'de> {
+    Seq(NonuniqueBox<dyn Seq<'de> + 'de>),
+    Map(NonuniqueBox<dyn Map<'de> + 'de>),
 }
 
-impl<'a, 'b> Drop for Deserializer<'a, 'b> {
+impl<'de> Drop for Deserializer<'de> {
     fn drop(&mut self) {
         while self.stack.pop().is_some() {}
     }
 }
 
-fn internal_from_str(s: &str, visitor: &mut dyn Visitor) -> Result<()> {
-    let visitor = NonNull::from(visitor);
-    let mut visitor = unsafe { extend_lifetime!(visitor as NonNull<dyn Visitor>) };
+fn internal_from_slice<'de>(input: &'de [u8], config: DeserializerConfig, visitor: &mut dyn Visitor<'de>) -> Result<()> {
+    if let Some(max_input_len) = config.max_input_len.filter(|&max| input.len() > max) {
+        return Err(Error::new(ErrorKind::LimitExceeded(Limit::InputLength)).at_position(input, max_input_len));
+    }
     let mut deserializer = Deserializer {
-        input: s.as_bytes(),
+        input,
         pos: 0,
         buffer: Vec::new(),
         stack: Vec::new(),
+        config,
+        lengths: Vec::new(),
     };
+    let mut path = Path::new();
+
+    deserialize(&mut deserializer, &mut path, visitor).map_err(|error| {
+        error
+            .with_path(path.to_string())
+            .at_position(deserializer.input, deserializer.pos)
+    })
+}
+
+fn deserialize<'de>(deserializer: &mut Deserializer<'de>, path: &mut Path, visitor: &mut dyn Visitor<'de>) -> Result<()> {
+    let visitor = NonNull::from(visitor);
+    let mut visitor = unsafe { extend_lifetime!(visitor as NonNull<dyn Visitor<'de>>) };
 
     'outer_loop: loop {
         let visitor_mut = unsafe { &mut *visitor.as_ptr() };
@@ -74,20 +208,36 @@ fn internal_from_str(s: &str, visitor: &mut dyn Visitor) -> Result<()> {
                 visitor_mut.nonnegative(n)?;
                 None
             }
+            BigNegative(n) => {
+                visitor_mut.big_negative(n)?;
+                None
+            }
+            BigNonnegative(n) => {
+                visitor_mut.big_nonnegative(n)?;
+                None
+            }
             Float(n) => {
                 visitor_mut.float(n)?;
                 None
             }
-            Str(s) => {
+            Str(Reference::Borrowed(s)) => {
+                visitor_mut.borrowed_string(s)?;
+                None
+            }
+            Str(Reference::Copied(s)) => {
                 visitor_mut.string(s)?;
                 None
             }
             SeqStart => {
+                deserializer.open_collection()?;
                 let seq = visitor_mut.seq()?;
+                path.push_seq();
                 Some(Layer::Seq(NonuniqueBox::from(seq)))
             }
             MapStart => {
+                deserializer.open_collection()?;
                 let map = visitor_mut.map()?;
+                path.push_map();
                 Some(Layer::Map(NonuniqueBox::from(map)))
             }
         };
@@ -109,17 +259,26 @@ fn internal_from_str(s: &str, visitor: &mut dyn Visitor) -> Result<()> {
         };
 
         loop {
-            match deserializer.skip_whitespace().unwrap_or(b'\0') {
-                b',' if accept_comma => {
+            match deserializer.skip_whitespace() {
+                Some(b',') if accept_comma => {
                     deserializer.move_next_pos();
                     break;
                 }
-                close_parenthesis @ (b']' | b'}') => {
-                    deserializer.move_next_pos();
-                    match &mut layer {
-                        Layer::Seq(seq) if close_parenthesis == b']' => seq.finish()?,
-                        Layer::Map(map) if close_parenthesis == b'}' => map.finish()?,
-                        _ => return Err(Error),
+                Some(close_parenthesis @ (b']' | b'}')) => {
+                    match (&mut layer, close_parenthesis) {
+                        (Layer::Seq(seq), b']') => {
+                            deserializer.move_next_pos();
+                            deserializer.lengths.pop();
+                            path.pop();
+                            seq.finish()?
+                        }
+                        (Layer::Map(map), b'}') => {
+                            deserializer.move_next_pos();
+                            deserializer.lengths.pop();
+                            path.pop();
+                            map.finish()?
+                        }
+                        _ => return Err(deserializer.unexpected_at(deserializer.pos)),
                     };
                     let frame = match deserializer.stack.pop() {
                         Some(frame) => frame,
@@ -131,7 +290,7 @@ fn internal_from_str(s: &str, visitor: &mut dyn Visitor) -> Result<()> {
                 }
                 _ => {
                     if accept_comma {
-                        return Err(Error);
+                        return Err(deserializer.unexpected_at(deserializer.pos));
                     }
                     break;
                 }
@@ -141,23 +300,27 @@ fn internal_from_str(s: &str, visitor: &mut dyn Visitor) -> Result<()> {
         let outer = visitor;
         match layer {
             Layer::Seq(mut seq) => {
+                path.next_element();
+                deserializer.count_entry()?;
                 let element = seq.element()?;
                 let next = NonNull::from(element);
-                visitor = unsafe { extend_lifetime!(next as NonNull<dyn Visitor>) };
+                visitor = unsafe { extend_lifetime!(next as NonNull<dyn Visitor<'de>>) };
                 deserializer.stack.push((outer, Layer::Seq(seq)));
             }
             Layer::Map(mut map) => {
                 match deserializer.skip_whitespace() {
                     Some(b'"') => deserializer.move_next_pos(),
-                    _ => return Err(Error),
+                    _ => return Err(deserializer.unexpected_at(deserializer.pos)),
                 }
                 let key = deserializer.parse_str()?;
-                let entry = map.key(key)?;
+                path.set_key(&key);
+                let entry = map.key(&key)?;
+                deserializer.count_entry()?;
                 let next = NonNull::from(entry);
-                visitor = unsafe { extend_lifetime!(next as NonNull<dyn Visitor>) };
+                visitor = unsafe { extend_lifetime!(next as NonNull<dyn Visitor<'de>>) };
                 match deserializer.skip_whitespace() {
                     Some(b':') => deserializer.move_next_pos(),
-                    _ => return Err(Error),
+                    _ => return Err(deserializer.unexpected_at(deserializer.pos)),
                 }
                 deserializer.stack.push((outer, Layer::Map(map)));
             }
@@ -166,7 +329,7 @@ fn internal_from_str(s: &str, visitor: &mut dyn Visitor) -> Result<()> {
 
     // Remove remaining whitespace, raise error if unusual character found
     match deserializer.skip_whitespace() {
-        Some(_) => Err(Error),
+        Some(_) => Err(deserializer.unexpected_at(deserializer.pos)),
         None => Ok(()),
     }
 }
@@ -185,7 +348,32 @@ macro_rules! overflow {
     };
 }
 
-impl<'a, 'b> Deserializer<'a, 'b> {
+impl<'de> Deserializer<'de> {
+    /// Start counting the entries of a new sequence or map, the opening bracket was just read.
+    fn open_collection(&mut self) -> Result<()> {
+        if self.config.max_depth.is_some_and(|max| self.lengths.len() >= max) {
+            return Err(self.limit_exceeded(Limit::Depth, self.pos - 1));
+        }
+        self.lengths.push(0);
+        Ok(())
+    }
+
+    /// Count one more element or entry in the innermost collection.
+    fn count_entry(&mut self) -> Result<()> {
+        let max = self.config.max_collection_len;
+        if let Some(length) = self.lengths.last_mut() {
+            *length += 1;
+            if max.is_some_and(|max| *length > max) {
+                return Err(self.limit_exceeded(Limit::CollectionLength, self.pos));
+            }
+        }
+        Ok(())
+    }
+
+    fn limit_exceeded(&self, limit: Limit, pos: usize) -> Error {
+        Error::new(ErrorKind::LimitExceeded(limit)).at_position(self.input, pos)
+    }
+
     fn next(&mut self) -> Option<u8> {
         let cur_pos = self.pos;
         if cur_pos >= self.input.len() {
@@ -216,18 +404,35 @@ impl<'a, 'b> Deserializer<'a, 'b> {
         self.pos += 1;
     }
 
-    fn get_event(&mut self) -> Result<Event> {
+    /// Error for the character starting at `pos`, or for the end of input.
+    fn unexpected_at(&self, pos: usize) -> Error {
+        let kind = match self.input.get(pos..).filter(|rest| !rest.is_empty()) {
+            None => ErrorKind::Eof,
+            Some(rest) => {
+                let len = rest.len().min(4);
+                let c = match str::from_utf8(&rest[..len]) {
+                    Ok(s) => s.chars().next(),
+                    Err(e) => str::from_utf8(&rest[..e.valid_up_to()]).ok().and_then(|s| s.chars().next()),
+                };
+                ErrorKind::UnexpectedChar(c.unwrap_or(char::REPLACEMENT_CHARACTER))
+            }
+        };
+        Error::new(kind).at_position(self.input, pos)
+    }
+
+    fn get_event(&mut self) -> Result<Event<'de, '_>> {
         let peek = match self.skip_whitespace() {
             Some(b) => b,
-            None => return Err(Error),
+            None => return Err(self.unexpected_at(self.pos)),
         };
+        let start = self.pos;
         self.move_next_pos();
         match peek {
             b'"' => self.parse_str().map(Str),
-            digit @ b'0'..=b'9' => self.parse_integer(true, digit),
+            digit @ b'0'..=b'9' => self.parse_integer(start, true, digit),
             b'-' => {
                 let first_digit = self.next_or_null();
-                self.parse_integer(false, first_digit)
+                self.parse_integer(start, false, first_digit)
             }
             b'{' => Ok(MapStart),
             b'[' => Ok(SeqStart),
@@ -243,32 +448,39 @@ impl<'a, 'b> Deserializer<'a, 'b> {
                 self.parse_ident(b"alse")?;
                 Ok(Bool(false))
             }
-            _ => Err(Error),
+            _ => Err(self.unexpected_at(self.pos - 1)),
         }
     }
 
-    fn parse_str(&mut self) -> Result<&str> {
+    fn parse_str(&mut self) -> Result<Reference<'de, '_>> {
         fn result(bytes: &[u8]) -> &str {
+            // every chunk copied into the buffer was validated, and escapes only push whole characters
             unsafe { str::from_utf8_unchecked(bytes) }
         }
 
+        // Position of the opening quote, already read.
+        let quote = self.pos - 1;
         // Index of the first byte not yet copied into the scratch space.
         let mut start = self.pos;
         self.buffer.clear();
 
         loop {
-            while !ESCAPE[(self.input[self.pos]) as usize] {
+            while self.pos < self.input.len() && !ESCAPE[(self.input[self.pos]) as usize] {
                 self.move_next_pos();
-                if self.pos == self.input.len() {
-                    return Err(Error);
-                }
+            }
+            if self.pos == self.input.len() {
+                return Err(self.unexpected_at(self.pos));
             }
 
             let borrowed_str = &self.input[start..self.pos];
+            // quotes and backslashes never occur inside a multi-byte sequence, so every chunk between them is validated alone
+            if let Err(e) = str::from_utf8(borrowed_str) {
+                return Err(Error::new(ErrorKind::InvalidUtf8).at_position(self.input, start + e.valid_up_to()));
+            }
             let cur_char = self.input[self.pos];
 
             if cur_char != b'\\' && cur_char != b'"' {
-                return Err(Error);
+                return Err(self.unexpected_at(self.pos));
             }
             self.move_next_pos();
             match cur_char {
@@ -278,11 +490,14 @@ impl<'a, 'b> Deserializer<'a, 'b> {
                     start = self.pos;
                 }
                 b'"' => {
+                    if self.config.max_string_len.is_some_and(|max| self.buffer.len() + borrowed_str.len() > max) {
+                        return Err(self.limit_exceeded(Limit::StringLength, quote));
+                    }
                     if self.buffer.is_empty() {
-                        return Ok(result(borrowed_str));
+                        return Ok(Reference::Borrowed(result(borrowed_str)));
                     } else {
-                        self.buffer.extend_from_slice(&borrowed_str);
-                        return Ok(result(&self.buffer));
+                        self.buffer.extend_from_slice(borrowed_str);
+                        return Ok(Reference::Copied(result(&self.buffer)));
                     }
                 }
                 _ => unreachable!(),
@@ -291,7 +506,7 @@ impl<'a, 'b> Deserializer<'a, 'b> {
     }
 
     fn next_or_eof(&mut self) -> Result<u8> {
-        self.next().ok_or(Error)
+        self.next().ok_or_else(|| self.unexpected_at(self.pos))
     }
 
     fn parse_escape(&mut self) -> Result<()> {
@@ -306,14 +521,48 @@ impl<'a, 'b> Deserializer<'a, 'b> {
             b'n' => self.buffer.push(b'\n'),
             b'r' => self.buffer.push(b'\r'),
             b't' => self.buffer.push(b'\t'),
+            b'u' => {
+                let escape_start = self.pos - 2;
+                let c = match self.parse_hex4()? {
+                    // characters outside of the basic multilingual plane are written as a UTF-16 surrogate pair
+                    high @ 0xD800..=0xDBFF => {
+                        if self.next_or_eof()? != b'\\' || self.next_or_eof()? != b'u' {
+                            return Err(self.unexpected_at(self.pos - 1));
+                        }
+                        match self.parse_hex4()? {
+                            low @ 0xDC00..=0xDFFF => char::from_u32(0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)),
+                            _ => None,
+                        }
+                    }
+                    n => char::from_u32(n),
+                };
+                match c {
+                    Some(c) => self.buffer.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes()),
+                    None => return Err(self.unexpected_at(escape_start)),
+                }
+            }
             _ => {
-                return Err(Error);
+                return Err(self.unexpected_at(self.pos - 1));
             }
         }
 
         Ok(())
     }
 
+    fn parse_hex4(&mut self) -> Result<u32> {
+        let mut n = 0;
+        for _ in 0..4 {
+            let digit = match self.next_or_eof()? {
+                b @ b'0'..=b'9' => b - b'0',
+                b @ b'a'..=b'f' => b - b'a' + 10,
+                b @ b'A'..=b'F' => b - b'A' + 10,
+                _ => return Err(self.unexpected_at(self.pos - 1)),
+            };
+            n = n * 16 + digit as u32;
+        }
+        Ok(n)
+    }
+
     fn skip_whitespace(&mut self) -> Option<u8> {
         let is_whitespace = |char: Option<u8>| match char {
             Some(b' ') | Some(b'\n') | Some(b'\t') | Some(b'\r') => true,
@@ -328,23 +577,23 @@ impl<'a, 'b> Deserializer<'a, 'b> {
 
     fn parse_ident(&mut self, ident: &[u8]) -> Result<()> {
         for &expected in ident {
-            let next = self.next();
-            if next.is_none() || next.unwrap() != expected {
-                return Err(Error);
+            if self.peek() != Some(expected) {
+                return Err(self.unexpected_at(self.pos));
             }
+            self.move_next_pos();
         }
         Ok(())
     }
 
-    fn parse_integer(&mut self, nonnegative: bool, first_digit: u8) -> Result<Event> {
+    fn parse_integer(&mut self, start: usize, nonnegative: bool, first_digit: u8) -> Result<Event<'de, '_>> {
         if !first_digit.is_ascii_digit() {
-            return Err(Error);
+            return Err(self.unexpected_at(self.pos.saturating_sub(1)));
         }
         if first_digit == b'0' {
             if self.peek_or_null().is_ascii_digit() {
-                return Err(Error);
+                return Err(self.unexpected_at(self.pos));
             }
-            return self.parse_number(nonnegative, 0);
+            return self.parse_number(start, nonnegative, 0);
         }
 
         let mut number = (first_digit - b'0') as u64;
@@ -357,39 +606,18 @@ impl<'a, 'b> Deserializer<'a, 'b> {
 
             let digit = (d - b'0') as u64;
             if overflow!(number * 10 + digit, u64::MAX) {
-                // use long f64 if overflow, skip the digit, i.e. result *10
-                return self.parse_long_integer(nonnegative, number).map(Float);
+                // too long for u64, carry on in 128 bits
+                return self.parse_big_integer(start, nonnegative, number as u128 * 10 + digit as u128);
             }
 
             number = number * 10 + digit;
         }
-        return self.parse_number(nonnegative, number);
+        self.parse_number(start, nonnegative, number)
     }
 
-    fn parse_long_integer(&mut self, nonnegative: bool, mantissa: u64) -> Result<f64> {
-        let mut base_10_exp = 1;
-        while self.peek_or_null().is_ascii_digit() {
-            self.move_next_pos();
-            base_10_exp += 1;
-        }
-
+    fn parse_number(&mut self, start: usize, nonnegative: bool, mantissa: u64) -> Result<Event<'de, '_>> {
         match self.peek_or_null() {
-            b'.' => {
-                return self.parse_decimal(nonnegative, mantissa, base_10_exp);
-            }
-            b'e' | b'E' => {
-                return self.parse_exponent(nonnegative, mantissa, base_10_exp);
-            }
-            _ => {
-                return construct_f64(nonnegative, mantissa, base_10_exp);
-            }
-        }
-    }
-
-    fn parse_number(&mut self, nonnegative: bool, mantissa: u64) -> Result<Event> {
-        match self.peek_or_null() {
-            b'.' => self.parse_decimal(nonnegative, mantissa, 0).map(Float),
-            b'e' | b'E' => self.parse_exponent(nonnegative, mantissa, 0).map(Float),
+            b'.' | b'e' | b'E' => self.parse_float(start).map(|f| self.float_event(f)),
             _ => {
                 if nonnegative {
                     return Ok(Nonnegative(mantissa));
@@ -397,102 +625,87 @@ impl<'a, 'b> Deserializer<'a, 'b> {
 
                 let neg = (mantissa as i64).wrapping_neg(); // negative underflow
 
-                return Ok(if neg > 0 { Float(-(mantissa as f64)) } else { Negative(neg) });
+                Ok(if neg > 0 { BigNegative(-(mantissa as i128)) } else { Negative(neg) })
             }
         }
     }
 
-    fn parse_decimal(&mut self, nonnegative: bool, mut mantissa: u64, mut base_10_exp: i32) -> Result<f64> {
-        self.move_next_pos();
-
-        let mut at_least_one_digit = false;
-        while let c @ b'0'..=b'9' = self.peek_or_null() {
-            self.move_next_pos();
-            let digit = (c - b'0') as u64;
-            at_least_one_digit = true;
-
-            if overflow!(mantissa * 10 + digit, u64::MAX) {
-                while let b'0'..=b'9' = self.peek_or_null() {
-                    self.move_next_pos();
-                }
+    /// Same as [`parse_integer`](Deserializer::parse_integer) past the 64-bit range, integers too long for 128 bits
+    /// are read as floats.
+    fn parse_big_integer(&mut self, start: usize, nonnegative: bool, mut number: u128) -> Result<Event<'de, '_>> {
+        while let Some(d) = self.peek() {
+            if !d.is_ascii_digit() {
                 break;
             }
+            self.move_next_pos();
 
-            mantissa = mantissa * 10 + digit;
-            base_10_exp -= 1;
-        }
+            let digit = (d - b'0') as u128;
+            if overflow!(number * 10 + digit, u128::MAX) {
+                self.skip_digits();
+                return self.parse_float(start).map(Float);
+            }
 
-        if !at_least_one_digit {
-            return Err(Error);
+            number = number * 10 + digit;
         }
 
         match self.peek_or_null() {
-            b'e' | b'E' => self.parse_exponent(nonnegative, mantissa, base_10_exp),
-            _ => construct_f64(nonnegative, mantissa, base_10_exp),
+            b'.' | b'e' | b'E' => self.parse_float(start).map(Float),
+            _ if nonnegative => Ok(BigNonnegative(number)),
+            // `i128::MIN` is the only one whose negation does not fit
+            _ if number <= i128::MIN.unsigned_abs() => Ok(BigNegative((number as i128).wrapping_neg())),
+            _ => Ok(Float(-(number as f64))),
         }
     }
 
-    fn parse_exponent(&mut self, nonnegative: bool, mantissa: u64, starting_base_10_exp: i32) -> Result<f64> {
-        self.move_next_pos();
+    /// Whole floats are read as integers with [`DeserializerConfig::integral_floats`].
+    fn float_event(&self, f: f64) -> Event<'de, 'static> {
+        // every integer up to 2^53 is exactly representable
+        const EXACT: f64 = (1u64 << 53) as f64;
+        if !self.config.integral_floats || f.fract() != 0.0 || f.abs() > EXACT {
+            return Float(f);
+        }
+        match f >= 0.0 {
+            true => Nonnegative(f as u64),
+            false => Negative(f as i64),
+        }
+    }
 
-        let is_positive_exponent = match self.peek_or_null() {
-            b'+' => {
-                self.move_next_pos();
-                true
-            }
-            b'-' => {
-                self.move_next_pos();
-                false
+    /// Read the fraction and exponent of the number starting at `start`, whose integer part was already read.
+    /// The conversion is done by the standard library, which rounds correctly, so that every float written in its
+    /// shortest form reads back to the same value.
+    fn parse_float(&mut self, start: usize) -> Result<f64> {
+        if self.peek_or_null() == b'.' {
+            self.move_next_pos();
+            if !self.peek_or_null().is_ascii_digit() {
+                return Err(self.unexpected_at(self.pos));
             }
-            _ => true,
-        };
-
-        let _cur = self.next_or_null();
-        if !_cur.is_ascii_digit() {
-            return Err(Error);
+            self.skip_digits();
         }
-        let mut exp = (_cur - b'0') as i32;
 
-        while let c @ b'0'..=b'9' = self.peek_or_null() {
+        if let b'e' | b'E' = self.peek_or_null() {
             self.move_next_pos();
-            let digit = (c - b'0') as i32;
-
-            if overflow!(exp * 10 + digit, i32::MAX) {
-                // Out of bounds -> Error
-                if mantissa != 0 && is_positive_exponent {
-                    return Err(Error);
-                }
-
-                while let b'0'..=b'9' = self.peek_or_null() {
-                    self.move_next_pos();
-                }
-
-                // negligible small -> cast to absolute 0.
-                return Ok(if nonnegative { 0.0 } else { -0.0 });
+            if let b'+' | b'-' = self.peek_or_null() {
+                self.move_next_pos();
             }
-
-            exp = exp * 10 + digit;
+            if !self.peek_or_null().is_ascii_digit() {
+                return Err(self.unexpected_at(self.pos));
+            }
+            self.skip_digits();
         }
 
-        let final_exp = if is_positive_exponent {
-            starting_base_10_exp.saturating_add(exp)
-        } else {
-            starting_base_10_exp.saturating_sub(exp)
-        };
-
-        construct_f64(nonnegative, mantissa, final_exp)
+        // only ASCII digits, sign, dot and exponent were read
+        let number = unsafe { str::from_utf8_unchecked(&self.input[start..self.pos]) };
+        match number.parse::<f64>() {
+            Ok(f) if f.is_finite() => Ok(f),
+            _ => Err(Error::new(ErrorKind::NumberOverflow)),
+        }
     }
-}
 
-fn construct_f64(nonnegative: bool, significand: u64, base_10_exp: i32) -> Result<f64> {
-    let mut f = significand as f64;
-    const BASE: f64 = 10.0;
-    let pow = BASE.powi(base_10_exp);
-    f *= pow;
-    if f.is_infinite() {
-        return Err(Error);
+    fn skip_digits(&mut self) {
+        while self.peek_or_null().is_ascii_digit() {
+            self.move_next_pos();
+        }
     }
-    Ok(if nonnegative { f } else { -f })
 }
 
 const CT: bool = true; // control character \x00..=\x1F
@@ -0,0 +1,61 @@
+/// Build a [`Value`](crate::json::Value) from a JSON literal. Keys must be string literals, any other value is an
+/// expression converted with [`to_value`](crate::json::to_value).
+///
+/// ```
+/// use serde::json;
+///
+/// let seats = 3;
+/// let body = json!({
+///     "price": -150.99,
+///     "seats": seats,
+///     "source": "LAS",
+///     "stops": ["SIN", null],
+/// });
+/// assert_eq!(json::to_string(&body), r#"{"price":-150.99,"seats":3,"source":"LAS","stops":["SIN",null]}"#);
+/// ```
+#[macro_export]
+macro_rules! json {
+    // Elements of an array, the converted ones are accumulated in the brackets.
+    (@array [$($done:expr,)*]) => {
+        <$crate::json::Array as ::core::iter::FromIterator<$crate::json::Value>>::from_iter([$($done,)*])
+    };
+    (@array [$($done:expr,)*] $next:tt $(, $($rest:tt)*)?) => {
+        $crate::json!(@array [$($done,)* $crate::json!($next),] $($($rest)*)?)
+    };
+    (@array [$($done:expr,)*] $next:expr, $($rest:tt)*) => {
+        $crate::json!(@array [$($done,)* $crate::json!($next),] $($rest)*)
+    };
+    (@array [$($done:expr,)*] $last:expr) => {
+        $crate::json!(@array [$($done,)* $crate::json!($last),])
+    };
+
+    // Entries of an object, inserted one by one.
+    (@object $object:ident) => {};
+    (@object $object:ident $key:literal : $value:tt $(, $($rest:tt)*)?) => {
+        $object.insert($crate::__private::String::from($key), $crate::json!($value));
+        $crate::json!(@object $object $($($rest)*)?);
+    };
+    (@object $object:ident $key:literal : $value:expr, $($rest:tt)*) => {
+        $object.insert($crate::__private::String::from($key), $crate::json!($value));
+        $crate::json!(@object $object $($rest)*);
+    };
+    (@object $object:ident $key:literal : $value:expr) => {
+        $object.insert($crate::__private::String::from($key), $crate::json!($value));
+    };
+
+    (null) => {
+        $crate::json::Value::Null
+    };
+    ([$($elements:tt)*]) => {
+        $crate::json::Value::Array($crate::json!(@array [] $($elements)*))
+    };
+    ({$($entries:tt)*}) => {{
+        #[allow(unused_mut)]
+        let mut object = $crate::json::Object::new();
+        $crate::json!(@object object $($entries)*);
+        $crate::json::Value::Object(object)
+    }};
+    ($other:expr) => {
+        $crate::json::to_value(&$other)
+    };
+}
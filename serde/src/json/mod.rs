@@ -1,10 +1,174 @@
 mod ser;
-pub use self::ser::to_string;
+pub use self::ser::{to_string, to_string_pretty, try_to_string, Serializer};
 
 mod de;
-pub use self::de::from_str;
+pub use self::de::{from_reader, from_slice, from_str, DeserializerConfig};
 
 mod value;
 pub use self::value::*;
 
 mod drop;
+
+mod macros;
+
+#[cfg(test)]
+mod tests {
+    use super::{from_reader, from_slice, from_str, DeserializerConfig, Serializer, Value};
+    use crate::{ser, ErrorKind, Limit};
+    use std::borrow::Cow;
+    use std::collections::{BTreeMap, HashMap};
+
+    #[test]
+    fn syntax_error_should_report_line_and_column() {
+        let error = from_str::<Value>("{\n  \"flights\": [1, 2,, 3]\n}").unwrap_err();
+        assert_eq!(error.kind(), &ErrorKind::UnexpectedChar(','));
+        assert_eq!((error.line(), error.column(), error.offset()), (Some(2), Some(20), Some(21)));
+
+        let error = from_str::<Value>("[\"LAS").unwrap_err();
+        assert_eq!(error.kind(), &ErrorKind::Eof);
+    }
+
+    #[test]
+    fn type_error_should_report_path() {
+        let error = from_str::<Vec<Vec<u8>>>("[[1], [2, 300]]").unwrap_err();
+        assert_eq!(error.kind(), &ErrorKind::NumberOverflow);
+        assert_eq!(error.path(), "[1][1]");
+
+        let error = from_str::<Vec<u32>>(r#"[1, "2"]"#).unwrap_err();
+        assert_eq!(error.kind(), &ErrorKind::TypeMismatch);
+        assert_eq!(error.to_string(), "invalid type at `[1]`, line 1 column 8");
+    }
+
+    #[test]
+    fn pretty_serializer_should_indent_nested_values() {
+        let value = from_str::<Value>(r#"{"extra":{},"flights":[{"id":1,"seats":[]},{"id":2,"seats":[3]}]}"#).unwrap();
+        let text = Serializer::new().pretty(2).to_string(&value).unwrap();
+        let expected = r#"{
+  "extra": {},
+  "flights": [
+    {
+      "id": 1,
+      "seats": []
+    },
+    {
+      "id": 2,
+      "seats": [
+        3
+      ]
+    }
+  ]
+}"#;
+        assert_eq!(text, expected);
+        assert_eq!(super::to_string(&from_str::<Value>(&text).unwrap()), super::to_string(&value));
+    }
+
+    #[test]
+    fn serializer_should_sort_keys_and_escape_non_ascii() {
+        let fares = HashMap::from([("SIN", 2), ("HAN", 1), ("LAS", 3)]);
+        let text = Serializer::new().sort_keys(true).to_string(&fares).unwrap();
+        assert_eq!(text, r#"{"HAN":1,"LAS":3,"SIN":2}"#);
+        let routes = HashMap::from([("SIN", vec![fares.clone()]), ("HAN", vec![])]);
+        let text = Serializer::new().sort_keys(true).to_string(&routes).unwrap();
+        assert_eq!(text, r#"{"HAN":[],"SIN":[{"HAN":1,"LAS":3,"SIN":2}]}"#);
+
+        let cities = BTreeMap::from([("Zürich", "✈ 🛫")]);
+        let text = Serializer::new().ascii_only(true).to_string(&cities).unwrap();
+        assert_eq!(text, r#"{"Z\u00fcrich":"\u2708 \ud83d\udeeb"}"#);
+        assert_eq!(from_str::<BTreeMap<String, String>>(&text).unwrap()["Zürich"], "✈ 🛫");
+        assert_eq!(from_str::<String>(&super::to_string("\u{1}")).unwrap(), "\u{1}");
+        assert_eq!(from_str::<String>(r#""\udc00""#).unwrap_err().kind(), &ErrorKind::UnexpectedChar('\\'));
+    }
+
+    /// Map yielding references into a slot it overwrites on every call.
+    struct Countdown {
+        remaining: u32,
+        slot: u32,
+    }
+
+    impl ser::Map for Countdown {
+        fn next(&mut self) -> Option<(Cow<'_, str>, &dyn ser::Serialize)> {
+            self.remaining = self.remaining.checked_sub(1)?;
+            self.slot = self.remaining;
+            Some((Cow::Owned(format!("k{}", self.remaining)), &self.slot))
+        }
+    }
+
+    impl ser::Serialize for Countdown {
+        fn begin(&self) -> ser::Fragment<'_> {
+            ser::Fragment::Map(Box::new(Countdown { remaining: self.remaining, slot: 0 }))
+        }
+    }
+
+    #[test]
+    fn sorted_keys_should_not_keep_borrowed_values() {
+        let text = Serializer::new().sort_keys(true).to_string(&Countdown { remaining: 3, slot: 0 }).unwrap();
+        assert_eq!(text, r#"{"k0":0,"k1":1,"k2":2}"#);
+
+        let fares = BTreeMap::from([("SIN", vec![f64::NAN])]);
+        let error = Serializer::new().sort_keys(true).reject_non_finite(true).to_string(&fares).unwrap_err();
+        assert_eq!(error.kind(), &ErrorKind::NonFiniteFloat);
+    }
+
+    #[test]
+    fn serializer_should_write_into_writer() {
+        let mut out = Vec::new();
+        Serializer::new().to_writer(&mut out, &vec![Some(1.5), None]).unwrap();
+        assert_eq!(out, b"[1.5,null]");
+
+        let error = Serializer::new().reject_non_finite(true).to_writer(&mut out, &f64::NAN).unwrap_err();
+        assert_eq!(error.kind(), &ErrorKind::NonFiniteFloat);
+    }
+
+    #[test]
+    fn invalid_utf8_should_be_reported_at_its_offset() {
+        let error = from_slice::<Vec<String>>(b"[\"LAS\", \"H\xc3N\"]").unwrap_err();
+        assert_eq!(error.kind(), &ErrorKind::InvalidUtf8);
+        assert_eq!((error.offset(), error.path()), (Some(10), "[1]"));
+
+        let error = from_slice::<String>(b"\"\\n\xff\"").unwrap_err();
+        assert_eq!(error.kind(), &ErrorKind::InvalidUtf8);
+        assert_eq!(error.offset(), Some(3));
+
+        assert_eq!(from_slice::<String>("\"Zürich\\n\"".as_bytes()).unwrap(), "Zürich\n");
+    }
+
+    #[test]
+    fn reader_should_be_read_to_the_end() {
+        let flights: Vec<u32> = from_reader(&b"[1, 2,\n 3]"[..]).unwrap();
+        assert_eq!(flights, [1, 2, 3]);
+
+        let error = from_reader::<Vec<u32>, _>(&b"[1, 2"[..]).unwrap_err();
+        assert_eq!(error.kind(), &ErrorKind::Eof);
+    }
+
+    #[test]
+    fn limits_should_be_enforced() {
+        let strict = DeserializerConfig::new().max_depth(2).max_string_len(3).max_collection_len(2).max_input_len(32);
+        let value = strict.from_str::<Value>(r#"{"LAS": [1, 2], "SIN": "HAN"}"#).unwrap();
+        assert_eq!(value["LAS"][1], 2);
+
+        let error = strict.from_str::<Value>("[[[1]]]").unwrap_err();
+        assert_eq!(error.kind(), &ErrorKind::LimitExceeded(Limit::Depth));
+        assert_eq!((error.offset(), error.path()), (Some(2), "[0][0]"));
+
+        let error = strict.from_str::<Value>(r#"{"LAS": "Las Vegas"}"#).unwrap_err();
+        assert_eq!(error.kind(), &ErrorKind::LimitExceeded(Limit::StringLength));
+        assert_eq!((error.offset(), error.path()), (Some(8), "LAS"));
+        let error = strict.from_str::<Value>(r#"{"LASV": 1}"#).unwrap_err();
+        assert_eq!(error.kind(), &ErrorKind::LimitExceeded(Limit::StringLength));
+        assert_eq!(strict.from_str::<String>(r#""ü""#).unwrap(), "ü");
+
+        let error = strict.from_str::<Value>("[1, 2, 3]").unwrap_err();
+        assert_eq!(error.kind(), &ErrorKind::LimitExceeded(Limit::CollectionLength));
+        assert_eq!(error.path(), "[2]");
+        let error = strict.from_str::<Value>(r#"{"a": 1, "b": 2, "c": 3}"#).unwrap_err();
+        assert_eq!((error.kind(), error.path()), (&ErrorKind::LimitExceeded(Limit::CollectionLength), "c"));
+
+        let long = format!("[{}1]", " ".repeat(32));
+        let error = strict.from_str::<Vec<u32>>(&long).unwrap_err();
+        assert_eq!((error.kind(), error.offset()), (&ErrorKind::LimitExceeded(Limit::InputLength), Some(32)));
+        let error = strict.from_reader::<Vec<u32>, _>(long.as_bytes()).unwrap_err();
+        assert_eq!(error.kind(), &ErrorKind::LimitExceeded(Limit::InputLength));
+        assert_eq!(error.to_string(), "input length limit exceeded, line 1 column 33");
+    }
+}
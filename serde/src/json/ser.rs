@@ -1,141 +1,271 @@
+use super::value::value_of;
 use crate::ser::{Fragment, Map, Seq, Serialize};
 use crate::{extend_lifetime, extend_lifetime_impl};
+use crate::{Error, ErrorKind, Result};
 
 use std::borrow::Cow;
 use std::boxed::Box;
+use std::io;
 use std::string::String;
-use std::vec::Vec;
+use std::vec::{self, Vec};
+
+/// Only non-finite floats with `reject_non_finite` and I/O fail to serialize, neither happens writing into a `Vec`.
+const INFALLIBLE: &str = "a serializer allowing non-finite floats cannot fail to write into a Vec";
 
 /// Serialize any serializable type into a JSON string.
+///
+/// Floats are written in their shortest form that reads back to the same value. `NaN` and infinities have no JSON
+/// representation and are written as `null`, see [`try_to_string`] to reject them instead.
 pub fn to_string<T: ?Sized + Serialize>(value: &T) -> String {
-    to_string_impl(&value)
+    Serializer::new().to_string(value).expect(INFALLIBLE)
+}
+
+/// Same as [`to_string`], indented by two spaces per level.
+pub fn to_string_pretty<T: ?Sized + Serialize>(value: &T) -> String {
+    Serializer::new().pretty(2).to_string(value).expect(INFALLIBLE)
+}
+
+/// Same as [`to_string`], but fails with [`ErrorKind::NonFiniteFloat`] on `NaN` and infinite floats.
+pub fn try_to_string<T: ?Sized + Serialize>(value: &T) -> Result<String> {
+    Serializer::new().reject_non_finite(true).to_string(value)
+}
+
+/// JSON writer with configurable layout, compact by default.
+///
+/// ```
+/// use serde::json::Serializer;
+///
+/// let pretty = Serializer::new().pretty(2).sort_keys(true);
+/// let text = pretty.to_string(&vec![1, 2]).unwrap();
+/// assert_eq!(text, "[\n  1,\n  2\n]");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Serializer {
+    indent: Option<usize>,
+    sort_keys: bool,
+    ascii_only: bool,
+    reject_non_finite: bool,
 }
 
-fn to_string_impl(value: &dyn Serialize) -> String {
-    let mut out_str = String::new();
-    let mut serializer = Serializer { stack: Vec::new() };
-    let mut fragment = value.begin();
-
-    loop {
-        match fragment {
-            Fragment::Null => out_str.push_str("null"),
-            Fragment::Bool(b) => out_str.push_str(if b { "true" } else { "false" }),
-            Fragment::Str(s) => escape_str(&s, &mut out_str),
-            Fragment::U64(u) => out_str.push_str(&u.to_string()),
-            Fragment::I64(i) => out_str.push_str(&i.to_string()),
-            Fragment::F64(f) => out_str.push_str(&format!("{:.2}", f)),
-            Fragment::Seq(mut seq) => {
-                out_str.push('[');
-                match unsafe { extend_lifetime!(seq.next() as Option<&dyn Serialize>) } {
+impl Serializer {
+    pub fn new() -> Self {
+        Serializer::default()
+    }
+
+    /// Write every element and entry on its own line, indented by `indent` spaces per level.
+    pub fn pretty(mut self, indent: usize) -> Self {
+        self.indent = Some(indent);
+        self
+    }
+
+    /// Write the entries of every map ordered by key instead of in iteration order.
+    pub fn sort_keys(mut self, sort_keys: bool) -> Self {
+        self.sort_keys = sort_keys;
+        self
+    }
+
+    /// Escape every non-ASCII character as `\uXXXX`.
+    pub fn ascii_only(mut self, ascii_only: bool) -> Self {
+        self.ascii_only = ascii_only;
+        self
+    }
+
+    /// Fail with [`ErrorKind::NonFiniteFloat`] on `NaN` and infinite floats instead of writing `null`.
+    pub fn reject_non_finite(mut self, reject_non_finite: bool) -> Self {
+        self.reject_non_finite = reject_non_finite;
+        self
+    }
+
+    pub fn to_string<T: ?Sized + Serialize>(&self, value: &T) -> Result<String> {
+        let mut out = Vec::new();
+        self.write(&mut out, &value)?;
+        // only whole `str` slices and ASCII are written
+        Ok(unsafe { String::from_utf8_unchecked(out) })
+    }
+
+    /// Write `value` into `writer`, which should be buffered as the output is written piece by piece.
+    pub fn to_writer<W: io::Write, T: ?Sized + Serialize>(&self, mut writer: W, value: &T) -> Result<()> {
+        self.write(&mut writer, &value)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    fn write(&self, out: &mut dyn io::Write, value: &dyn Serialize) -> Result<()> {
+        match self.sort_keys {
+            // maps are sorted in place over a copy of the whole value, made once
+            true => self.write_fragments(out, &value_of(value, self.reject_non_finite)?),
+            false => self.write_fragments(out, value),
+        }
+    }
+
+    fn write_fragments(&self, out: &mut dyn io::Write, value: &dyn Serialize) -> Result<()> {
+        let mut stack = Stack(Vec::new());
+        let mut fragment = value.begin();
+
+        loop {
+            match fragment {
+                Fragment::Null => out.write_all(b"null")?,
+                Fragment::Bool(b) => out.write_all(if b { b"true" } else { b"false" })?,
+                Fragment::Str(s) => self.write_str(out, &s)?,
+                Fragment::U64(u) => write!(out, "{}", u)?,
+                Fragment::I64(i) => write!(out, "{}", i)?,
+                Fragment::U128(u) => write!(out, "{}", u)?,
+                Fragment::I128(i) => write!(out, "{}", i)?,
+                Fragment::F32(f) if f.is_finite() => out.write_all(ryu::Buffer::new().format_finite(f).as_bytes())?,
+                Fragment::F64(f) if f.is_finite() => out.write_all(ryu::Buffer::new().format_finite(f).as_bytes())?,
+                Fragment::F32(_) | Fragment::F64(_) => match self.reject_non_finite {
+                    false => out.write_all(b"null")?,
+                    true => return Err(Error::new(ErrorKind::NonFiniteFloat)),
+                },
+                Fragment::Seq(mut seq) => match unsafe { extend_lifetime!(seq.next() as Option<&dyn Serialize>) } {
                     Some(first) => {
-                        serializer.stack.push(Layer::Seq(seq));
+                        out.write_all(b"[")?;
+                        stack.0.push(Layer::Seq(seq));
+                        self.write_newline(out, stack.0.len())?;
                         fragment = first.begin();
                         continue;
                     }
-                    None => out_str.push(']'),
+                    None => out.write_all(b"[]")?,
+                },
+                Fragment::Map(map) => {
+                    let mut map = if self.sort_keys { sort_entries(map) } else { map };
+                    match unsafe { extend_lifetime!(map.next() as Option<(Cow<str>, &dyn Serialize)>) } {
+                        Some((key, first)) => {
+                            out.write_all(b"{")?;
+                            stack.0.push(Layer::Map(map));
+                            self.write_newline(out, stack.0.len())?;
+                            self.write_key(out, &key)?;
+                            fragment = first.begin();
+                            continue;
+                        }
+                        None => out.write_all(b"{}")?,
+                    }
                 }
             }
-            Fragment::Map(mut map) => {
-                out_str.push('{');
-                match unsafe { extend_lifetime!(map.next() as Option<(Cow<str>, &dyn Serialize)>) } {
-                    Some((key, first)) => {
-                        escape_str(&key, &mut out_str);
-                        out_str.push(':');
-                        serializer.stack.push(Layer::Map(map));
-                        fragment = first.begin();
-                        continue;
-                    }
-                    None => out_str.push('}'),
+
+            loop {
+                let depth = stack.0.len();
+                match stack.0.last_mut() {
+                    Some(Layer::Seq(seq)) => match unsafe { extend_lifetime!(seq.next() as Option<&dyn Serialize>) } {
+                        Some(next) => {
+                            out.write_all(b",")?;
+                            self.write_newline(out, depth)?;
+                            fragment = next.begin();
+                            break;
+                        }
+                        None => {
+                            self.write_newline(out, depth - 1)?;
+                            out.write_all(b"]")?;
+                        }
+                    },
+                    Some(Layer::Map(map)) => match unsafe { extend_lifetime!(map.next() as Option<(Cow<str>, &dyn Serialize)>) } {
+                        Some((key, next)) => {
+                            out.write_all(b",")?;
+                            self.write_newline(out, depth)?;
+                            self.write_key(out, &key)?;
+                            fragment = next.begin();
+                            break;
+                        }
+                        None => {
+                            self.write_newline(out, depth - 1)?;
+                            out.write_all(b"}")?;
+                        }
+                    },
+                    None => return Ok(()),
                 }
+                stack.0.pop();
             }
         }
+    }
 
-        loop {
-            match serializer.stack.last_mut() {
-                Some(Layer::Seq(seq)) => match unsafe { extend_lifetime!(seq.next() as Option<&dyn Serialize>) } {
-                    Some(next) => {
-                        out_str.push(',');
-                        fragment = next.begin();
-                        break;
-                    }
-                    None => out_str.push(']'),
-                },
-                Some(Layer::Map(map)) => match unsafe { extend_lifetime!(map.next() as Option<(Cow<str>, &dyn Serialize)>) } {
-                    Some((key, next)) => {
-                        out_str.push(',');
-                        escape_str(&key, &mut out_str);
-                        out_str.push(':');
-                        fragment = next.begin();
-                        break;
+    /// Start a new line at nesting level `depth`, in pretty mode only.
+    fn write_newline(&self, out: &mut dyn io::Write, depth: usize) -> io::Result<()> {
+        if let Some(indent) = self.indent {
+            write!(out, "\n{:width$}", "", width = indent * depth)?;
+        }
+        Ok(())
+    }
+
+    fn write_key(&self, out: &mut dyn io::Write, key: &str) -> io::Result<()> {
+        self.write_str(out, key)?;
+        out.write_all(if self.indent.is_some() { b": " } else { b":" })
+    }
+
+    fn write_str(&self, out: &mut dyn io::Write, value: &str) -> io::Result<()> {
+        out.write_all(b"\"")?;
+
+        let bytes = value.as_bytes();
+        let mut start = 0;
+
+        for (i, &byte) in bytes.iter().enumerate() {
+            let escape = ESCAPE[byte as usize];
+            // bytes written as is, and the continuation bytes of a character escaped below
+            if i < start || (escape == 0 && (byte.is_ascii() || !self.ascii_only)) {
+                continue;
+            }
+
+            out.write_all(&bytes[start..i])?;
+            start = i + 1;
+
+            match escape {
+                0 => {
+                    // `\uXXXX` escapes, as UTF-16 surrogate pairs outside of the basic multilingual plane
+                    let c = value[i..].chars().next().unwrap();
+                    let mut units = [0; 2];
+                    for unit in c.encode_utf16(&mut units) {
+                        write!(out, "\\u{:04x}", unit)?;
                     }
-                    None => out_str.push('}'),
-                },
-                None => return out_str,
+                    start = i + c.len_utf8();
+                }
+                self::U => {
+                    static HEX_DIGITS: [u8; 16] = *b"0123456789abcdef";
+                    out.write_all(&[b'\\', b'u', b'0', b'0', HEX_DIGITS[(byte >> 4) as usize], HEX_DIGITS[(byte & 0xF) as usize]])?
+                }
+                escape => out.write_all(&[b'\\', escape])?,
             }
-            serializer.stack.pop();
         }
+
+        out.write_all(&bytes[start..])?;
+        out.write_all(b"\"")
     }
 }
 
-struct Serializer<'a> {
-    stack: Vec<Layer<'a>>,
-}
+/// Layers currently open, dropped in reverse order.
+struct Stack<'a>(Vec<Layer<'a>>);
 
 enum Layer<'a> {
     Seq(Box<dyn Seq + 'a>),
     Map(Box<dyn Map + 'a>),
 }
 
-impl<'a> Drop for Serializer<'a> {
+impl<'a> Drop for Stack<'a> {
     fn drop(&mut self) {
-        // Drop layers in reverse order.
-        while !self.stack.is_empty() {
-            self.stack.pop();
+        while !self.0.is_empty() {
+            self.0.pop();
         }
     }
 }
 
-fn escape_str(value: &str, out: &mut String) {
-    out.push('"');
-
-    let bytes = value.as_bytes();
-    let mut start = 0;
-
-    for (i, &byte) in bytes.iter().enumerate() {
-        let escape = ESCAPE[byte as usize];
-        if escape == 0 {
-            continue;
-        }
-
-        if start < i {
-            out.push_str(&value[start..i]);
-        }
-
-        match escape {
-            self::B_ => out.push_str("\\b"),
-            self::T_ => out.push_str("\\t"),
-            self::N_ => out.push_str("\\n"),
-            self::F_ => out.push_str("\\f"),
-            self::R_ => out.push_str("\\r"),
-            self::QT => out.push_str("\\\""),
-            self::BS => out.push_str("\\\\"),
-            self::U => {
-                static HEX_DIGITS: [u8; 16] = *b"0123456789abcdef";
-                out.push_str("\\u00");
-                out.push(HEX_DIGITS[(byte >> 4) as usize] as char);
-                out.push(HEX_DIGITS[(byte & 0xF) as usize] as char);
-            }
-            _ => unreachable!(),
-        }
+/// Entries of a map, collected ahead and ordered by key.
+struct SortedEntries<'a> {
+    entries: vec::IntoIter<(Cow<'a, str>, &'a dyn Serialize)>,
+}
 
-        start = i + 1;
+impl<'a> Map for SortedEntries<'a> {
+    fn next(&mut self) -> Option<(Cow<'_, str>, &dyn Serialize)> {
+        self.entries.next()
     }
+}
 
-    if start != bytes.len() {
-        out.push_str(&value[start..]);
+/// Order the entries of `map` by key. Only called on the maps of a [`Value`](super::Value), whose entries are borrowed from the
+/// value and stay in place until the whole value is written.
+fn sort_entries<'a>(mut map: Box<dyn Map + 'a>) -> Box<dyn Map + 'a> {
+    let mut entries = Vec::new();
+    while let Some(entry) = unsafe { extend_lifetime!(map.next() as Option<(Cow<str>, &dyn Serialize)>) } {
+        entries.push(entry);
     }
-
-    out.push('"');
+    entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+    Box::new(SortedEntries { entries: entries.into_iter() })
 }
 
 const B_: u8 = b'b'; // \x08
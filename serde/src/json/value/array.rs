@@ -83,6 +83,12 @@ impl FromIterator<Value> for Array {
     }
 }
 
+impl PartialEq for Array {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && super::impls::equal(self.iter().zip(other.iter()).collect())
+    }
+}
+
 impl Debug for Array {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
         formatter.write_str("Array ")?;
@@ -91,50 +97,50 @@ impl Debug for Array {
 }
 
 impl Serialize for Array {
-    fn begin(&self) -> Fragment {
+    fn begin(&self) -> Fragment<'_> {
         StreamSerializer::stream_slice(self)
     }
 }
 
-impl Deserialize for Array {
-    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor {
-        impl Visitor for Place<Array> {
-            fn seq(&mut self) -> Result<Box<dyn Seq + '_>> {
-                Ok(Box::new(ArrayBuilder {
-                    out: &mut self.out,
-                    array: Array::new(),
-                    element: None,
-                }))
-            }
-        }
+impl<'de> Deserialize<'de> for Array {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+        Place::new(out)
+    }
+}
 
-        struct ArrayBuilder<'a> {
-            out: &'a mut Option<Array>,
-            array: Array,
-            element: Option<Value>,
-        }
+impl<'de> Visitor<'de> for Place<Array> {
+    fn seq(&mut self) -> Result<Box<dyn Seq<'de> + '_>> {
+        Ok(Box::new(ArrayBuilder {
+            out: &mut self.out,
+            array: Array::new(),
+            element: None,
+        }))
+    }
+}
 
-        impl<'a> ArrayBuilder<'a> {
-            fn shift(&mut self) {
-                if let Some(e) = self.element.take() {
-                    self.array.push(e);
-                }
-            }
-        }
+struct ArrayBuilder<'a> {
+    out: &'a mut Option<Array>,
+    array: Array,
+    element: Option<Value>,
+}
 
-        impl<'a> Seq for ArrayBuilder<'a> {
-            fn element(&mut self) -> Result<&mut dyn Visitor> {
-                self.shift();
-                Ok(Deserialize::begin(&mut self.element))
-            }
-
-            fn finish(&mut self) -> Result<()> {
-                self.shift();
-                *self.out = Some(mem::replace(&mut self.array, Array::new()));
-                Ok(())
-            }
+impl<'a> ArrayBuilder<'a> {
+    fn shift(&mut self) {
+        if let Some(e) = self.element.take() {
+            self.array.push(e);
         }
+    }
+}
 
-        Place::new(out)
+impl<'a, 'de> Seq<'de> for ArrayBuilder<'a> {
+    fn element(&mut self) -> Result<&mut dyn Visitor<'de>> {
+        self.shift();
+        Ok(Deserialize::begin(&mut self.element))
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.shift();
+        *self.out = Some(mem::replace(&mut self.array, Array::new()));
+        Ok(())
     }
 }
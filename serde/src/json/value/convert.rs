@@ -0,0 +1,215 @@
+use super::{Array, Number, Object, Value};
+use crate::de::{DeserializeOwned, Map as DeMap, Seq as DeSeq, Visitor};
+use crate::non_unique_box::NonuniqueBox;
+use crate::path::Path;
+use crate::ser::{Fragment, Map, Seq, Serialize};
+use crate::{extend_lifetime, extend_lifetime_impl};
+use crate::{Error, ErrorKind, Result};
+
+use core::mem;
+use core::ptr::NonNull;
+use std::borrow::Cow;
+use std::boxed::Box;
+use std::string::String;
+use std::vec::Vec;
+
+/// Convert any serializable type into a [`Value`], the same value `from_str(&to_string(value))` would give.
+///
+/// Non recursive impls using a stack. `NaN` and infinite floats become `Value::Null` like in [`to_string`].
+///
+/// [`to_string`]: crate::json::to_string
+pub fn to_value<T: ?Sized + Serialize>(value: &T) -> Value {
+    match value_of(value, false) {
+        Ok(value) => value,
+        Err(_) => unreachable!("only rejected non-finite floats fail to convert"),
+    }
+}
+
+/// Same as [`to_value`], failing on `NaN` and infinite floats when `reject_non_finite` is set.
+pub(crate) fn value_of<T: ?Sized + Serialize>(value: &T, reject_non_finite: bool) -> Result<Value> {
+    let mut stack = SerStack(Vec::new());
+    let mut fragment = value.begin();
+
+    loop {
+        let mut value = match fragment {
+            Fragment::Null => Value::Null,
+            Fragment::Bool(b) => Value::Bool(b),
+            Fragment::Str(s) => Value::String(s.into_owned()),
+            Fragment::U64(u) => Value::Number(Number::U64(u)),
+            Fragment::I64(i) => Value::Number(Number::I64(i)),
+            Fragment::U128(u) => Value::Number(Number::U128(u)),
+            Fragment::I128(i) => Value::Number(Number::I128(i)),
+            Fragment::F32(f) if f.is_finite() => Value::Number(Number::F64(widen_f32(f))),
+            Fragment::F64(f) if f.is_finite() => Value::Number(Number::F64(f)),
+            Fragment::F32(_) | Fragment::F64(_) => match reject_non_finite {
+                false => Value::Null,
+                true => return Err(Error::new(ErrorKind::NonFiniteFloat)),
+            },
+            Fragment::Seq(mut seq) => match unsafe { extend_lifetime!(seq.next() as Option<&dyn Serialize>) } {
+                Some(first) => {
+                    stack.0.push(SerLayer::Seq(seq, Array::new()));
+                    fragment = first.begin();
+                    continue;
+                }
+                None => Value::Array(Array::new()),
+            },
+            Fragment::Map(mut map) => match unsafe { extend_lifetime!(map.next() as Option<(Cow<str>, &dyn Serialize)>) } {
+                Some((key, first)) => {
+                    stack.0.push(SerLayer::Map(map, Object::new(), key.into_owned()));
+                    fragment = first.begin();
+                    continue;
+                }
+                None => Value::Object(Object::new()),
+            },
+        };
+
+        loop {
+            match stack.0.last_mut() {
+                Some(SerLayer::Seq(seq, array)) => {
+                    array.push(value);
+                    if let Some(next) = unsafe { extend_lifetime!(seq.next() as Option<&dyn Serialize>) } {
+                        fragment = next.begin();
+                        break;
+                    }
+                }
+                Some(SerLayer::Map(map, object, key)) => {
+                    object.insert(mem::take(key), value);
+                    if let Some((next_key, next)) = unsafe { extend_lifetime!(map.next() as Option<(Cow<str>, &dyn Serialize)>) } {
+                        *key = next_key.into_owned();
+                        fragment = next.begin();
+                        break;
+                    }
+                }
+                None => return Ok(value),
+            }
+            value = match stack.0.pop() {
+                Some(SerLayer::Seq(_, array)) => Value::Array(array),
+                Some(SerLayer::Map(_, object, _)) => Value::Object(object),
+                None => unreachable!("the stack was checked to be non empty"),
+            };
+        }
+    }
+}
+
+/// Convert a [`Value`] into any deserializable type, failing like [`from_str`] does on the same JSON.
+///
+/// [`from_str`]: crate::json::from_str
+pub fn from_value<T: DeserializeOwned>(value: Value) -> Result<T> {
+    let mut out = None;
+    let mut path = Path::new();
+    visit(value, &mut path, T::begin(&mut out)).map_err(|error| error.with_path(path.to_string()))?;
+    out.ok_or_else(|| Error::new(ErrorKind::TypeMismatch))
+}
+
+/// Go through the shortest text form so that `150.99f32` gives `150.99` and not its widened `f64` value.
+pub(super) fn widen_f32(f: f32) -> f64 {
+    match ryu::Buffer::new().format_finite(f).parse() {
+        Ok(widened) => widened,
+        Err(_) => f64::from(f),
+    }
+}
+
+/// Layers currently open while building a value, the entries built so far are kept next to their iterator.
+struct SerStack<'a>(Vec<SerLayer<'a>>);
+
+enum SerLayer<'a> {
+    Seq(Box<dyn Seq + 'a>, Array),
+    Map(Box<dyn Map + 'a>, Object, String),
+}
+
+impl<'a> Drop for SerStack<'a> {
+    fn drop(&mut self) {
+        while self.0.pop().is_some() {}
+    }
+}
+
+/// Layers currently open while visiting a value, with the children not visited yet.
+struct DeStack<'de>(Vec<DeLayer<'de>>);
+
+enum DeLayer<'de> {
+    Seq(NonuniqueBox<dyn DeSeq<'de> + 'de>, <Array as IntoIterator>::IntoIter),
+    Map(NonuniqueBox<dyn DeMap<'de> + 'de>, <Object as IntoIterator>::IntoIter),
+}
+
+impl<'de> Drop for DeStack<'de> {
+    fn drop(&mut self) {
+        while self.0.pop().is_some() {}
+    }
+}
+
+pub(crate) fn visit<'de>(mut value: Value, path: &mut Path, visitor: &mut dyn Visitor<'de>) -> Result<()> {
+    let mut stack = DeStack(Vec::new());
+    let visitor = NonNull::from(visitor);
+    let mut visitor = unsafe { extend_lifetime!(visitor as NonNull<dyn Visitor<'de>>) };
+
+    loop {
+        let visitor_mut = unsafe { &mut *visitor.as_ptr() };
+        match value {
+            Value::Null => visitor_mut.null()?,
+            Value::Bool(b) => visitor_mut.boolean(b)?,
+            Value::Number(Number::U64(n)) => visitor_mut.nonnegative(n)?,
+            // Parsed values only hold negative `I64`, built ones may hold any.
+            Value::Number(Number::I64(n)) if n >= 0 => visitor_mut.nonnegative(n as u64)?,
+            Value::Number(Number::I64(n)) => visitor_mut.negative(n)?,
+            Value::Number(Number::U128(n)) => visit_u128(n, visitor_mut)?,
+            Value::Number(Number::I128(n)) if n >= 0 => visit_u128(n as u128, visitor_mut)?,
+            Value::Number(Number::I128(n)) => match i64::try_from(n) {
+                Ok(n) => visitor_mut.negative(n)?,
+                Err(_) => visitor_mut.big_negative(n)?,
+            },
+            Value::Number(Number::F64(n)) => visitor_mut.float(n)?,
+            Value::String(s) => visitor_mut.string(&s)?,
+            Value::Array(array) => {
+                let seq = visitor_mut.seq()?;
+                path.push_seq();
+                stack.0.push(DeLayer::Seq(NonuniqueBox::from(seq), array.into_iter()));
+            }
+            Value::Object(object) => {
+                let map = visitor_mut.map()?;
+                path.push_map();
+                stack.0.push(DeLayer::Map(NonuniqueBox::from(map), object.into_iter()));
+            }
+        }
+
+        loop {
+            match stack.0.last_mut() {
+                Some(DeLayer::Seq(seq, elements)) => match elements.next() {
+                    Some(element) => {
+                        path.next_element();
+                        let next = NonNull::from(seq.element()?);
+                        visitor = unsafe { extend_lifetime!(next as NonNull<dyn Visitor<'de>>) };
+                        value = element;
+                        break;
+                    }
+                    None => {
+                        path.pop();
+                        seq.finish()?;
+                    }
+                },
+                Some(DeLayer::Map(map, entries)) => match entries.next() {
+                    Some((key, entry)) => {
+                        path.set_key(&key);
+                        let next = NonNull::from(map.key(&key)?);
+                        visitor = unsafe { extend_lifetime!(next as NonNull<dyn Visitor<'de>>) };
+                        value = entry;
+                        break;
+                    }
+                    None => {
+                        path.pop();
+                        map.finish()?;
+                    }
+                },
+                None => return Ok(()),
+            }
+            stack.0.pop();
+        }
+    }
+}
+
+/// Built values may hold 128-bit numbers within the 64-bit range, visited like parsed ones.
+fn visit_u128(n: u128, visitor: &mut dyn Visitor<'_>) -> Result<()> {
+    match u64::try_from(n) {
+        Ok(n) => visitor.nonnegative(n),
+        Err(_) => visitor.big_nonnegative(n),
+    }
+}
@@ -0,0 +1,187 @@
+use super::convert::widen_f32;
+use super::{Array, Number, Object, Value};
+
+use std::borrow::Cow;
+use std::string::String;
+use std::vec::Vec;
+
+macro_rules! unsigned {
+    ($($ty:ident)*) => {
+        $(
+            impl From<$ty> for Value {
+                fn from(n: $ty) -> Self {
+                    Value::Number(Number::U64(n as u64))
+                }
+            }
+        )*
+    };
+}
+
+unsigned!(u8 u16 u32 u64 usize);
+
+/// Nonnegative integers are stored as `U64` like the parser does.
+macro_rules! signed {
+    ($($ty:ident)*) => {
+        $(
+            impl From<$ty> for Value {
+                fn from(i: $ty) -> Self {
+                    match u64::try_from(i) {
+                        Ok(n) => Value::Number(Number::U64(n)),
+                        Err(_) => Value::Number(Number::I64(i as i64)),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+signed!(i8 i16 i32 i64 isize);
+
+/// 128-bit integers are stored in 64-bit variants when they fit.
+impl From<u128> for Value {
+    fn from(n: u128) -> Self {
+        match u64::try_from(n) {
+            Ok(n) => Value::Number(Number::U64(n)),
+            Err(_) => Value::Number(Number::U128(n)),
+        }
+    }
+}
+
+impl From<i128> for Value {
+    fn from(i: i128) -> Self {
+        match (u128::try_from(i), i64::try_from(i)) {
+            (Ok(n), _) => Value::from(n),
+            (Err(_), Ok(i)) => Value::Number(Number::I64(i)),
+            (Err(_), Err(_)) => Value::Number(Number::I128(i)),
+        }
+    }
+}
+
+/// `NaN` and infinities become `Value::Null` like in [`to_value`](super::to_value).
+impl From<f64> for Value {
+    fn from(f: f64) -> Self {
+        match f.is_finite() {
+            true => Value::Number(Number::F64(f)),
+            false => Value::Null,
+        }
+    }
+}
+
+impl From<f32> for Value {
+    fn from(f: f32) -> Self {
+        match f.is_finite() {
+            true => Value::Number(Number::F64(widen_f32(f))),
+            false => Value::Null,
+        }
+    }
+}
+
+impl From<bool> for Value {
+    fn from(b: bool) -> Self {
+        Value::Bool(b)
+    }
+}
+
+impl From<()> for Value {
+    fn from(_: ()) -> Self {
+        Value::Null
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Value::String(s)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Value::String(s.to_owned())
+    }
+}
+
+impl<'a> From<Cow<'a, str>> for Value {
+    fn from(s: Cow<'a, str>) -> Self {
+        Value::String(s.into_owned())
+    }
+}
+
+impl From<Number> for Value {
+    fn from(number: Number) -> Self {
+        Value::Number(number)
+    }
+}
+
+impl From<Array> for Value {
+    fn from(array: Array) -> Self {
+        Value::Array(array)
+    }
+}
+
+impl From<Object> for Value {
+    fn from(object: Object) -> Self {
+        Value::Object(object)
+    }
+}
+
+impl<T: Into<Value>> From<Vec<T>> for Value {
+    fn from(elements: Vec<T>) -> Self {
+        Value::Array(elements.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(option: Option<T>) -> Self {
+        match option {
+            Some(value) => value.into(),
+            None => Value::Null,
+        }
+    }
+}
+
+macro_rules! partial_eq {
+    ($($ty:ident)* => $as:ident) => {
+        $(
+            impl PartialEq<$ty> for Value {
+                fn eq(&self, other: &$ty) -> bool {
+                    self.$as() == Some(*other as _)
+                }
+            }
+        )*
+    };
+}
+
+partial_eq!(u8 u16 u32 u64 usize => as_u64);
+partial_eq!(i8 i16 i32 i64 isize => as_i64);
+partial_eq!(bool => as_bool);
+
+/// Integers are never equal to a float, like between numbers.
+impl PartialEq<f64> for Value {
+    fn eq(&self, other: &f64) -> bool {
+        matches!(self, Value::Number(Number::F64(f)) if f == other)
+    }
+}
+
+impl PartialEq<f32> for Value {
+    fn eq(&self, other: &f32) -> bool {
+        other.is_finite() && *self == widen_f32(*other)
+    }
+}
+
+impl PartialEq<str> for Value {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == Some(other)
+    }
+}
+
+impl PartialEq<&str> for Value {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == Some(*other)
+    }
+}
+
+impl PartialEq<String> for Value {
+    fn eq(&self, other: &String) -> bool {
+        self.as_str() == Some(other.as_str())
+    }
+}
@@ -3,11 +3,12 @@ use crate::de::{Deserialize, Map, Seq, Visitor};
 use crate::ser::{Fragment, Serialize};
 use crate::{Place, Result};
 
-use core::fmt::{self, Debug};
+use core::fmt::{self, Debug, Display};
 use core::mem;
 use core::str;
 use std::borrow::{Cow, ToOwned};
 use std::boxed::Box;
+use std::vec::Vec;
 
 /// Value for JSON objects.
 ///
@@ -42,8 +43,121 @@ impl Debug for Value {
     }
 }
 
+/// Written as JSON, `{:#}` indents it like [`to_string_pretty`](crate::json::to_string_pretty).
+impl Display for Value {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let text = match formatter.alternate() {
+            false => crate::json::to_string(self),
+            true => crate::json::to_string_pretty(self),
+        };
+        formatter.write_str(&text)
+    }
+}
+
+/// Numbers compare by value between `U64` and `I64`, objects regardless of the order of their entries.
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        equal(vec![(self, other)])
+    }
+}
+
+/// Compare pairs of values with a stack so it is safe to compare arbitrarily deeply nested instances.
+pub(super) fn equal<'a>(mut pairs: Vec<(&'a Value, &'a Value)>) -> bool {
+    while let Some(pair) = pairs.pop() {
+        match pair {
+            (Value::Null, Value::Null) => {}
+            (Value::Bool(a), Value::Bool(b)) if a == b => {}
+            (Value::Number(a), Value::Number(b)) if a == b => {}
+            (Value::String(a), Value::String(b)) if a == b => {}
+            (Value::Array(a), Value::Array(b)) if a.len() == b.len() => pairs.extend(a.iter().zip(b.iter())),
+            (Value::Object(a), Value::Object(b)) if a.len() == b.len() => {
+                for (key, a) in a.iter() {
+                    match b.get(key) {
+                        Some(b) => pairs.push((a, b)),
+                        None => return false,
+                    }
+                }
+            }
+            _ => return false,
+        }
+    }
+    true
+}
+
+impl Value {
+    pub fn is_null(&self) -> bool {
+        matches!(self, Value::Null)
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    /// The number if it is an integer that fits in a `u64`.
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Value::Number(number) => number.as_u64(),
+            _ => None,
+        }
+    }
+
+    /// The number if it is an integer that fits in an `i64`.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Value::Number(number) => number.as_i64(),
+            _ => None,
+        }
+    }
+
+    /// Any number, integers that are too large are rounded.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Number(number) => Some(number.as_f64()),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&Array> {
+        match self {
+            Value::Array(array) => Some(array),
+            _ => None,
+        }
+    }
+
+    pub fn as_array_mut(&mut self) -> Option<&mut Array> {
+        match self {
+            Value::Array(array) => Some(array),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&Object> {
+        match self {
+            Value::Object(object) => Some(object),
+            _ => None,
+        }
+    }
+
+    pub fn as_object_mut(&mut self) -> Option<&mut Object> {
+        match self {
+            Value::Object(object) => Some(object),
+            _ => None,
+        }
+    }
+}
+
 impl Serialize for Value {
-    fn begin(&self) -> Fragment {
+    fn begin(&self) -> Fragment<'_> {
         match self {
             Value::Null => Fragment::Null,
             Value::Bool(b) => Fragment::Bool(*b),
@@ -55,113 +169,123 @@ impl Serialize for Value {
     }
 }
 
-impl Deserialize for Value {
-    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor {
-        impl Visitor for Place<Value> {
-            fn null(&mut self) -> Result<()> {
-                self.out = Some(Value::Null);
-                Ok(())
-            }
+impl<'de> Deserialize<'de> for Value {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+        Place::new(out)
+    }
+}
 
-            fn boolean(&mut self, b: bool) -> Result<()> {
-                self.out = Some(Value::Bool(b));
-                Ok(())
-            }
+impl<'de> Visitor<'de> for Place<Value> {
+    fn null(&mut self) -> Result<()> {
+        self.out = Some(Value::Null);
+        Ok(())
+    }
 
-            fn string(&mut self, s: &str) -> Result<()> {
-                self.out = Some(Value::String(s.to_owned()));
-                Ok(())
-            }
+    fn boolean(&mut self, b: bool) -> Result<()> {
+        self.out = Some(Value::Bool(b));
+        Ok(())
+    }
 
-            fn negative(&mut self, n: i64) -> Result<()> {
-                self.out = Some(Value::Number(Number::I64(n)));
-                Ok(())
-            }
+    fn string(&mut self, s: &str) -> Result<()> {
+        self.out = Some(Value::String(s.to_owned()));
+        Ok(())
+    }
 
-            fn nonnegative(&mut self, n: u64) -> Result<()> {
-                self.out = Some(Value::Number(Number::U64(n)));
-                Ok(())
-            }
+    fn negative(&mut self, n: i64) -> Result<()> {
+        self.out = Some(Value::Number(Number::I64(n)));
+        Ok(())
+    }
 
-            fn float(&mut self, n: f64) -> Result<()> {
-                self.out = Some(Value::Number(Number::F64(n)));
-                Ok(())
-            }
+    fn nonnegative(&mut self, n: u64) -> Result<()> {
+        self.out = Some(Value::Number(Number::U64(n)));
+        Ok(())
+    }
 
-            fn seq(&mut self) -> Result<Box<dyn Seq + '_>> {
-                Ok(Box::new(ArrayBuilder {
-                    out: &mut self.out,
-                    array: Array::new(),
-                    element: None,
-                }))
-            }
+    fn big_negative(&mut self, n: i128) -> Result<()> {
+        self.out = Some(Value::Number(Number::I128(n)));
+        Ok(())
+    }
 
-            fn map(&mut self) -> Result<Box<dyn Map + '_>> {
-                Ok(Box::new(ObjectBuilder {
-                    out: &mut self.out,
-                    object: Object::new(),
-                    key: None,
-                    value: None,
-                }))
-            }
-        }
+    fn big_nonnegative(&mut self, n: u128) -> Result<()> {
+        self.out = Some(Value::Number(Number::U128(n)));
+        Ok(())
+    }
 
-        struct ArrayBuilder<'a> {
-            out: &'a mut Option<Value>,
-            array: Array,
-            element: Option<Value>,
-        }
+    fn float(&mut self, n: f64) -> Result<()> {
+        self.out = Some(Value::Number(Number::F64(n)));
+        Ok(())
+    }
 
-        impl<'a> ArrayBuilder<'a> {
-            fn shift(&mut self) {
-                if let Some(e) = self.element.take() {
-                    self.array.push(e);
-                }
-            }
-        }
+    fn seq(&mut self) -> Result<Box<dyn Seq<'de> + '_>> {
+        Ok(Box::new(ArrayBuilder {
+            out: &mut self.out,
+            array: Array::new(),
+            element: None,
+        }))
+    }
 
-        impl<'a> Seq for ArrayBuilder<'a> {
-            fn element(&mut self) -> Result<&mut dyn Visitor> {
-                self.shift();
-                Ok(Deserialize::begin(&mut self.element))
-            }
+    fn map(&mut self) -> Result<Box<dyn Map<'de> + '_>> {
+        Ok(Box::new(ObjectBuilder {
+            out: &mut self.out,
+            object: Object::new(),
+            key: None,
+            value: None,
+        }))
+    }
+}
 
-            fn finish(&mut self) -> Result<()> {
-                self.shift();
-                *self.out = Some(Value::Array(mem::replace(&mut self.array, Array::new())));
-                Ok(())
-            }
-        }
+struct ArrayBuilder<'a> {
+    out: &'a mut Option<Value>,
+    array: Array,
+    element: Option<Value>,
+}
 
-        struct ObjectBuilder<'a> {
-            out: &'a mut Option<Value>,
-            object: Object,
-            key: Option<String>,
-            value: Option<Value>,
+impl<'a> ArrayBuilder<'a> {
+    fn shift(&mut self) {
+        if let Some(e) = self.element.take() {
+            self.array.push(e);
         }
+    }
+}
 
-        impl<'a> ObjectBuilder<'a> {
-            fn shift(&mut self) {
-                if let (Some(k), Some(v)) = (self.key.take(), self.value.take()) {
-                    self.object.insert(k, v);
-                }
-            }
-        }
+impl<'a, 'de> Seq<'de> for ArrayBuilder<'a> {
+    fn element(&mut self) -> Result<&mut dyn Visitor<'de>> {
+        self.shift();
+        Ok(Deserialize::begin(&mut self.element))
+    }
 
-        impl<'a> Map for ObjectBuilder<'a> {
-            fn key(&mut self, k: &str) -> Result<&mut dyn Visitor> {
-                self.shift();
-                self.key = Some(k.to_owned());
-                Ok(Deserialize::begin(&mut self.value))
-            }
+    fn finish(&mut self) -> Result<()> {
+        self.shift();
+        *self.out = Some(Value::Array(mem::replace(&mut self.array, Array::new())));
+        Ok(())
+    }
+}
 
-            fn finish(&mut self) -> Result<()> {
-                self.shift();
-                *self.out = Some(Value::Object(mem::replace(&mut self.object, Object::new())));
-                Ok(())
-            }
+struct ObjectBuilder<'a> {
+    out: &'a mut Option<Value>,
+    object: Object,
+    key: Option<String>,
+    value: Option<Value>,
+}
+
+impl<'a> ObjectBuilder<'a> {
+    fn shift(&mut self) {
+        if let (Some(k), Some(v)) = (self.key.take(), self.value.take()) {
+            self.object.insert(k, v);
         }
+    }
+}
 
-        Place::new(out)
+impl<'a, 'de> Map<'de> for ObjectBuilder<'a> {
+    fn key(&mut self, k: &str) -> Result<&mut dyn Visitor<'de>> {
+        self.shift();
+        self.key = Some(k.to_owned());
+        Ok(Deserialize::begin(&mut self.value))
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.shift();
+        *self.out = Some(Value::Object(mem::replace(&mut self.object, Object::new())));
+        Ok(())
     }
 }
@@ -0,0 +1,119 @@
+use super::Value;
+
+use core::ops;
+use std::string::String;
+
+/// Key of an object entry or position of an array element, used by [`Value::get`] and `value[index]`.
+pub trait ValueIndex {
+    fn index_into<'v>(&self, value: &'v Value) -> Option<&'v Value>;
+
+    fn index_into_mut<'v>(&self, value: &'v mut Value) -> Option<&'v mut Value>;
+}
+
+impl ValueIndex for usize {
+    fn index_into<'v>(&self, value: &'v Value) -> Option<&'v Value> {
+        value.as_array()?.get(*self)
+    }
+
+    fn index_into_mut<'v>(&self, value: &'v mut Value) -> Option<&'v mut Value> {
+        value.as_array_mut()?.get_mut(*self)
+    }
+}
+
+impl ValueIndex for str {
+    fn index_into<'v>(&self, value: &'v Value) -> Option<&'v Value> {
+        value.as_object()?.get(self)
+    }
+
+    fn index_into_mut<'v>(&self, value: &'v mut Value) -> Option<&'v mut Value> {
+        value.as_object_mut()?.get_mut(self)
+    }
+}
+
+impl ValueIndex for String {
+    fn index_into<'v>(&self, value: &'v Value) -> Option<&'v Value> {
+        self.as_str().index_into(value)
+    }
+
+    fn index_into_mut<'v>(&self, value: &'v mut Value) -> Option<&'v mut Value> {
+        self.as_str().index_into_mut(value)
+    }
+}
+
+impl<T: ?Sized + ValueIndex> ValueIndex for &T {
+    fn index_into<'v>(&self, value: &'v Value) -> Option<&'v Value> {
+        (**self).index_into(value)
+    }
+
+    fn index_into_mut<'v>(&self, value: &'v mut Value) -> Option<&'v mut Value> {
+        (**self).index_into_mut(value)
+    }
+}
+
+static NULL: Value = Value::Null;
+
+/// Missing keys, out of bounds positions and indexing into anything else than an object or an array give
+/// `Value::Null` instead of panicking, so lookups can be chained: `value["flights"][0]["id"]`.
+impl<I: ValueIndex> ops::Index<I> for Value {
+    type Output = Value;
+
+    fn index(&self, index: I) -> &Value {
+        index.index_into(self).unwrap_or(&NULL)
+    }
+}
+
+impl Value {
+    pub fn get<I: ValueIndex>(&self, index: I) -> Option<&Value> {
+        index.index_into(self)
+    }
+
+    pub fn get_mut<I: ValueIndex>(&mut self, index: I) -> Option<&mut Value> {
+        index.index_into_mut(self)
+    }
+
+    /// Look up a value by [JSON pointer](https://tools.ietf.org/html/rfc6901), e.g. `/flights/0/id`. The empty
+    /// pointer is the value itself.
+    pub fn pointer(&self, pointer: &str) -> Option<&Value> {
+        if pointer.is_empty() {
+            return Some(self);
+        }
+        pointer
+            .strip_prefix('/')?
+            .split('/')
+            .map(unescape_token)
+            .try_fold(self, |target, token| match target {
+                Value::Object(object) => object.get(&token),
+                Value::Array(array) => array.get(parse_position(&token)?),
+                _ => None,
+            })
+    }
+
+    /// Same as [`pointer`](Value::pointer) with a mutable reference.
+    pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut Value> {
+        if pointer.is_empty() {
+            return Some(self);
+        }
+        pointer
+            .strip_prefix('/')?
+            .split('/')
+            .map(unescape_token)
+            .try_fold(self, |target, token| match target {
+                Value::Object(object) => object.get_mut(&token),
+                Value::Array(array) => array.get_mut(parse_position(&token)?),
+                _ => None,
+            })
+    }
+}
+
+/// `~1` stands for `/` and `~0` for `~` inside a pointer token.
+fn unescape_token(token: &str) -> String {
+    token.replace("~1", "/").replace("~0", "~")
+}
+
+/// Array positions are written in decimal without sign nor leading zero.
+fn parse_position(token: &str) -> Option<usize> {
+    if token.starts_with('+') || (token.starts_with('0') && token.len() > 1) {
+        return None;
+    }
+    token.parse().ok()
+}
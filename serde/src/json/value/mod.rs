@@ -7,6 +7,14 @@ pub use self::numbers::Number;
 mod object;
 pub use self::object::Object;
 
-
 mod impls;
 pub use impls::Value;
+
+mod convert;
+pub use self::convert::{from_value, to_value};
+pub(crate) use self::convert::{value_of, visit};
+
+mod index;
+pub use self::index::ValueIndex;
+
+mod from;
@@ -9,48 +9,131 @@ use core::fmt::{self, Display};
 pub enum Number {
     U64(u64),
     I64(i64),
+    /// Integers above `u64::MAX`, parsed numbers only use it for those.
+    U128(u128),
+    /// Integers below `i64::MIN`, parsed numbers only use it for those.
+    I128(i128),
     F64(f64),
 }
 
+impl Number {
+    pub fn as_u64(&self) -> Option<u64> {
+        match *self {
+            Number::U64(n) => Some(n),
+            Number::I64(i) => u64::try_from(i).ok(),
+            Number::U128(n) => u64::try_from(n).ok(),
+            Number::I128(i) => u64::try_from(i).ok(),
+            Number::F64(_) => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            Number::U64(n) => i64::try_from(n).ok(),
+            Number::I64(i) => Some(i),
+            Number::U128(n) => i64::try_from(n).ok(),
+            Number::I128(i) => i64::try_from(i).ok(),
+            Number::F64(_) => None,
+        }
+    }
+
+    pub fn as_u128(&self) -> Option<u128> {
+        match *self {
+            Number::U64(n) => Some(n as u128),
+            Number::I64(i) => u128::try_from(i).ok(),
+            Number::U128(n) => Some(n),
+            Number::I128(i) => u128::try_from(i).ok(),
+            Number::F64(_) => None,
+        }
+    }
+
+    pub fn as_i128(&self) -> Option<i128> {
+        match *self {
+            Number::U64(n) => Some(n as i128),
+            Number::I64(i) => Some(i as i128),
+            Number::U128(n) => i128::try_from(n).ok(),
+            Number::I128(i) => Some(i),
+            Number::F64(_) => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> f64 {
+        match *self {
+            Number::U64(n) => n as f64,
+            Number::I64(i) => i as f64,
+            Number::U128(n) => n as f64,
+            Number::I128(i) => i as f64,
+            Number::F64(f) => f,
+        }
+    }
+}
+
+/// Integers are equal whatever their variant, they are never equal to a float.
+impl PartialEq for Number {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Number::F64(a), Number::F64(b)) => a == b,
+            (Number::F64(_), _) | (_, Number::F64(_)) => false,
+            _ => self.as_i128() == other.as_i128() && self.as_u128() == other.as_u128(),
+        }
+    }
+}
+
 impl Display for Number {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Number::U64(n) => formatter.write_str(&n.to_string()),
-            Number::I64(i) => formatter.write_str(&i.to_string()),
-            Number::F64(f) => formatter.write_str(&f.to_string()),
+            Number::U64(n) => Display::fmt(n, formatter),
+            Number::I64(i) => Display::fmt(i, formatter),
+            Number::U128(n) => Display::fmt(n, formatter),
+            Number::I128(i) => Display::fmt(i, formatter),
+            // same as the JSON serializer
+            Number::F64(f) if f.is_finite() => formatter.write_str(ryu::Buffer::new().format_finite(*f)),
+            Number::F64(_) => formatter.write_str("null"),
         }
     }
 }
 
 impl Serialize for Number {
-    fn begin(&self) -> Fragment {
+    fn begin(&self) -> Fragment<'_> {
         match self {
             Number::U64(n) => Fragment::U64(*n),
             Number::I64(i) => Fragment::I64(*i),
+            Number::U128(n) => Fragment::U128(*n),
+            Number::I128(i) => Fragment::I128(*i),
             Number::F64(f) => Fragment::F64(*f),
         }
     }
 }
 
-impl Deserialize for Number {
-    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor {
-        impl Visitor for Place<Number> {
-            fn negative(&mut self, i: i64) -> Result<()> {
-                self.out = Some(Number::I64(i));
-                Ok(())
-            }
+impl<'de> Deserialize<'de> for Number {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+        Place::new(out)
+    }
+}
 
-            fn nonnegative(&mut self, n: u64) -> Result<()> {
-                self.out = Some(Number::U64(n));
-                Ok(())
-            }
+impl<'de> Visitor<'de> for Place<Number> {
+    fn negative(&mut self, i: i64) -> Result<()> {
+        self.out = Some(Number::I64(i));
+        Ok(())
+    }
 
-            fn float(&mut self, f: f64) -> Result<()> {
-                self.out = Some(Number::F64(f));
-                Ok(())
-            }
-        }
+    fn nonnegative(&mut self, n: u64) -> Result<()> {
+        self.out = Some(Number::U64(n));
+        Ok(())
+    }
 
-        Place::new(out)
+    fn big_negative(&mut self, i: i128) -> Result<()> {
+        self.out = Some(Number::I128(i));
+        Ok(())
+    }
+
+    fn big_nonnegative(&mut self, n: u128) -> Result<()> {
+        self.out = Some(Number::U128(n));
+        Ok(())
+    }
+
+    fn float(&mut self, f: f64) -> Result<()> {
+        self.out = Some(Number::F64(f));
+        Ok(())
     }
 }
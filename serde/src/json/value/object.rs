@@ -11,12 +11,19 @@ use core::ptr;
 use core::str;
 use std::borrow::{Cow, ToOwned};
 use std::boxed::Box;
-use std::collections::{btree_map, BTreeMap};
 use std::string::String;
 
+#[cfg(not(feature = "preserve_order"))]
+use std::collections::{btree_map as entries, BTreeMap as Entries};
+
+#[cfg(feature = "preserve_order")]
+use indexmap::{map as entries, IndexMap as Entries};
+
+/// Entries of a JSON object, sorted by key. With the `preserve_order` feature they are kept in insertion order
+/// instead, so that a parsed object is written back unchanged.
 #[derive(Clone, Default)]
 pub struct Object {
-    inner: BTreeMap<String, Value>,
+    inner: Entries<String, Value>,
 }
 
 impl Drop for Object {
@@ -27,19 +34,19 @@ impl Drop for Object {
     }
 }
 
-fn take(object: Object) -> BTreeMap<String, Value> {
+fn take(object: Object) -> Entries<String, Value> {
     let object = ManuallyDrop::new(object);
     unsafe { ptr::read(&object.inner) }
 }
 
 impl Object {
     pub fn new() -> Self {
-        Object { inner: BTreeMap::new() }
+        Object { inner: Entries::new() }
     }
 }
 
 impl Deref for Object {
-    type Target = BTreeMap<String, Value>;
+    type Target = Entries<String, Value>;
 
     fn deref(&self) -> &Self::Target {
         &self.inner
@@ -54,7 +61,7 @@ impl DerefMut for Object {
 
 impl IntoIterator for Object {
     type Item = (String, Value);
-    type IntoIter = <BTreeMap<String, Value> as IntoIterator>::IntoIter;
+    type IntoIter = <Entries<String, Value> as IntoIterator>::IntoIter;
 
     fn into_iter(self) -> Self::IntoIter {
         take(self).into_iter()
@@ -63,7 +70,7 @@ impl IntoIterator for Object {
 
 impl<'a> IntoIterator for &'a Object {
     type Item = (&'a String, &'a Value);
-    type IntoIter = <&'a BTreeMap<String, Value> as IntoIterator>::IntoIter;
+    type IntoIter = <&'a Entries<String, Value> as IntoIterator>::IntoIter;
 
     fn into_iter(self) -> Self::IntoIter {
         self.iter()
@@ -72,7 +79,7 @@ impl<'a> IntoIterator for &'a Object {
 
 impl<'a> IntoIterator for &'a mut Object {
     type Item = (&'a String, &'a mut Value);
-    type IntoIter = <&'a mut BTreeMap<String, Value> as IntoIterator>::IntoIter;
+    type IntoIter = <&'a mut Entries<String, Value> as IntoIterator>::IntoIter;
 
     fn into_iter(self) -> Self::IntoIter {
         self.iter_mut()
@@ -85,8 +92,21 @@ impl FromIterator<(String, Value)> for Object {
         I: IntoIterator<Item = (String, Value)>,
     {
         Object {
-            inner: BTreeMap::from_iter(iter),
+            inner: Entries::from_iter(iter),
+        }
+    }
+}
+
+impl PartialEq for Object {
+    fn eq(&self, other: &Self) -> bool {
+        let mut pairs = Vec::with_capacity(self.len());
+        for (key, value) in self.iter() {
+            match other.get(key) {
+                Some(other) => pairs.push((value, other)),
+                None => return false,
+            }
         }
+        self.len() == other.len() && super::impls::equal(pairs)
     }
 }
 
@@ -99,7 +119,7 @@ impl Debug for Object {
 
 impl Serialize for Object {
     fn begin(&self) -> Fragment {
-        struct ObjectIter<'a>(btree_map::Iter<'a, String, Value>);
+        struct ObjectIter<'a>(entries::Iter<'a, String, Value>);
 
         impl<'a> ser::Map for ObjectIter<'a> {
             fn next(&mut self) -> Option<(Cow<str>, &dyn Serialize)> {
@@ -112,10 +132,10 @@ impl Serialize for Object {
     }
 }
 
-impl Deserialize for Object {
-    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor {
-        impl Visitor for Place<Object> {
-            fn map(&mut self) -> Result<Box<dyn Map + '_>> {
+impl<'de> Deserialize<'de> for Object {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+        impl<'de> Visitor<'de> for Place<Object> {
+            fn map(&mut self) -> Result<Box<dyn Map<'de> + '_>> {
                 Ok(Box::new(ObjectBuilder {
                     out: &mut self.out,
                     object: Object::new(),
@@ -140,8 +160,8 @@ impl Deserialize for Object {
             }
         }
 
-        impl<'a> Map for ObjectBuilder<'a> {
-            fn key(&mut self, k: &str) -> Result<&mut dyn Visitor> {
+        impl<'a, 'de> Map<'de> for ObjectBuilder<'a> {
+            fn key(&mut self, k: &str) -> Result<&mut dyn Visitor<'de>> {
                 self.shift();
                 self.key = Some(k.to_owned());
                 Ok(Deserialize::begin(&mut self.value))
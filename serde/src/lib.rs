@@ -1,9 +1,11 @@
+pub mod binary;
 pub mod de;
 pub mod json;
 pub mod ser;
 
 mod ignore;
 mod non_unique_box;
+mod path;
 mod types;
 
 #[path = "macro_export.rs"]
@@ -17,5 +19,5 @@ pub use derive_macro::*;
 
 pub use crate::de::Deserialize;
 pub use crate::ser::Serialize;
-pub use crate::types::{Error, Result, Place};
+pub use crate::types::{Error, ErrorKind, Limit, Result, Place};
 
@@ -3,8 +3,15 @@
 pub use std::borrow::Cow;
 pub use std::boxed::Box;
 pub use std::string::String;
+pub use core::default::Default;
+pub use core::mem::take;
 pub use core::option::Option::{self, None, Some};
 pub use core::result::Result::{Err, Ok};
+pub use std::vec::Vec;
+
+pub use crate::de::buffer::{buffer_entry, replay_entries, replay_map, Buffered};
+pub use crate::de::wrapper::{begin_wrapper, default_wrapper, Wrapper};
+pub use crate::ser::variant::{serialize_tuple, serialize_variant, Content, Tagging};
 
 pub use self::help::Str as str;
 pub use self::help::Usize as usize;
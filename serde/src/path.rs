@@ -0,0 +1,58 @@
+use core::fmt::{self, Display};
+
+/// Position of the value being deserialized inside the collections currently open, used to report errors.
+pub(crate) struct Path {
+    segments: Vec<Segment>,
+}
+
+enum Segment {
+    /// Number of elements handed out so far, the current element is the last one.
+    Seq(usize),
+    /// Key of the current entry.
+    Map(String),
+}
+
+impl Path {
+    pub fn new() -> Self {
+        Path { segments: Vec::new() }
+    }
+
+    pub fn push_seq(&mut self) {
+        self.segments.push(Segment::Seq(0));
+    }
+
+    pub fn push_map(&mut self) {
+        self.segments.push(Segment::Map(String::new()));
+    }
+
+    pub fn pop(&mut self) {
+        self.segments.pop();
+    }
+
+    pub fn next_element(&mut self) {
+        if let Some(Segment::Seq(count)) = self.segments.last_mut() {
+            *count += 1;
+        }
+    }
+
+    pub fn set_key(&mut self, key: &str) {
+        if let Some(Segment::Map(current)) = self.segments.last_mut() {
+            current.clear();
+            current.push_str(key);
+        }
+    }
+}
+
+/// Formatted as `a.b[0].c`.
+impl Display for Path {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        for (i, segment) in self.segments.iter().enumerate() {
+            match segment {
+                Segment::Seq(count) => write!(formatter, "[{}]", count.saturating_sub(1))?,
+                Segment::Map(key) if i == 0 => formatter.write_str(key)?,
+                Segment::Map(key) => write!(formatter, ".{}", key)?,
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,11 +1,21 @@
-use super::{Fragment, Seq, Serialize};
+use super::variant::serialize_tuple;
+use super::{Fragment, Key, Map, Seq, Serialize};
 use crate::StreamSerializer;
 
+use core::marker::PhantomData;
+use core::num::{
+    NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128, NonZeroU16, NonZeroU32,
+    NonZeroU64, NonZeroU8, NonZeroUsize,
+};
 use core::slice;
 use core::str;
 use std::borrow::{Cow, ToOwned};
 use std::boxed::Box;
-use std::string::String;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+use std::path::{Path, PathBuf};
+use std::string::{String, ToString};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use std::vec::Vec;
 
 impl Serialize for () {
@@ -32,6 +42,30 @@ impl Serialize for String {
     }
 }
 
+impl Key for str {
+    fn to_key(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self)
+    }
+}
+
+impl Key for String {
+    fn to_key(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self)
+    }
+}
+
+impl<T: ?Sized + Key> Key for &T {
+    fn to_key(&self) -> Cow<'_, str> {
+        (**self).to_key()
+    }
+}
+
+impl<T: ?Sized + ToOwned + Key> Key for Cow<'_, T> {
+    fn to_key(&self) -> Cow<'_, str> {
+        (**self).to_key()
+    }
+}
+
 macro_rules! unsigned {
     ($ty:ident) => {
         impl Serialize for $ty {
@@ -39,6 +73,12 @@ macro_rules! unsigned {
                 Fragment::U64(*self as u64)
             }
         }
+
+        impl Key for $ty {
+            fn to_key(&self) -> Cow<'_, str> {
+                Cow::Owned(self.to_string())
+            }
+        }
     };
 }
 unsigned!(u8);
@@ -54,6 +94,12 @@ macro_rules! signed {
                 Fragment::I64(*self as i64)
             }
         }
+
+        impl Key for $ty {
+            fn to_key(&self) -> Cow<'_, str> {
+                Cow::Owned(self.to_string())
+            }
+        }
     };
 }
 signed!(i8);
@@ -62,17 +108,84 @@ signed!(i32);
 signed!(i64);
 signed!(isize);
 
-macro_rules! float {
-    ($ty:ident) => {
+impl Serialize for u128 {
+    fn begin(&self) -> Fragment<'_> {
+        unsigned128(*self)
+    }
+}
+
+fn unsigned128(u: u128) -> Fragment<'static> {
+    match u64::try_from(u) {
+        Ok(u) => Fragment::U64(u),
+        Err(_) => Fragment::U128(u),
+    }
+}
+
+impl Key for u128 {
+    fn to_key(&self) -> Cow<'_, str> {
+        Cow::Owned(self.to_string())
+    }
+}
+
+impl Serialize for i128 {
+    fn begin(&self) -> Fragment<'_> {
+        signed128(*self)
+    }
+}
+
+fn signed128(i: i128) -> Fragment<'static> {
+    match i64::try_from(i) {
+        Ok(i) => Fragment::I64(i),
+        Err(_) if i > 0 => Fragment::U128(i as u128),
+        Err(_) => Fragment::I128(i),
+    }
+}
+
+impl Key for i128 {
+    fn to_key(&self) -> Cow<'_, str> {
+        Cow::Owned(self.to_string())
+    }
+}
+
+macro_rules! nonzero {
+    ($ty:ident $fragment:ident $int:ident) => {
         impl Serialize for $ty {
-            fn begin(&self) -> Fragment {
-                Fragment::F64(*self as f64)
+            fn begin(&self) -> Fragment<'_> {
+                $fragment(self.get() as $int)
+            }
+        }
+
+        impl Key for $ty {
+            fn to_key(&self) -> Cow<'_, str> {
+                Cow::Owned(self.to_string())
             }
         }
     };
 }
-float!(f32);
-float!(f64);
+nonzero!(NonZeroU8 unsigned128 u128);
+nonzero!(NonZeroU16 unsigned128 u128);
+nonzero!(NonZeroU32 unsigned128 u128);
+nonzero!(NonZeroU64 unsigned128 u128);
+nonzero!(NonZeroU128 unsigned128 u128);
+nonzero!(NonZeroUsize unsigned128 u128);
+nonzero!(NonZeroI8 signed128 i128);
+nonzero!(NonZeroI16 signed128 i128);
+nonzero!(NonZeroI32 signed128 i128);
+nonzero!(NonZeroI64 signed128 i128);
+nonzero!(NonZeroI128 signed128 i128);
+nonzero!(NonZeroIsize signed128 i128);
+
+impl Serialize for f32 {
+    fn begin(&self) -> Fragment<'_> {
+        Fragment::F32(*self)
+    }
+}
+
+impl Serialize for f64 {
+    fn begin(&self) -> Fragment<'_> {
+        Fragment::F64(*self)
+    }
+}
 
 impl<'a, T: ?Sized + Serialize> Serialize for &'a T {
     fn begin(&self) -> Fragment {
@@ -101,48 +214,207 @@ impl<'a, T: ?Sized + ToOwned + Serialize> Serialize for Cow<'a, T> {
     }
 }
 
-impl<A: Serialize, B: Serialize> Serialize for (A, B) {
-    fn begin(&self) -> Fragment {
-        struct TupleStream<'a> {
-            first: &'a dyn Serialize,
-            second: &'a dyn Serialize,
-            state: usize,
+impl<T: ?Sized> Serialize for PhantomData<T> {
+    fn begin(&self) -> Fragment<'_> {
+        Fragment::Null
+    }
+}
+
+impl Serialize for char {
+    fn begin(&self) -> Fragment<'_> {
+        Fragment::Str(Cow::Owned(self.to_string()))
+    }
+}
+
+impl Key for char {
+    fn to_key(&self) -> Cow<'_, str> {
+        Cow::Owned(self.to_string())
+    }
+}
+
+/// Paths that are not valid UTF-8 are written with replacement characters.
+impl Serialize for Path {
+    fn begin(&self) -> Fragment<'_> {
+        Fragment::Str(self.to_string_lossy())
+    }
+}
+
+impl Serialize for PathBuf {
+    fn begin(&self) -> Fragment<'_> {
+        Fragment::Str(self.to_string_lossy())
+    }
+}
+
+/// Addresses are written in their usual text form, e.g. `"10.0.0.1:8080"` or `"[::1]:8080"`.
+macro_rules! address {
+    ($ty:ident) => {
+        impl Serialize for $ty {
+            fn begin(&self) -> Fragment<'_> {
+                Fragment::Str(Cow::Owned(self.to_string()))
+            }
         }
 
-        impl<'a> Seq for TupleStream<'a> {
-            fn next(&mut self) -> Option<&dyn Serialize> {
-                let state = self.state;
-                self.state += 1;
-                match state {
-                    0 => Some(self.first),
-                    1 => Some(self.second),
-                    _ => None,
-                }
+        impl Key for $ty {
+            fn to_key(&self) -> Cow<'_, str> {
+                Cow::Owned(self.to_string())
             }
         }
+    };
+}
+address!(IpAddr);
+address!(Ipv4Addr);
+address!(Ipv6Addr);
+address!(SocketAddr);
+address!(SocketAddrV4);
+address!(SocketAddrV6);
+
+/// Written as `{"secs": u64, "nanos": u32}`.
+impl Serialize for Duration {
+    fn begin(&self) -> Fragment<'_> {
+        Fragment::Map(Box::new(TimeStream {
+            names: ["secs", "nanos"],
+            secs: self.as_secs() as i128,
+            nanos: self.subsec_nanos(),
+            state: 0,
+        }))
+    }
+}
 
-        Fragment::Seq(Box::new(TupleStream {
-            first: &self.0,
-            second: &self.1,
+/// Written as `{"secs_since_epoch": i64, "nanos_since_epoch": u32}`. Times before the epoch have negative seconds,
+/// the nanoseconds always count forward.
+impl Serialize for SystemTime {
+    fn begin(&self) -> Fragment<'_> {
+        let (secs, nanos) = match self.duration_since(UNIX_EPOCH) {
+            Ok(since) => (since.as_secs() as i128, since.subsec_nanos()),
+            Err(error) => {
+                let before = error.duration();
+                match before.subsec_nanos() {
+                    0 => (-(before.as_secs() as i128), 0),
+                    nanos => (-(before.as_secs() as i128) - 1, 1_000_000_000 - nanos),
+                }
+            }
+        };
+        Fragment::Map(Box::new(TimeStream {
+            names: ["secs_since_epoch", "nanos_since_epoch"],
+            secs,
+            nanos,
             state: 0,
         }))
     }
 }
 
+macro_rules! tuple {
+    ($($n:tt $t:ident)+) => {
+        impl<$($t: Serialize),+> Serialize for ($($t,)+) {
+            fn begin(&self) -> Fragment<'_> {
+                serialize_tuple(vec![$(&self.$n as &dyn Serialize),+])
+            }
+        }
+    };
+}
+tuple!(0 A);
+tuple!(0 A 1 B);
+tuple!(0 A 1 B 2 C);
+tuple!(0 A 1 B 2 C 3 D);
+tuple!(0 A 1 B 2 C 3 D 4 E);
+tuple!(0 A 1 B 2 C 3 D 4 E 5 F);
+tuple!(0 A 1 B 2 C 3 D 4 E 5 F 6 G);
+tuple!(0 A 1 B 2 C 3 D 4 E 5 F 6 G 7 H);
+tuple!(0 A 1 B 2 C 3 D 4 E 5 F 6 G 7 H 8 I);
+tuple!(0 A 1 B 2 C 3 D 4 E 5 F 6 G 7 H 8 I 9 J);
+tuple!(0 A 1 B 2 C 3 D 4 E 5 F 6 G 7 H 8 I 9 J 10 K);
+tuple!(0 A 1 B 2 C 3 D 4 E 5 F 6 G 7 H 8 I 9 J 10 K 11 L);
+
 impl<T: Serialize> Serialize for [T] {
-    fn begin(&self) -> Fragment {
+    fn begin(&self) -> Fragment<'_> {
         StreamSerializer::stream_slice(self)
     }
 }
 
 impl<T: Serialize> Serialize for Vec<T> {
-    fn begin(&self) -> Fragment {
+    fn begin(&self) -> Fragment<'_> {
+        StreamSerializer::stream_slice(self)
+    }
+}
+
+impl<T: Serialize, const N: usize> Serialize for [T; N] {
+    fn begin(&self) -> Fragment<'_> {
         StreamSerializer::stream_slice(self)
     }
 }
 
+impl<T: Serialize> Serialize for VecDeque<T> {
+    fn begin(&self) -> Fragment<'_> {
+        Fragment::Seq(Box::new(IterStream(self.iter(), PhantomData)))
+    }
+}
+
+impl<T: Serialize> Serialize for BTreeSet<T> {
+    fn begin(&self) -> Fragment<'_> {
+        Fragment::Seq(Box::new(IterStream(self.iter(), PhantomData)))
+    }
+}
+
+impl<T: Serialize, S> Serialize for HashSet<T, S> {
+    fn begin(&self) -> Fragment<'_> {
+        Fragment::Seq(Box::new(IterStream(self.iter(), PhantomData)))
+    }
+}
+
+impl<K: Key, V: Serialize> Serialize for BTreeMap<K, V> {
+    fn begin(&self) -> Fragment<'_> {
+        Fragment::Map(Box::new(EntryStream(self.iter(), PhantomData)))
+    }
+}
+
+impl<K: Key, V: Serialize, S> Serialize for HashMap<K, V, S> {
+    fn begin(&self) -> Fragment<'_> {
+        Fragment::Map(Box::new(EntryStream(self.iter(), PhantomData)))
+    }
+}
+
+/// Elements of a collection, in iteration order. The marker ties the lifetime of the elements to the stream.
+struct IterStream<'a, I>(I, PhantomData<&'a ()>);
+
+impl<'a, T: Serialize + 'a, I: Iterator<Item = &'a T>> Seq for IterStream<'a, I> {
+    fn next(&mut self) -> Option<&dyn Serialize> {
+        let element = self.0.next()?;
+        Some(element)
+    }
+}
+
+/// Entries of a map, in iteration order.
+struct EntryStream<'a, I>(I, PhantomData<&'a ()>);
+
+impl<'a, K: Key + 'a, V: Serialize + 'a, I: Iterator<Item = (&'a K, &'a V)>> Map for EntryStream<'a, I> {
+    fn next(&mut self) -> Option<(Cow<'_, str>, &dyn Serialize)> {
+        let (k, v) = self.0.next()?;
+        Some((k.to_key(), v))
+    }
+}
+
+/// Seconds and nanoseconds entries of a `Duration` or `SystemTime`.
+struct TimeStream {
+    names: [&'static str; 2],
+    secs: i128,
+    nanos: u32,
+    state: usize,
+}
+
+impl Map for TimeStream {
+    fn next(&mut self) -> Option<(Cow<'_, str>, &dyn Serialize)> {
+        let state = self.state;
+        self.state += 1;
+        match state {
+            0 => Some((Cow::Borrowed(self.names[0]), &self.secs)),
+            1 => Some((Cow::Borrowed(self.names[1]), &self.nanos)),
+            _ => None,
+        }
+    }
+}
+
 impl StreamSerializer {
-    pub fn stream_slice<T: Serialize>(slice: &[T]) -> Fragment {
+    pub fn stream_slice<T: Serialize>(slice: &[T]) -> Fragment<'_> {
         struct SliceStream<'a, T: 'a>(slice::Iter<'a, T>);
 
         impl<'a, T: Serialize> Seq for SliceStream<'a, T> {
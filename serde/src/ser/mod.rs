@@ -1,4 +1,5 @@
 mod impls;
+pub(crate) mod variant;
 
 use std::borrow::Cow;
 use std::boxed::Box;
@@ -9,6 +10,11 @@ pub enum Fragment<'a> {
     Str(Cow<'a, str>),
     U64(u64),
     I64(i64),
+    /// Integers outside of the 64-bit range, smaller ones are `U64` or `I64`.
+    U128(u128),
+    I128(i128),
+    /// Kept apart from `F64` so that text formats can write the shortest representation of the `f32` value.
+    F32(f32),
     F64(f64),
     Seq(Box<dyn Seq + 'a>),
     Map(Box<dyn Map + 'a>),
@@ -29,3 +35,8 @@ pub trait Map {
     fn next(&mut self) -> Option<(Cow<str>, &dyn Serialize)>;
 }
 
+
+/// Trait for types that can be written as the key of a map entry, integers are written in decimal.
+pub trait Key {
+    fn to_key(&self) -> Cow<'_, str>;
+}
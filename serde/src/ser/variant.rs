@@ -0,0 +1,120 @@
+//! Serialization of enum variants carrying data and of tuple structs, used by the `Serialize` derive.
+
+use super::{Fragment, Map, Seq, Serialize};
+
+use std::borrow::Cow;
+use std::boxed::Box;
+use std::vec::Vec;
+
+/// How the variant name is written next to the variant content.
+#[derive(Copy, Clone)]
+pub enum Tagging {
+    /// `{"Variant": content}`, or `"Variant"` for unit variants.
+    External,
+    /// `{"tag": "Variant", ...fields}`, unit, struct and newtype variants only. The content of a newtype variant must
+    /// serialize as a map, whose entries follow the tag, other contents are left out.
+    Internal(&'static str),
+    /// `{"tag": "Variant", "content": content}`, content omitted for unit variants.
+    Adjacent(&'static str, &'static str),
+}
+
+/// Borrowed fields of a variant.
+pub enum Content<'a> {
+    Unit,
+    Newtype(&'a dyn Serialize),
+    Tuple(Vec<&'a dyn Serialize>),
+    Struct(Vec<(&'static str, &'a dyn Serialize)>),
+}
+
+impl<'a> Serialize for Content<'a> {
+    fn begin(&self) -> Fragment<'_> {
+        match self {
+            Content::Unit => Fragment::Null,
+            Content::Newtype(value) => value.begin(),
+            Content::Tuple(elements) => Fragment::Seq(Box::new(Elements(elements.iter()))),
+            Content::Struct(fields) => Fragment::Map(Box::new(Fields(fields.iter()))),
+        }
+    }
+}
+
+/// Serialize the fields of a tuple struct as a sequence.
+pub fn serialize_tuple(elements: Vec<&dyn Serialize>) -> Fragment<'_> {
+    Fragment::Seq(Box::new(OwnedElements(elements.into_iter())))
+}
+
+struct OwnedElements<'a>(std::vec::IntoIter<&'a dyn Serialize>);
+
+impl<'a> Seq for OwnedElements<'a> {
+    fn next(&mut self) -> Option<&dyn Serialize> {
+        self.0.next()
+    }
+}
+
+struct Elements<'a, 'b>(core::slice::Iter<'b, &'a dyn Serialize>);
+
+impl<'a, 'b> Seq for Elements<'a, 'b> {
+    fn next(&mut self) -> Option<&dyn Serialize> {
+        self.0.next().copied()
+    }
+}
+
+struct Fields<'a, 'b>(core::slice::Iter<'b, (&'static str, &'a dyn Serialize)>);
+
+impl<'a, 'b> Map for Fields<'a, 'b> {
+    fn next(&mut self) -> Option<(Cow<'_, str>, &dyn Serialize)> {
+        let (name, value) = self.0.next()?;
+        Some((Cow::Borrowed(*name), *value))
+    }
+}
+
+/// Serialize the variant `name` with the given content.
+pub fn serialize_variant<'a>(tagging: Tagging, name: &'static str, content: Content<'a>) -> Fragment<'a> {
+    match (tagging, &content) {
+        (Tagging::External, Content::Unit) => Fragment::Str(Cow::Borrowed(name)),
+        _ => Fragment::Map(Box::new(Variant {
+            tagging,
+            name,
+            content,
+            state: 0,
+            inner: None,
+        })),
+    }
+}
+
+struct Variant<'a> {
+    tagging: Tagging,
+    name: &'static str,
+    content: Content<'a>,
+    state: usize,
+    /// Entries of the content of an internally tagged newtype variant.
+    inner: Option<Box<dyn Map + 'a>>,
+}
+
+impl<'a> Map for Variant<'a> {
+    fn next(&mut self) -> Option<(Cow<'_, str>, &dyn Serialize)> {
+        let state = self.state;
+        self.state += 1;
+        match (self.tagging, state) {
+            (Tagging::External, 0) => Some((Cow::Borrowed(self.name), &self.content)),
+            (Tagging::Internal(tag) | Tagging::Adjacent(tag, _), 0) => Some((Cow::Borrowed(tag), &self.name)),
+            (Tagging::Internal(_), i) => match &self.content {
+                Content::Struct(fields) => fields.get(i - 1).map(|(name, value)| (Cow::Borrowed(*name), *value)),
+                &Content::Newtype(value) => {
+                    if self.inner.is_none() {
+                        match value.begin() {
+                            Fragment::Map(map) => self.inner = Some(map),
+                            _ => return None,
+                        }
+                    }
+                    self.inner.as_mut()?.next()
+                }
+                _ => None,
+            },
+            (Tagging::Adjacent(_, content), 1) => match self.content {
+                Content::Unit => None,
+                _ => Some((Cow::Borrowed(content), &self.content)),
+            },
+            _ => None,
+        }
+    }
+}
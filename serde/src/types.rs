@@ -1,11 +1,199 @@
 use core::fmt::{self, Display};
+use std::io;
 
-#[derive(Copy, Clone, Debug)]
-pub struct Error;
+/// Reason of a serialization or deserialization failure.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ErrorKind {
+    /// A JSON document contains a character that is not allowed at this position.
+    UnexpectedChar(char),
+    /// A binary document contains an unknown value tag.
+    InvalidTag(u8),
+    /// A binary document has bytes left after the end of the value.
+    TrailingBytes,
+    /// The input ended in the middle of a value.
+    Eof,
+    /// The value has a different type than the one expected by the target, e.g. a string for a `u32`.
+    TypeMismatch,
+    /// A struct field is absent from the input.
+    MissingField(&'static str),
+    /// A key does not match any field of a struct with `#[serde(deny_unknown_fields)]`.
+    UnknownField(String),
+    /// An enum variant name does not match any variant.
+    UnknownVariant(String),
+    /// A number does not fit in the target type.
+    NumberOverflow,
+    /// A string is not valid UTF-8.
+    InvalidUtf8,
+    /// A `&str` or `&[u8]` was read from a string that contains escapes, so it does not appear as is in the input.
+    EscapedBorrow,
+    /// A `NaN` or infinite float was serialized to JSON, which cannot represent it.
+    NonFiniteFloat,
+    /// Writing the serialized output failed.
+    Io(io::ErrorKind),
+    /// The input goes over one of the limits set by [`DeserializerConfig`](crate::json::DeserializerConfig).
+    LimitExceeded(Limit),
+}
+
+/// Limit on the size of a deserialized input.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Limit {
+    /// Number of sequences and maps open at once.
+    Depth,
+    /// Length in bytes of a string or key, after unescaping.
+    StringLength,
+    /// Number of elements of a sequence or entries of a map.
+    CollectionLength,
+    /// Length in bytes of the whole input.
+    InputLength,
+}
+
+impl Display for Limit {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Limit::Depth => formatter.write_str("nesting depth"),
+            Limit::StringLength => formatter.write_str("string length"),
+            Limit::CollectionLength => formatter.write_str("collection length"),
+            Limit::InputLength => formatter.write_str("input length"),
+        }
+    }
+}
+
+impl Display for ErrorKind {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ErrorKind::UnexpectedChar(c) => write!(formatter, "unexpected character {:?}", c),
+            ErrorKind::InvalidTag(tag) => write!(formatter, "invalid tag 0x{:02x}", tag),
+            ErrorKind::TrailingBytes => formatter.write_str("trailing bytes after value"),
+            ErrorKind::Eof => formatter.write_str("unexpected end of input"),
+            ErrorKind::TypeMismatch => formatter.write_str("invalid type"),
+            ErrorKind::MissingField(field) => write!(formatter, "missing field `{}`", field),
+            ErrorKind::UnknownField(field) => write!(formatter, "unknown field `{}`", field),
+            ErrorKind::UnknownVariant(variant) => write!(formatter, "unknown variant `{}`", variant),
+            ErrorKind::NumberOverflow => formatter.write_str("number out of range"),
+            ErrorKind::InvalidUtf8 => formatter.write_str("invalid UTF-8"),
+            ErrorKind::EscapedBorrow => formatter.write_str("escaped string cannot be borrowed"),
+            ErrorKind::NonFiniteFloat => formatter.write_str("NaN or infinite float"),
+            ErrorKind::Io(kind) => write!(formatter, "I/O error: {}", kind),
+            ErrorKind::LimitExceeded(limit) => write!(formatter, "{} limit exceeded", limit),
+        }
+    }
+}
+
+/// Error returned by deserialization functions, and by serialization functions that can fail.
+///
+/// Along with its [`ErrorKind`], the error records where the failure happened: the byte offset in the input (plus line and
+/// column for text formats) and the path of the failing value, e.g. `flights[0].flight_id`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Error {
+    kind: ErrorKind,
+    offset: Option<usize>,
+    line_column: Option<(usize, usize)>,
+    path: String,
+}
+
+impl Error {
+    pub fn new(kind: ErrorKind) -> Self {
+        Error {
+            kind,
+            offset: None,
+            line_column: None,
+            path: String::new(),
+        }
+    }
+
+    pub fn missing_field(field: &'static str) -> Self {
+        Error::new(ErrorKind::MissingField(field))
+    }
+
+    pub fn unknown_field(field: &str) -> Self {
+        Error::new(ErrorKind::UnknownField(field.to_owned()))
+    }
+
+    pub fn unknown_variant(variant: &str) -> Self {
+        Error::new(ErrorKind::UnknownVariant(variant.to_owned()))
+    }
+
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
+    }
+
+    /// Byte offset in the input where the error was detected.
+    pub fn offset(&self) -> Option<usize> {
+        self.offset
+    }
+
+    /// 1-based line where the error was detected, text formats only.
+    pub fn line(&self) -> Option<usize> {
+        self.line_column.map(|(line, _)| line)
+    }
+
+    /// 1-based column where the error was detected, text formats only.
+    pub fn column(&self) -> Option<usize> {
+        self.line_column.map(|(_, column)| column)
+    }
+
+    /// Path of the value that failed to deserialize, empty for the root value.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Record the position of the error in a binary input, keeping the first position recorded.
+    pub(crate) fn at_offset(mut self, offset: usize) -> Self {
+        self.offset.get_or_insert(offset);
+        self
+    }
+
+    /// Record the position of the error in a text input, keeping the first position recorded.
+    pub(crate) fn at_position(mut self, input: &[u8], offset: usize) -> Self {
+        if self.offset.is_none() {
+            let offset = offset.min(input.len());
+            let line = 1 + input[..offset].iter().filter(|&&b| b == b'\n').count();
+            let line_start = input[..offset].iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+            self.offset = Some(offset);
+            self.line_column = Some((line, 1 + offset - line_start));
+        }
+        self
+    }
+
+    /// Record the path of the failing value, keeping the first path recorded. A missing field is reported at the path
+    /// it would have had.
+    pub(crate) fn with_path(mut self, mut path: String) -> Self {
+        if self.path.is_empty() {
+            if let ErrorKind::MissingField(field) = self.kind {
+                if !path.is_empty() {
+                    path.push('.');
+                }
+                path.push_str(field);
+            }
+            self.path = path;
+        }
+        self
+    }
+}
+
+impl From<ErrorKind> for Error {
+    fn from(kind: ErrorKind) -> Self {
+        Error::new(kind)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Error::new(ErrorKind::Io(error.kind()))
+    }
+}
 
 impl Display for Error {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        formatter.write_str("serde error")
+        Display::fmt(&self.kind, formatter)?;
+        if !self.path.is_empty() {
+            write!(formatter, " at `{}`", self.path)?;
+        }
+        match (self.line_column, self.offset) {
+            (Some((line, column)), _) => write!(formatter, ", line {} column {}", line, column),
+            (None, Some(offset)) => write!(formatter, ", byte {}", offset),
+            (None, None) => Ok(()),
+        }
     }
 }
 
@@ -0,0 +1,49 @@
+mod common;
+
+use common::round_trip;
+use serde::{json, ErrorKind};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+
+#[test]
+fn maps_should_round_trip() {
+    let seats = BTreeMap::from([("LAS".to_owned(), 3u32), ("SIN".to_owned(), 0)]);
+    round_trip(seats, r#"{"LAS":3,"SIN":0}"#);
+
+    let delays = BTreeMap::from([(-1i64, "early".to_owned()), (7, "late".to_owned())]);
+    round_trip(delays, r#"{"-1":"early","7":"late"}"#);
+
+    let flights = HashMap::from([(7u32, vec!["LAS".to_owned()])]);
+    round_trip(flights, r#"{"7":["LAS"]}"#);
+
+    let error = json::from_str::<BTreeMap<u8, u32>>(r#"{"256":1}"#).unwrap_err();
+    assert_eq!(error.kind(), &ErrorKind::NumberOverflow);
+    let error = json::from_str::<HashMap<u32, u32>>(r#"{"LAS":1}"#).unwrap_err();
+    assert_eq!(error.kind(), &ErrorKind::TypeMismatch);
+}
+
+#[test]
+fn sequences_should_round_trip() {
+    round_trip(BTreeSet::from([3u32, 1, 2]), "[1,2,3]");
+    round_trip(HashSet::from([7u32]), "[7]");
+    round_trip(VecDeque::from([1u32, 2]), "[1,2]");
+    round_trip([1u32, 2, 3], "[1,2,3]");
+    round_trip(Box::new(7u32), "7");
+    round_trip(Some(Box::new(vec![1u32])), "[1]");
+
+    let error = json::from_str::<[u32; 3]>("[1,2]").unwrap_err();
+    assert_eq!(error.kind(), &ErrorKind::TypeMismatch);
+    let error = json::from_str::<[u32; 1]>("[1,2]").unwrap_err();
+    assert_eq!(error.kind(), &ErrorKind::TypeMismatch);
+}
+
+#[test]
+fn tuples_should_round_trip() {
+    round_trip((7u32,), "[7]");
+    round_trip((7u32, "LAS".to_owned(), Some(-3i32)), r#"[7,"LAS",-3]"#);
+    round_trip((1u8, 2u16, 3u32, 4u64, 5i8, 6i16, 7i32, 8i64, true, (), 'x'.to_string(), vec![12u32]), r#"[1,2,3,4,5,6,7,8,true,null,"x",[12]]"#);
+
+    let error = json::from_str::<(u32, u32)>("[1]").unwrap_err();
+    assert_eq!(error.kind(), &ErrorKind::TypeMismatch);
+    let error = json::from_str::<(u32, u32)>("[1,2,3]").unwrap_err();
+    assert_eq!(error.kind(), &ErrorKind::TypeMismatch);
+}
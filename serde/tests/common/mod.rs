@@ -0,0 +1,10 @@
+use serde::de::DeserializeOwned;
+use serde::{binary, json, Serialize};
+
+/// Check the JSON text of `value`, and that it reads back from JSON and from the binary format.
+pub fn round_trip<T: Serialize + DeserializeOwned + PartialEq + core::fmt::Debug>(value: T, expected: &str) {
+    let text = json::to_string(&value);
+    assert_eq!(text, expected);
+    assert_eq!(json::from_str::<T>(&text).unwrap(), value);
+    assert_eq!(binary::from_bytes::<T>(&binary::to_bytes(&value)).unwrap(), value);
+}
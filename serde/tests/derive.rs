@@ -0,0 +1,304 @@
+mod common;
+
+use common::round_trip;
+use serde::{binary, json, Deserialize, ErrorKind, Serialize};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Request {
+    Ping,
+    Query(u32),
+    Reserve(u32, u32),
+    Search { source: String, destination: String },
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+enum Internal {
+    Ping,
+    Search { source: String, limit: Option<u32> },
+    Itinerary(Itinerary),
+    Fares(BTreeMap<String, u32>),
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Itinerary {
+    source: String,
+    destination: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "t", content = "c")]
+enum Adjacent {
+    Ping,
+    Query(u32),
+    Reserve(u32, u32),
+    Search { source: String },
+}
+
+#[test]
+fn externally_tagged_variants_should_round_trip() {
+    round_trip(Request::Ping, r#""Ping""#);
+    round_trip(Request::Query(7), r#"{"Query":7}"#);
+    round_trip(Request::Reserve(7, 2), r#"{"Reserve":[7,2]}"#);
+    round_trip(
+        Request::Search { source: "LAS".to_owned(), destination: "HAN".to_owned() },
+        r#"{"Search":{"source":"LAS","destination":"HAN"}}"#,
+    );
+    assert_eq!(json::from_str::<Request>(r#"{"Ping":null}"#).unwrap(), Request::Ping);
+}
+
+#[test]
+fn internally_tagged_variants_should_round_trip() {
+    round_trip(Internal::Ping, r#"{"type":"Ping"}"#);
+    round_trip(
+        Internal::Search { source: "LAS".to_owned(), limit: Some(3) },
+        r#"{"type":"Search","source":"LAS","limit":3}"#,
+    );
+    let search = json::from_str::<Internal>(r#"{"type":"Search","source":"LAS"}"#).unwrap();
+    assert_eq!(search, Internal::Search { source: "LAS".to_owned(), limit: None });
+
+    let itinerary = Itinerary { source: "LAS".to_owned(), destination: "HAN".to_owned() };
+    round_trip(Internal::Itinerary(itinerary), r#"{"type":"Itinerary","source":"LAS","destination":"HAN"}"#);
+    let fares = BTreeMap::from([("HAN".to_owned(), 120), ("SIN".to_owned(), 80)]);
+    round_trip(Internal::Fares(fares), r#"{"type":"Fares","HAN":120,"SIN":80}"#);
+}
+
+#[test]
+fn internally_tagged_variants_should_read_the_tag_after_the_content() {
+    let search = Internal::Search { source: "LAS".to_owned(), limit: Some(3) };
+    let itinerary = Internal::Itinerary(Itinerary { source: "LAS".to_owned(), destination: "HAN".to_owned() });
+    let cases = [
+        (r#"{"source":"LAS","type":"Search","limit":3}"#, search),
+        (r#"{"source":"LAS","destination":"HAN","type":"Itinerary"}"#, itinerary),
+        (r#"{"SIN":80,"type":"Fares"}"#, Internal::Fares(BTreeMap::from([("SIN".to_owned(), 80)]))),
+    ];
+    for (text, expected) in cases {
+        assert_eq!(json::from_str::<Internal>(text).unwrap(), expected);
+        let bytes = binary::to_bytes(&json::from_str::<json::Value>(text).unwrap());
+        assert_eq!(binary::from_bytes::<Internal>(&bytes).unwrap(), expected);
+    }
+
+    let error = json::from_str::<Internal>(r#"{"source":"LAS","type":"Itinerary"}"#).unwrap_err();
+    assert_eq!(error.kind(), &ErrorKind::MissingField("destination"));
+}
+
+#[test]
+fn adjacently_tagged_variants_should_round_trip() {
+    round_trip(Adjacent::Ping, r#"{"t":"Ping"}"#);
+    round_trip(Adjacent::Query(7), r#"{"t":"Query","c":7}"#);
+    round_trip(Adjacent::Reserve(7, 2), r#"{"t":"Reserve","c":[7,2]}"#);
+    round_trip(Adjacent::Search { source: "LAS".to_owned() }, r#"{"t":"Search","c":{"source":"LAS"}}"#);
+}
+
+#[test]
+fn invalid_variants_should_be_rejected() {
+    let error = json::from_str::<Request>(r#""Cancel""#).unwrap_err();
+    assert_eq!(error.kind(), &ErrorKind::UnknownVariant("Cancel".to_owned()));
+
+    let error = json::from_str::<Request>(r#""Query""#).unwrap_err();
+    assert_eq!(error.kind(), &ErrorKind::TypeMismatch);
+
+    let error = json::from_str::<Request>(r#"{"Reserve":[7]}"#).unwrap_err();
+    assert_eq!(error.kind(), &ErrorKind::TypeMismatch);
+
+    let error = json::from_str::<Internal>(r#"{"source":"LAS"}"#).unwrap_err();
+    assert_eq!(error.kind(), &ErrorKind::MissingField("type"));
+
+    let error = json::from_str::<Adjacent>(r#"{"t":"Search","c":{}}"#).unwrap_err();
+    assert_eq!(error.kind(), &ErrorKind::MissingField("source"));
+}
+
+fn default_seats() -> u32 {
+    1
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Booking {
+    flight_id: u32,
+    #[serde(rename = "seats", default = "default_seats")]
+    num_seat: u32,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    luggage_kg: Vec<u32>,
+    #[serde(skip)]
+    cached: bool,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Strict {
+    id: u32,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum Event {
+    SeatsChanged {
+        #[serde(rename = "seats")]
+        seat_avail: u32,
+    },
+    #[serde(rename = "cancelled")]
+    FlightCancelled,
+}
+
+#[test]
+fn field_attributes_should_be_applied() {
+    let booking = Booking { flight_id: 7, num_seat: 2, luggage_kg: Vec::new(), cached: true };
+    assert_eq!(json::to_string(&booking), r#"{"flightId":7,"seats":2}"#);
+
+    let decoded = json::from_str::<Booking>(r#"{"flightId":7,"luggageKg":[20]}"#).unwrap();
+    assert_eq!(decoded, Booking { flight_id: 7, num_seat: 1, luggage_kg: vec![20], cached: false });
+
+    let error = json::from_str::<Booking>(r#"{"seats":2}"#).unwrap_err();
+    assert_eq!(error.kind(), &ErrorKind::MissingField("flightId"));
+}
+
+#[test]
+fn unknown_fields_should_be_denied() {
+    assert_eq!(json::from_str::<Strict>(r#"{"id":7}"#).unwrap(), Strict { id: 7 });
+    let error = json::from_str::<Strict>(r#"{"id":7,"name":"LAS"}"#).unwrap_err();
+    assert_eq!(error.kind(), &ErrorKind::UnknownField("name".to_owned()));
+}
+
+#[test]
+fn variant_attributes_should_be_applied() {
+    round_trip(Event::SeatsChanged { seat_avail: 3 }, r#"{"kind":"seats_changed","seats":3}"#);
+    round_trip(Event::FlightCancelled, r#"{"kind":"cancelled"}"#);
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct FlightId(u32);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Seats(Vec<u32>);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Route(String, String, Option<FlightId>);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Ack;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Reservation {
+    flight_id: FlightId,
+    seats: Seats,
+    note: Option<FlightId>,
+}
+
+#[test]
+fn newtype_structs_should_be_transparent() {
+    round_trip(FlightId(7), "7");
+    round_trip(Seats(vec![1, 2]), "[1,2]");
+    round_trip(
+        Reservation { flight_id: FlightId(7), seats: Seats(vec![3]), note: None },
+        r#"{"flight_id":7,"seats":[3],"note":null}"#,
+    );
+    let reservation = json::from_str::<Reservation>(r#"{"flight_id":7,"seats":[]}"#).unwrap();
+    assert_eq!(reservation.note, None);
+}
+
+#[test]
+fn tuple_and_unit_structs_should_round_trip() {
+    round_trip(Route("LAS".to_owned(), "HAN".to_owned(), Some(FlightId(7))), r#"["LAS","HAN",7]"#);
+    round_trip(Ack, "null");
+
+    let error = json::from_str::<RouteRef>(r#"["LAS"]"#).unwrap_err();
+    assert_eq!(error.kind(), &ErrorKind::TypeMismatch);
+    let error = json::from_str::<RouteRef>(r#"["LAS","HAN",7,8]"#).unwrap_err();
+    assert_eq!(error.kind(), &ErrorKind::TypeMismatch);
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum RpcResult<T> {
+    Ok(T),
+    Err { message: String },
+}
+
+/// Not serializable, only mentioned through `PhantomData`.
+#[derive(Debug, PartialEq)]
+struct Cursor;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Page<T, M> {
+    items: Vec<T>,
+    #[serde(skip)]
+    marker: core::marker::PhantomData<M>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Airport<'a> {
+    code: Cow<'a, str>,
+}
+
+#[derive(Serialize)]
+struct AirportRef<'a> {
+    code: &'a str,
+}
+
+#[test]
+fn generic_enums_should_round_trip() {
+    round_trip(RpcResult::Ok(7u32), r#"{"Ok":7}"#);
+    round_trip(RpcResult::Ok(vec![FlightId(1)]), r#"{"Ok":[1]}"#);
+    round_trip(RpcResult::<u32>::Err { message: "full".to_owned() }, r#"{"Err":{"message":"full"}}"#);
+
+    let page = Page::<u32, Cursor> { items: vec![1, 2], marker: core::marker::PhantomData };
+    round_trip(page, r#"{"items":[1,2]}"#);
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct RouteRef<'a> {
+    source: &'a str,
+    destination: &'a str,
+}
+
+#[test]
+fn structs_with_lifetimes_should_borrow() {
+    let code = String::from("SIN");
+    assert_eq!(json::to_string(&AirportRef { code: &code }), r#"{"code":"SIN"}"#);
+
+    let text = json::to_string(&RpcResult::Ok(Airport { code: code.as_str().into() }));
+    assert_eq!(text, r#"{"Ok":{"code":"SIN"}}"#);
+    match json::from_str::<RpcResult<Airport>>(&text).unwrap() {
+        RpcResult::Ok(airport) => assert!(matches!(airport.code, Cow::Borrowed("SIN"))),
+        other => panic!("unexpected {:?}", other),
+    }
+    let escaped = json::from_str::<Airport>(r#"{"code":"S\u0049N"}"#).unwrap();
+    assert!(matches!(escaped.code, Cow::Owned(ref code) if code == "SIN"));
+
+    let bytes = binary::to_bytes(&Airport { code: "LAS".into() });
+    assert!(matches!(binary::from_bytes::<Airport>(&bytes).unwrap().code, Cow::Borrowed("LAS")));
+
+    let route = json::from_str::<RouteRef>(r#"{"source":"LAS","destination":"HAN"}"#).unwrap();
+    assert_eq!(route, RouteRef { source: "LAS", destination: "HAN" });
+    let error = json::from_str::<RouteRef>(r#"{"source":"L\"S","destination":"HAN"}"#).unwrap_err();
+    assert_eq!(error.kind(), &ErrorKind::EscapedBorrow);
+    assert_eq!(error.path(), "source");
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Payload<'a> {
+    data: &'a [u8],
+}
+
+#[test]
+fn byte_slices_should_borrow_unescaped_strings() {
+    let text = String::from(r#"{"data":"LAS-HAN"}"#);
+    let payload = json::from_str::<Payload>(&text).unwrap();
+    assert_eq!(payload.data, b"LAS-HAN");
+    assert!(text.as_bytes().as_ptr_range().contains(&payload.data.as_ptr()), "Bytes should be borrowed from the input");
+
+    let error = json::from_str::<Payload>(r#"{"data":"LAS\nHAN"}"#).unwrap_err();
+    assert_eq!((error.kind(), error.path()), (&ErrorKind::EscapedBorrow, "data"));
+    assert_eq!(error.to_string(), "escaped string cannot be borrowed at `data`, line 1 column 19");
+
+    #[derive(Serialize)]
+    struct Text<'a> {
+        data: &'a str,
+    }
+    let bytes = binary::to_bytes(&Text { data: "SIN" });
+    assert_eq!(binary::from_bytes::<Payload>(&bytes).unwrap().data, b"SIN");
+    let error = json::from_str::<Payload>(&json::to_string(&[76u8, 65, 83][..])).unwrap_err();
+    assert_eq!(error.kind(), &ErrorKind::TypeMismatch, "Serialized byte slices are sequences");
+}
@@ -0,0 +1,51 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{binary, json, ErrorKind};
+
+#[test]
+fn floats_should_be_written_in_shortest_form() {
+    assert_eq!(json::to_string(&150.99f32), "150.99");
+    assert_eq!(json::to_string(&0.1f32), "0.1");
+    assert_eq!(json::to_string(&vec![1.0, 0.1 + 0.2, 1e300, -5e-324]), "[1.0,0.30000000000000004,1e300,-5e-324]");
+}
+
+#[test]
+fn non_finite_floats_should_be_null_or_rejected() {
+    assert_eq!(json::to_string(&vec![f64::NAN, f64::INFINITY]), "[null,null]");
+    assert_eq!(json::to_string(&f32::NEG_INFINITY), "null");
+
+    let error = json::try_to_string(&vec![1.5, f64::NAN]).unwrap_err();
+    assert_eq!(error.kind(), &ErrorKind::NonFiniteFloat);
+    assert_eq!(json::try_to_string(&1.5f32).unwrap(), "1.5");
+}
+
+#[test]
+fn out_of_range_floats_should_be_rejected() {
+    assert_eq!(json::from_str::<f64>("1e400").unwrap_err().kind(), &ErrorKind::NumberOverflow);
+    assert_eq!(json::from_str::<f32>("1e39").unwrap_err().kind(), &ErrorKind::NumberOverflow);
+    assert_eq!(json::from_str::<f64>("1e-400").unwrap(), 0.0);
+}
+
+#[test]
+fn f64_should_round_trip() {
+    let mut rng = StdRng::seed_from_u64(4013);
+    let edge_cases = [0.0, -0.0, f64::MIN_POSITIVE, f64::MAX, f64::MIN, f64::EPSILON, 5e-324, 150.99];
+    let random = (0..100_000).map(|_| f64::from_bits(rng.gen())).filter(|f| f.is_finite());
+    for f in edge_cases.into_iter().chain(random) {
+        let text = json::to_string(&f);
+        assert_eq!(json::from_str::<f64>(&text).unwrap().to_bits(), f.to_bits(), "{}", text);
+        assert_eq!(binary::from_bytes::<f64>(&binary::to_bytes(&f)).unwrap().to_bits(), f.to_bits());
+    }
+}
+
+#[test]
+fn f32_should_round_trip() {
+    let mut rng = StdRng::seed_from_u64(4013);
+    let edge_cases = [0.0, -0.0, f32::MIN_POSITIVE, f32::MAX, f32::MIN, f32::EPSILON, 1e-45, 150.99];
+    let random = (0..100_000).map(|_| f32::from_bits(rng.gen())).filter(|f| f.is_finite());
+    for f in edge_cases.into_iter().chain(random) {
+        let text = json::to_string(&f);
+        assert_eq!(json::from_str::<f32>(&text).unwrap().to_bits(), f.to_bits(), "{}", text);
+        assert_eq!(binary::from_bytes::<f32>(&binary::to_bytes(&f)).unwrap().to_bits(), f.to_bits());
+    }
+}
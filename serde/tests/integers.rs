@@ -0,0 +1,92 @@
+use serde::json::{DeserializerConfig, Number, Value};
+use serde::{binary, json, Deserialize, ErrorKind, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Reservation {
+    flight_id: u32,
+    seats: u8,
+    delay: i16,
+}
+
+#[test]
+fn wide_integers_should_round_trip() {
+    let values = [0, 7, u64::MAX as u128, u64::MAX as u128 + 1, u128::MAX];
+    for u in values {
+        let text = json::to_string(&u);
+        assert_eq!(text, u.to_string());
+        assert_eq!(json::from_str::<u128>(&text).unwrap(), u);
+        assert_eq!(binary::from_bytes::<u128>(&binary::to_bytes(&u)).unwrap(), u);
+    }
+
+    let values = [0, -7, i64::MIN as i128, i64::MIN as i128 - 1, i128::MIN, i128::MAX];
+    for i in values {
+        let text = json::to_string(&i);
+        assert_eq!(text, i.to_string());
+        assert_eq!(json::from_str::<i128>(&text).unwrap(), i);
+        assert_eq!(binary::from_bytes::<i128>(&binary::to_bytes(&i)).unwrap(), i);
+    }
+
+    // small values keep the 64-bit encoding
+    assert_eq!(binary::to_bytes(&7u128), binary::to_bytes(&7u64));
+}
+
+#[test]
+fn out_of_range_integers_should_be_rejected_at_their_field() {
+    let error = json::from_str::<Reservation>(r#"{"flight_id":4294967296,"seats":2,"delay":0}"#).unwrap_err();
+    assert_eq!((error.kind(), error.path()), (&ErrorKind::NumberOverflow, "flight_id"));
+    assert_eq!(error.to_string(), "number out of range at `flight_id`, line 1 column 24");
+
+    let error = json::from_str::<Vec<Reservation>>(r#"[{"flight_id":1,"seats":256,"delay":0}]"#).unwrap_err();
+    assert_eq!((error.kind(), error.path()), (&ErrorKind::NumberOverflow, "[0].seats"));
+
+    let error = json::from_str::<Reservation>(r#"{"flight_id":1,"seats":2,"delay":-32769}"#).unwrap_err();
+    assert_eq!((error.kind(), error.path()), (&ErrorKind::NumberOverflow, "delay"));
+
+    assert_eq!(json::from_str::<u64>("18446744073709551616").unwrap_err().kind(), &ErrorKind::NumberOverflow);
+    assert_eq!(json::from_str::<i64>("-9223372036854775809").unwrap_err().kind(), &ErrorKind::NumberOverflow);
+    assert_eq!(json::from_str::<u128>("-1").unwrap_err().kind(), &ErrorKind::TypeMismatch);
+    assert_eq!(json::from_str::<i128>(&u128::MAX.to_string()).unwrap_err().kind(), &ErrorKind::NumberOverflow);
+    let bytes = binary::to_bytes(&(u64::MAX as u128 + 1));
+    assert_eq!(binary::from_bytes::<u64>(&bytes).unwrap_err().kind(), &ErrorKind::NumberOverflow);
+}
+
+#[test]
+fn integers_too_long_for_128_bits_should_be_floats() {
+    let text = format!("{}0", u128::MAX);
+    assert_eq!(json::from_str::<f64>(&text).unwrap(), text.parse::<f64>().unwrap());
+    assert_eq!(json::from_str::<u128>(&text).unwrap_err().kind(), &ErrorKind::TypeMismatch);
+}
+
+#[test]
+fn integral_floats_should_be_accepted_when_enabled() {
+    let text = r#"{"flight_id":7.0,"seats":2e0,"delay":-3.00}"#;
+    let error = json::from_str::<Reservation>(text).unwrap_err();
+    assert_eq!((error.kind(), error.path()), (&ErrorKind::TypeMismatch, "flight_id"));
+
+    let lenient = DeserializerConfig::new().integral_floats(true);
+    let reservation = lenient.from_str::<Reservation>(text).unwrap();
+    assert_eq!(reservation, Reservation { flight_id: 7, seats: 2, delay: -3 });
+
+    let error = lenient.from_str::<Reservation>(r#"{"flight_id":7.5,"seats":2,"delay":0}"#).unwrap_err();
+    assert_eq!((error.kind(), error.path()), (&ErrorKind::TypeMismatch, "flight_id"));
+    let error = lenient.from_str::<Reservation>(r#"{"flight_id":1,"seats":300.0,"delay":0}"#).unwrap_err();
+    assert_eq!((error.kind(), error.path()), (&ErrorKind::NumberOverflow, "seats"));
+
+    assert_eq!(lenient.from_str::<f64>("5.0").unwrap(), 5.0);
+    assert_eq!(lenient.from_str::<u64>("9007199254740992.0").unwrap(), 1 << 53);
+    assert!(lenient.from_str::<u64>("1e16").is_err());
+}
+
+#[test]
+fn values_should_hold_wide_integers() {
+    let value = json::from_str::<Value>(&format!("[{},{}]", u128::MAX, i128::MIN)).unwrap();
+    assert_eq!(value[0], Value::Number(Number::U128(u128::MAX)));
+    assert_eq!(value[1], Value::Number(Number::I128(i128::MIN)));
+    assert_eq!(json::to_string(&value), format!("[{},{}]", u128::MAX, i128::MIN));
+    assert_eq!(json::from_value::<(u128, i128)>(value).unwrap(), (u128::MAX, i128::MIN));
+
+    assert_eq!(Value::from(7u128), Value::Number(Number::U64(7)));
+    assert_eq!(Number::U128(7), Number::I64(7));
+    assert_eq!(json::from_value::<u8>(Value::Number(Number::I128(7))).unwrap(), 7);
+    assert_eq!(json::to_value(&(u64::MAX as u128 + 1)), Value::Number(Number::U128(u64::MAX as u128 + 1)));
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use common::round_trip;
+use serde::{json, Deserialize, ErrorKind, Serialize};
+use std::collections::BTreeMap;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::num::{NonZeroI8, NonZeroU32};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Departure {
+    flight_id: NonZeroU32,
+    departure_time: SystemTime,
+    boarding: Duration,
+    gate: char,
+    client_addr: SocketAddr,
+}
+
+#[test]
+fn std_types_should_round_trip() {
+    let departure = Departure {
+        flight_id: NonZeroU32::new(7).unwrap(),
+        departure_time: UNIX_EPOCH + Duration::new(1680105600, 250),
+        boarding: Duration::from_millis(1500),
+        gate: 'B',
+        client_addr: "127.0.0.1:2222".parse().unwrap(),
+    };
+    round_trip(
+        departure,
+        r#"{"flight_id":7,"departure_time":{"secs_since_epoch":1680105600,"nanos_since_epoch":250},"boarding":{"secs":1,"nanos":500000000},"gate":"B","client_addr":"127.0.0.1:2222"}"#,
+    );
+
+    round_trip(PathBuf::from("/var/lib/flights.db"), r#""/var/lib/flights.db""#);
+    round_trip("[::1]:8080".parse::<SocketAddr>().unwrap(), r#""[::1]:8080""#);
+    round_trip(IpAddr::V4(Ipv4Addr::LOCALHOST), r#""127.0.0.1""#);
+    round_trip('é', r#""é""#);
+    round_trip(NonZeroI8::new(-128).unwrap(), "-128");
+    round_trip(BTreeMap::from([(IpAddr::V4(Ipv4Addr::LOCALHOST), 'x')]), r#"{"127.0.0.1":"x"}"#);
+}
+
+#[test]
+fn times_before_the_epoch_should_have_negative_seconds() {
+    let time = UNIX_EPOCH - Duration::new(1, 250);
+    round_trip(time, r#"{"secs_since_epoch":-2,"nanos_since_epoch":999999750}"#);
+    let now = SystemTime::now();
+    round_trip(now, &json::to_string(&now));
+}
+
+#[test]
+fn invalid_std_values_should_be_rejected() {
+    let error = json::from_str::<NonZeroU32>("0").unwrap_err();
+    assert_eq!(error.kind(), &ErrorKind::NumberOverflow);
+    assert_eq!(json::from_str::<NonZeroU32>("-1").unwrap_err().kind(), &ErrorKind::TypeMismatch);
+    assert_eq!(json::from_str::<char>(r#""AB""#).unwrap_err().kind(), &ErrorKind::TypeMismatch);
+    assert_eq!(json::from_str::<char>(r#""""#).unwrap_err().kind(), &ErrorKind::TypeMismatch);
+    assert_eq!(json::from_str::<SocketAddr>(r#""localhost""#).unwrap_err().kind(), &ErrorKind::TypeMismatch);
+
+    let error = json::from_str::<Duration>(r#"{"secs":1,"nanos":1000000000}"#).unwrap_err();
+    assert_eq!(error.kind(), &ErrorKind::NumberOverflow);
+    let error = json::from_str::<Duration>(r#"{"secs":-1,"nanos":0}"#).unwrap_err();
+    assert_eq!(error.kind(), &ErrorKind::NumberOverflow);
+    let error = json::from_str::<Duration>(r#"{"secs":1}"#).unwrap_err();
+    assert_eq!(error.kind(), &ErrorKind::MissingField("nanos"));
+
+    let error = json::from_str::<Departure>(r#"{"flight_id":0}"#).unwrap_err();
+    assert_eq!((error.kind(), error.path()), (&ErrorKind::NumberOverflow, "flight_id"));
+}
@@ -0,0 +1,156 @@
+use serde::json::Value;
+use serde::{json, Deserialize, ErrorKind, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Flight {
+    id: u32,
+    source: String,
+    price: f32,
+    stops: Vec<String>,
+    delay: Option<i64>,
+}
+
+fn flight() -> Flight {
+    Flight {
+        id: 7,
+        source: "LAS".to_owned(),
+        price: 150.99,
+        stops: vec!["SIN".to_owned()],
+        delay: Some(-3),
+    }
+}
+
+#[test]
+fn value_should_match_parsed_json() {
+    let value = json::to_value(&flight());
+    let parsed = json::from_str::<Value>(&json::to_string(&flight())).unwrap();
+    assert_eq!(value, parsed);
+    let sorted = json::Serializer::new().sort_keys(true).to_string(&value).unwrap();
+    assert_eq!(sorted, r#"{"delay":-3,"id":7,"price":150.99,"source":"LAS","stops":["SIN"]}"#);
+
+    assert_eq!(json::to_string(&json::to_value(&f64::NAN)), "null");
+    assert_eq!(json::to_string(&json::to_value(&Vec::<u32>::new())), "[]");
+}
+
+#[test]
+fn value_should_convert_back() {
+    assert_eq!(json::from_value::<Flight>(json::to_value(&flight())).unwrap(), flight());
+
+    let mut value = json::to_value(&flight());
+    if let Value::Object(object) = &mut value {
+        object.insert("id".to_owned(), json!(8));
+    }
+    assert_eq!(json::from_value::<Flight>(value).unwrap().id, 8);
+}
+
+#[test]
+fn from_value_should_report_path() {
+    let value = json!({"id": 7, "source": "LAS", "price": 1, "stops": ["SIN", 3], "delay": null});
+    let error = json::from_value::<Flight>(value).unwrap_err();
+    assert_eq!(error.kind(), &ErrorKind::TypeMismatch);
+    assert_eq!(error.path(), "stops[1]");
+
+    let error = json::from_value::<u8>(json!(256)).unwrap_err();
+    assert_eq!(error.kind(), &ErrorKind::NumberOverflow);
+}
+
+#[test]
+fn json_macro_should_build_literals() {
+    let id = 7u32;
+    let stops = vec!["SIN"];
+    let value = json!({
+        "delay": null,
+        "extra": {"nested": [1, -2, [], {}, null, true]},
+        "id": id,
+        "price": -150.99,
+        "source": "LAS",
+        "stops": stops,
+    });
+    let expected = r#"{"delay":null,"extra":{"nested":[1,-2,[],{},null,true]},"id":7,"price":-150.99,"source":"LAS","stops":["SIN"]}"#;
+    assert_eq!(json::to_string(&value), expected);
+    assert_eq!(json::to_string(&json!([id + 1, "x"])), r#"[8,"x"]"#);
+    assert_eq!(json::to_string(&json!(null)), "null");
+}
+
+#[test]
+fn deep_values_should_not_overflow_the_stack() {
+    let text = format!("{}{}", "[".repeat(100_000), "]".repeat(100_000));
+    let value = json::from_str::<Value>(&text).unwrap();
+    let copy = json::to_value(&value);
+    let copy = json::from_value::<Value>(copy).unwrap();
+    assert_eq!(json::to_string(&copy), text);
+    assert_eq!(copy, value);
+}
+
+#[test]
+fn value_should_be_indexed() {
+    let value = json!({"flights": [{"id": 7, "source": "LAS", "delay": -3, "price": 150.99}], "a/b": {"~": true}});
+    assert_eq!(value["flights"][0]["id"], 7);
+    assert_eq!(value["flights"][0]["source"], "LAS");
+    assert_eq!(value["flights"][0]["id"].as_u64(), Some(7));
+    assert_eq!(value["flights"][0]["delay"].as_i64(), Some(-3));
+    assert_eq!(value["flights"][0]["delay"].as_u64(), None);
+    assert_eq!(value["flights"][0]["price"].as_f64(), Some(150.99));
+    assert_eq!(value["flights"].as_array().map(|flights| flights.len()), Some(1));
+    assert!(value["flights"][1]["id"].is_null());
+    assert!(value["missing"]["id"].is_null());
+    assert_eq!(value.get("flights").and_then(|flights| flights.get(0)), value.pointer("/flights/0"));
+    assert_eq!(value.get("missing".to_owned()), None);
+
+    assert_eq!(value.pointer(""), Some(&value));
+    assert_eq!(value.pointer("/flights/0/source").and_then(Value::as_str), Some("LAS"));
+    assert_eq!(value.pointer("/a~1b/~0"), Some(&Value::Bool(true)));
+    assert_eq!(value.pointer("/flights/00"), None);
+    assert_eq!(value.pointer("flights"), None);
+}
+
+#[test]
+fn value_should_be_patched() {
+    let mut value = json!({"flights": [{"id": 7}]});
+    *value.pointer_mut("/flights/0/id").unwrap() = Value::from(8u32);
+    if let Some(flights) = value["flights"].as_array().cloned() {
+        value.as_object_mut().unwrap().insert("count".to_owned(), Value::from(flights.len()));
+    }
+    assert_eq!(value, json!({"count": 1, "flights": [{"id": 8}]}));
+}
+
+#[test]
+fn values_should_compare_by_content() {
+    assert_eq!(Value::from(5i64), json::from_str::<Value>("5").unwrap());
+    assert_eq!(json::Number::I64(5), json::Number::U64(5));
+    assert_ne!(Value::from(5), Value::from(5.0));
+    assert_eq!(Value::from(150.99f32), json!(150.99));
+    assert_eq!(Value::from(f64::NAN), Value::Null);
+    assert_eq!(Value::from(vec![Some("LAS"), None]), json!(["LAS", null]));
+    assert_ne!(json!({"id": 7}), json!({"id": 7, "seats": 0}));
+    assert_ne!(json!([1, 2]), json!([2, 1]));
+}
+
+#[test]
+fn value_should_display_as_json() {
+    let value = json!({"id": 7, "stops": ["SIN"]});
+    assert_eq!(value.to_string(), r#"{"id":7,"stops":["SIN"]}"#);
+    assert_eq!(format!("{:#}", value), "{\n  \"id\": 7,\n  \"stops\": [\n    \"SIN\"\n  ]\n}");
+
+    for f in [1e21, 1e-7, 150.99, -0.0, 5.0] {
+        let number = json::Number::F64(f);
+        assert_eq!(number.to_string(), json::to_string(&number));
+    }
+}
+
+#[cfg(not(feature = "preserve_order"))]
+#[test]
+fn object_entries_should_be_sorted_by_key() {
+    let value = json::from_str::<Value>(r#"{"source":"LAS","id":7,"delay":null}"#).unwrap();
+    assert_eq!(json::to_string(&value), r#"{"delay":null,"id":7,"source":"LAS"}"#);
+}
+
+#[cfg(feature = "preserve_order")]
+#[test]
+fn object_entries_should_keep_insertion_order() {
+    let text = r#"{"source":"LAS","id":7,"delay":null}"#;
+    let value = json::from_str::<Value>(text).unwrap();
+    assert_eq!(json::to_string(&value), text);
+    assert_eq!(json::to_string(&json::to_value(&flight())), r#"{"id":7,"source":"LAS","price":150.99,"stops":["SIN"],"delay":-3}"#);
+    assert_eq!(value, json!({"delay": null, "id": 7, "source": "LAS"}));
+}
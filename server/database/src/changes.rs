@@ -0,0 +1,81 @@
+use crate::types::Flight;
+
+/// Change of a flight between two reads of the flights of a route.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FlightChange {
+    /// The flight was not in the previous read.
+    Added(Flight),
+    /// Seat availability went from at least the threshold to below it.
+    SeatsBelowThreshold { flight_id: u32, seat_available: u32 },
+    AirfareChanged { flight_id: u32, previous: f32, airfare: f32 },
+}
+
+/// Changes from the `previous` flights of a route to the `current` ones, flights are matched by id.
+///
+/// Seats are only reported when they cross `seat_threshold`, not on every change below it. Removed flights are not
+/// reported, flights are never deleted.
+pub fn detect_changes(previous: &[Flight], current: &[Flight], seat_threshold: u32) -> Vec<FlightChange> {
+    let mut changes = Vec::new();
+    for flight in current {
+        let before = match previous.iter().find(|f| f.id == flight.id) {
+            Some(before) => before,
+            None => {
+                changes.push(FlightChange::Added(flight.clone()));
+                continue;
+            }
+        };
+        if before.seat_available >= seat_threshold && flight.seat_available < seat_threshold {
+            changes.push(FlightChange::SeatsBelowThreshold { flight_id: flight.id, seat_available: flight.seat_available });
+        }
+        if before.airfare != flight.airfare {
+            changes.push(FlightChange::AirfareChanged { flight_id: flight.id, previous: before.airfare, airfare: flight.airfare });
+        }
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flights() -> Vec<Flight> {
+        vec![Flight::new(1, "LAS", "HAN", 1680105600, 500, 150.99), Flight::new(8, "LAS", "HAN", 1680130800, 300, 120.99)]
+    }
+
+    #[test]
+    fn unchanged_flights_should_have_no_changes() {
+        assert_eq!(detect_changes(&flights(), &flights(), 100), []);
+    }
+
+    #[test]
+    fn new_flight_should_be_added() {
+        let mut current = flights();
+        current.push(Flight::new(11, "LAS", "HAN", 1680134400, 50, 99.99));
+        let changes = detect_changes(&flights(), &current, 100);
+        assert_eq!(changes, [FlightChange::Added(current[2].clone())], "Below threshold on arrival is not a crossing");
+    }
+
+    #[test]
+    fn seats_should_be_reported_when_crossing_the_threshold() {
+        let mut current = flights();
+        current[1].seat_available = 99;
+        assert_eq!(
+            detect_changes(&flights(), &current, 100),
+            [FlightChange::SeatsBelowThreshold { flight_id: 8, seat_available: 99 }]
+        );
+
+        let mut later = current.clone();
+        later[1].seat_available = 98;
+        assert_eq!(detect_changes(&current, &later, 100), [], "Already below threshold");
+    }
+
+    #[test]
+    fn airfare_change_should_be_reported() {
+        let mut current = flights();
+        current[0].airfare = 180.0;
+        assert_eq!(
+            detect_changes(&flights(), &current, 100),
+            [FlightChange::AirfareChanged { flight_id: 1, previous: 150.99, airfare: 180.0 }]
+        );
+    }
+}
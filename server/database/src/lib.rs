@@ -9,3 +9,6 @@ pub use self::schema::*;
 
 pub mod connection;
 pub use self::connection::*;
+
+pub mod changes;
+pub use self::changes::*;
@@ -1,9 +1,11 @@
+use crate::types::Flight;
 use rand::seq::SliceRandom;
 use rand::SeedableRng;
 use rusqlite::{Connection, Result};
 
 pub fn define_schemas(connection: &Connection) -> Result<()> {
     connection.execute("DROP TABLE IF EXISTS flight_informations", ())?;
+    connection.execute("DROP TABLE IF EXISTS reservations", ())?;
 
     let _define_flight_info_schema = "\
             CREATE TABLE IF NOT EXISTS flight_informations (\
@@ -21,7 +23,7 @@ pub fn define_schemas(connection: &Connection) -> Result<()> {
             CREATE TABLE IF NOT EXISTS reservations (\
                 id INTEGER PRIMARY KEY, \
                 flight_id INTEGER NOT NULL, \
-                client_ip TEXT NOT NULL, \
+                client_id TEXT NOT NULL, \
                 seat_reserved INTEGER NOT NULL, \
                 luggage_amount INTEGER DEFAULT 0
             )
@@ -66,6 +68,22 @@ pub fn seed_db(connection: &Connection) -> Result<()> {
     Ok(())
 }
 
+/// Insert the given flights, their ids are ignored and assigned by the database.
+pub fn seed_db_with_flights(connection: &Connection, flights: &[Flight]) -> Result<()> {
+    let insert_query = "\
+            INSERT INTO flight_informations \
+            (source, destination, departure_time, seat_available, airfare) \
+            VALUES (?1, ?2, ?3, ?4, ?5)
+        ";
+    for flight in flights {
+        connection.execute(
+            insert_query,
+            (&flight.source, &flight.destination, flight.departure_time, flight.seat_available, flight.airfare),
+        )?;
+    }
+    Ok(())
+}
+
 pub fn seed_test_db_with_hardcode_data(connection: &Connection) -> Result<()> {
     let insert = move |source: &str, destination: &str, departure_time: i32, seat_available: u32, airfare: f32| -> Result<()> {
         let _insert_query = "\
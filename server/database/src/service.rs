@@ -63,6 +63,30 @@ impl<'a> DatabaseService<'a> {
             .unwrap()
     }
 
+    /// Route monitor: <source, destination> -> Vec<Flight>, ordered by id
+    pub async fn get_flights_on_route(&self, source: String, destination: String) -> Result<Vec<Flight>> {
+        let pool_conn = self.pool.get().await.unwrap();
+        pool_conn
+            .interact(move |connection| {
+                const GET_ROUTE_FLIGHTS_QUERY: &str = "SELECT * from flight_informations WHERE source = ?1 AND destination = ?2 ORDER BY id";
+                let mut stmt = connection.prepare(GET_ROUTE_FLIGHTS_QUERY)?;
+
+                let flights = stmt.query_map(params![source, destination], |row| {
+                    Ok(Flight {
+                        id: row.get(0)?,
+                        source: row.get(1)?,
+                        destination: row.get(2)?,
+                        departure_time: row.get(3)?,
+                        seat_available: row.get(4)?,
+                        airfare: row.get(5)?,
+                    })
+                })?;
+                flights.collect::<Result<Vec<Flight>>>()
+            })
+            .await
+            .unwrap()
+    }
+
     /// Service 2: <id> -> <departure_time, seat_available, airfare>
     pub async fn get_flight_info(&self, id: u32) -> Result<Option<(i32, f32, u32)>> {
         let flight = self.get_flight_by_id(id).await?;
@@ -71,7 +95,7 @@ impl<'a> DatabaseService<'a> {
     }
 
     /// Service 3: <id, num_seat> -> ReservationStatus
-    pub async fn make_reservation(&self, flight_id: u32, client_ip: String, num_seat: u32) -> Result<ReservationStatus> {
+    pub async fn make_reservation(&self, flight_id: u32, client_id: String, num_seat: u32) -> Result<ReservationStatus> {
         if num_seat == 0 {
             return Ok(ReservationStatus::ZeroSeatReserved);
         }
@@ -86,7 +110,7 @@ impl<'a> DatabaseService<'a> {
             return Ok(ReservationStatus::InsufficientCapacity);
         }
 
-        let is_reservation_existed = self.get_reservation_by_client(flight_id, client_ip.clone()).await?.is_some();
+        let is_reservation_existed = self.get_reservation_by_client(flight_id, client_id.clone()).await?.is_some();
 
         let pool_conn = self.pool.get().await.unwrap();
         let result = pool_conn
@@ -97,13 +121,13 @@ impl<'a> DatabaseService<'a> {
                 match is_reservation_existed {
                     true => {
                         const UPDATE_RESERVATION_QUERY: &str =
-                            "UPDATE reservations SET seat_reserved = seat_reserved + ?3 WHERE flight_id = ?1 AND client_ip = ?2";
-                        transaction.execute(UPDATE_RESERVATION_QUERY, params![flight_id, client_ip, num_seat])?;
+                            "UPDATE reservations SET seat_reserved = seat_reserved + ?3 WHERE flight_id = ?1 AND client_id = ?2";
+                        transaction.execute(UPDATE_RESERVATION_QUERY, params![flight_id, client_id, num_seat])?;
                         transaction.commit().map(|_| ReservationStatus::Updated)
                     }
                     false => {
-                        const MAKE_RESERVATION_QUERY: &str = "INSERT INTO reservations (flight_id, client_ip, seat_reserved) VALUES (?1, ?2, ?3)";
-                        transaction.execute(MAKE_RESERVATION_QUERY, params![flight_id, client_ip, num_seat])?;
+                        const MAKE_RESERVATION_QUERY: &str = "INSERT INTO reservations (flight_id, client_id, seat_reserved) VALUES (?1, ?2, ?3)";
+                        transaction.execute(MAKE_RESERVATION_QUERY, params![flight_id, client_id, num_seat])?;
                         transaction.commit().map(|_| ReservationStatus::Created)
                     }
                 }
@@ -113,18 +137,18 @@ impl<'a> DatabaseService<'a> {
         result
     }
 
-    pub async fn get_reservation_by_client(&self, flight_id: u32, client_ip: String) -> Result<Option<Reservation>> {
+    pub async fn get_reservation_by_client(&self, flight_id: u32, client_id: String) -> Result<Option<Reservation>> {
         let pool_conn = self.pool.get().await.unwrap();
         pool_conn
             .interact(move |connection| {
-                const GET_RESERVATION_DETAILS: &str = "SELECT * from reservations WHERE flight_id = ?1 AND client_ip = ?2";
+                const GET_RESERVATION_DETAILS: &str = "SELECT * from reservations WHERE flight_id = ?1 AND client_id = ?2";
                 let mut stmt = connection.prepare(GET_RESERVATION_DETAILS)?;
 
-                let reservations = stmt.query_map(params![flight_id, client_ip], |row| {
+                let reservations = stmt.query_map(params![flight_id, client_id], |row| {
                     Ok(Reservation {
                         id: row.get(0)?,
                         flight_id: row.get(1)?,
-                        client_ip: row.get(2)?,
+                        client_id: row.get(2)?,
                         seat_reserved: row.get(3)?,
                         luggage_amount: row.get(4)?,
                     })
@@ -139,10 +163,10 @@ impl<'a> DatabaseService<'a> {
             .unwrap()
     }
 
-    /// Service 5: <flight_id, client_ip> -> ReservationStatus
+    /// Service 5: <flight_id, client_id> -> ReservationStatus
     /// NOTE: This function is idempotent
-    pub async fn cancel_reservation(&self, flight_id: u32, client_ip: String) -> Result<CancellationStatus> {
-        let reservation_detail = self.get_reservation_by_client(flight_id, client_ip.clone()).await?;
+    pub async fn cancel_reservation(&self, flight_id: u32, client_id: String) -> Result<CancellationStatus> {
+        let reservation_detail = self.get_reservation_by_client(flight_id, client_id.clone()).await?;
         if reservation_detail.is_none() {
             return Ok(CancellationStatus::ReservationNotExisted);
         }
@@ -158,8 +182,8 @@ impl<'a> DatabaseService<'a> {
                 const UPDATE_RESERVATION_QUERY: &str = "UPDATE flight_informations SET seat_available = seat_available + ?2 WHERE id = ?1";
                 transaction.execute(UPDATE_RESERVATION_QUERY, params![flight_id, num_reserved])?;
 
-                const CANCEL_RESERVATION_QUERY: &str = "DELETE FROM reservations WHERE flight_id = ?1 AND client_ip = ?2";
-                transaction.execute(CANCEL_RESERVATION_QUERY, params![flight_id, client_ip])?;
+                const CANCEL_RESERVATION_QUERY: &str = "DELETE FROM reservations WHERE flight_id = ?1 AND client_id = ?2";
+                transaction.execute(CANCEL_RESERVATION_QUERY, params![flight_id, client_id])?;
 
                 match transaction.commit() {
                     Ok(_) => Ok(CancellationStatus::Success),
@@ -170,10 +194,10 @@ impl<'a> DatabaseService<'a> {
             .unwrap()
     }
 
-    /// Service 6: <flight_id, client_ip, amount_in_kg> -> BuyLuggageStatus
+    /// Service 6: <flight_id, client_id, amount_in_kg> -> BuyLuggageStatus
     /// NOTE: This function is non-idempotent
-    pub async fn buy_luggage(&self, flight_id: u32, client_ip: String, amount_in_kg: u32) -> Result<BuyLuggageStatus> {
-        let is_reservation_existed = self.get_reservation_by_client(flight_id, client_ip.clone()).await?.is_some();
+    pub async fn buy_luggage(&self, flight_id: u32, client_id: String, amount_in_kg: u32) -> Result<BuyLuggageStatus> {
+        let is_reservation_existed = self.get_reservation_by_client(flight_id, client_id.clone()).await?.is_some();
         if !is_reservation_existed {
             return Ok(BuyLuggageStatus::ReservationNotExisted);
         }
@@ -185,8 +209,8 @@ impl<'a> DatabaseService<'a> {
                 let transaction = connection.transaction()?;
 
                 const UPDATE_FLIGHT_QUERY: &str =
-                    "UPDATE reservations SET luggage_amount = luggage_amount + ?3 WHERE flight_id = ?1 AND client_ip = ?2";
-                transaction.execute(UPDATE_FLIGHT_QUERY, params![flight_id, client_ip, amount_in_kg])?;
+                    "UPDATE reservations SET luggage_amount = luggage_amount + ?3 WHERE flight_id = ?1 AND client_id = ?2";
+                transaction.execute(UPDATE_FLIGHT_QUERY, params![flight_id, client_id, amount_in_kg])?;
 
                 match transaction.commit() {
                     Ok(_) => Ok(BuyLuggageStatus::Success),
@@ -274,6 +298,20 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn get_flights_on_route_should_return_every_flight() -> Result<()> {
+        let file_path = "get_flights_on_route_should_return_every_flight.db";
+        if PathBuf::from(file_path).exists() {
+            fs::remove_file(file_path).unwrap();
+        }
+        let pool = seed_db_and_prepare_pool(file_path).await?;
+        let service = DatabaseService::new(&pool)?;
+        let flights = service.get_flights_on_route("LAS".to_string(), "HAN".to_string()).await?;
+        assert_eq!(flights.iter().map(|f| f.id).collect::<Vec<_>>(), [1, 8]);
+        assert_eq!(flights[0], service.get_flight_by_id(1).await?.unwrap());
+        Ok(())
+    }
+
     #[tokio::test]
     async fn make_reservation_should_success_twice() -> Result<()> {
         let file_path = "make_reservation_should_success_twice.db";
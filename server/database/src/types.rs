@@ -31,17 +31,17 @@ impl Flight {
 pub struct Reservation {
     pub id: u32,
     pub flight_id: u32,
-    pub client_ip: String,
+    pub client_id: String,
     pub seat_reserved: u32,
     pub luggage_amount: u32,
 }
 
 impl Reservation {
-    pub fn new(id: u32, flight_id: u32, client_ip: &str, seat_reserved: u32) -> Self {
+    pub fn new(id: u32, flight_id: u32, client_id: &str, seat_reserved: u32) -> Self {
         Reservation {
             id,
             flight_id,
-            client_ip: client_ip.to_string(),
+            client_id: client_id.to_string(),
             seat_reserved,
             luggage_amount: 0,
         }
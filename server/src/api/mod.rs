@@ -3,3 +3,8 @@ pub use types::*;
 
 mod service_handler;
 pub use service_handler::*;
+
+pub mod router;
+pub use router::{Endpoint, Router, Service};
+
+pub mod services;
@@ -0,0 +1,169 @@
+use super::{Result, ServiceHandler};
+use core::future::Future;
+use core::pin::Pin;
+use rpc_contracts::body::contracts::ServiceFailedResponse;
+use rpc_contracts::{DecodeBody, EncodeBody, RPCRequest, RPCResponse};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Future returned by [`Endpoint::handle`].
+pub type HandleFuture<'a> = Pin<Box<dyn Future<Output = ()> + Send + 'a>>;
+
+/// A service answering every request with exactly one response.
+///
+/// The [`Router`] decodes the request body, replies with the encoded response when the call succeeds, or with a
+/// [`ServiceFailedResponse`] holding the error message when decoding or the call fails.
+pub trait Service: Send + Sync + 'static {
+    /// Body of the request, may borrow from the request.
+    type Request<'de>: Deserialize<'de> + Send;
+    type Response: Serialize + Send;
+
+    fn call<'a>(&'a self, handler: &'a ServiceHandler<'static>, request: Self::Request<'a>) -> impl Future<Output = Result<Self::Response>> + Send + 'a;
+}
+
+/// Handles a request from start to end, sending its replies itself.
+///
+/// Every [`Service`] is an endpoint once registered. Services that reply more than once, like monitors, or never, like
+/// acknowledgements, implement this trait directly.
+pub trait Endpoint: Send + Sync + 'static {
+    fn handle<'a>(&'a self, handler: &'a ServiceHandler<'static>, request: RPCRequest) -> HandleFuture<'a>;
+}
+
+struct ServiceEndpoint<S>(S);
+
+impl<S: Service> Endpoint for ServiceEndpoint<S> {
+    fn handle<'a>(&'a self, handler: &'a ServiceHandler<'static>, request: RPCRequest) -> HandleFuture<'a> {
+        Box::pin(async move {
+            let result = match request.decode_body::<S::Request<'_>>() {
+                Ok(body) => self.0.call(handler, body).await,
+                Err(_e) => Err(_e.into()),
+            };
+            let response = match result {
+                Ok(body) => {
+                    let mut response = RPCResponse::finished(&request).await;
+                    response.encode_body(body);
+                    response
+                }
+                Err(_e) => {
+                    let mut response = RPCResponse::failed(&request).await;
+                    response.encode_body(ServiceFailedResponse { error: _e.to_string() }); // encode error message in response
+                    response
+                }
+            };
+            handler.reply(&response).await;
+        })
+    }
+}
+
+/// Dispatch table from service type to the endpoint handling it.
+///
+/// ```ignore
+/// let router = Router::new().register(1, QueryFlightIds).register_endpoint(4, MonitorFlight);
+/// ```
+#[derive(Default)]
+pub struct Router {
+    endpoints: HashMap<u8, Box<dyn Endpoint>>,
+}
+
+impl Router {
+    pub fn new() -> Self {
+        Router::default()
+    }
+
+    /// Handle the requests of service type `id` with `service`, replacing any previous registration.
+    pub fn register<S: Service>(self, id: u8, service: S) -> Self {
+        self.register_endpoint(id, ServiceEndpoint(service))
+    }
+
+    /// Same as [`register`](Router::register) for an endpoint sending its own replies.
+    pub fn register_endpoint<E: Endpoint>(mut self, id: u8, endpoint: E) -> Self {
+        self.endpoints.insert(id, Box::new(endpoint));
+        self
+    }
+
+    /// Handle a request with the endpoint registered for its service type, unknown types get a failed reply.
+    pub async fn dispatch(&self, handler: &ServiceHandler<'static>, request: RPCRequest) {
+        match self.endpoints.get(&request.service_type) {
+            Some(endpoint) => endpoint.handle(handler, request).await,
+            None => handler.invalid_service_type(request).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::APIError;
+    use crate::monitor::MonitorRegistry;
+    use database::{get_connection_pool, DatabaseService};
+    use rpc_contracts::body::contracts::Service2RequestBody;
+    use rpc_contracts::{Codec, ResponseStatus};
+    use std::sync::Arc;
+    use tokio::net::UdpSocket;
+    use tokio::sync::Mutex;
+
+    /// Doubles the flight id, fails on zero.
+    struct Double;
+
+    impl Service for Double {
+        type Request<'de> = Service2RequestBody;
+        type Response = Service2RequestBody;
+
+        async fn call<'a>(&'a self, _: &'a ServiceHandler<'static>, request: Service2RequestBody) -> Result<Service2RequestBody> {
+            match request.flight_id {
+                0 => Err(APIError::ParametersOutOfBounds),
+                flight_id => Ok(Service2RequestBody { flight_id: 2 * flight_id }),
+            }
+        }
+    }
+
+    async fn dispatch(router: &Router, request: RPCRequest) -> RPCResponse {
+        let client = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let socket = Arc::new(UdpSocket::bind("127.0.0.1:0").await.unwrap());
+        // the pool only connects on first use, these services never touch the database
+        let db_service = Arc::new(Mutex::new(DatabaseService::new(get_connection_pool()).unwrap()));
+        let monitors = Arc::new(Mutex::new(MonitorRegistry::new()));
+        let client_addr = client.local_addr().unwrap().to_string();
+        let handler = ServiceHandler::new(socket, client_addr, request.client_id, db_service, None, monitors).await;
+
+        router.dispatch(&handler, request).await;
+        let mut buffer = [0; 512];
+        let size = client.recv(&mut buffer).await.unwrap();
+        RPCResponse::from_bytes(&buffer[..size]).unwrap()
+    }
+
+    async fn request(service_type: u8, body: impl Serialize) -> RPCRequest {
+        let mut request = RPCRequest::new(service_type, Codec::Json).await;
+        request.encode_body(body);
+        request
+    }
+
+    #[tokio::test]
+    async fn registered_service_should_reply_with_its_response() {
+        let router = Router::new().register(9, Double);
+        let response = dispatch(&router, request(9, Service2RequestBody { flight_id: 21 }).await).await;
+        assert!(matches!(response.status, ResponseStatus::Finished));
+        assert_eq!(response.decode_body::<Service2RequestBody>().unwrap().flight_id, 42);
+    }
+
+    #[tokio::test]
+    async fn failures_should_reply_with_their_message() {
+        let router = Router::new().register(9, Double);
+        let failures = [
+            (request(9, Service2RequestBody { flight_id: 0 }).await, APIError::ParametersOutOfBounds.to_string()),
+            (request(9, "not a body").await, "Request deserialization error: invalid type, line 1 column 13".to_owned()),
+        ];
+        for (request, message) in failures {
+            let response = dispatch(&router, request).await;
+            assert!(matches!(response.status, ResponseStatus::Failed));
+            assert_eq!(response.decode_body::<ServiceFailedResponse>().unwrap().error, message);
+        }
+    }
+
+    #[tokio::test]
+    async fn unknown_service_type_should_fail() {
+        let response = dispatch(&Router::new(), request(9, Service2RequestBody { flight_id: 21 }).await).await;
+        assert!(matches!(response.status, ResponseStatus::Failed));
+        assert_eq!(response.decode_body::<String>().unwrap(), "Invalid service type");
+    }
+}
@@ -1,152 +1,106 @@
-use super::{APIError, Result};
-use database::types::{BuyLuggageStatus, CancellationStatus, ReservationStatus};
-use database::DatabaseService;
+use super::APIError;
+use crate::history::RequestHistory;
+use crate::monitor::{run_monitor, Delivery, MonitorRegistry, MonitorSource, Topic, ROUTE_POLL_INTERVAL};
+use database::types::{Flight, ReservationStatus};
+use database::{detect_changes, DatabaseService, FlightChange};
 use rpc_contracts::body::contracts::*;
 use rpc_contracts::{DecodeBody, EncodeBody, RPCRequest, RPCResponse};
+use serde::Serialize;
 use std::sync::Arc;
+use std::time::Instant;
 use tokio::net::UdpSocket;
-use tokio::sync::mpsc::{Receiver, Sender};
 use tokio::sync::Mutex;
-use tokio::sync::Notify;
-use tokio::time::{sleep, Duration};
+use tokio::time::Duration;
 
 pub struct ServiceHandler<'a> {
     pub socket: Arc<UdpSocket>,
+    /// Where replies are sent.
     pub client_addr: String,
+    /// Identity of the client, its reservations and subscriptions do not depend on its address.
+    pub client_id: u64,
     pub db_service: Arc<Mutex<DatabaseService<'a>>>,
-    sender: &'static Sender<(u32, ReservationStatus)>,
-    receiver: &'static mut Receiver<(u32, ReservationStatus)>,
-    notify: &'static Notify,
-}
-
-macro_rules! use_internal_handler {
-    ($handler:ident, $internal_service:ident, $_req:ident) => {
-        match $handler.$internal_service(&$_req).await {
-            Ok(response) => response,
-            Err(_e) => {
-                let mut response = RPCResponse::failed($_req.id).await;
-                response.encode_body(ServiceFailedResponse { error: _e.to_string() }); // encode error message in response
-                response
-            }
-        }
-    };
+    history: Option<Arc<Mutex<RequestHistory>>>,
+    monitors: Arc<Mutex<MonitorRegistry>>,
+    /// Print every reply sent.
+    pub verbose: bool,
 }
 
 impl<'a> ServiceHandler<'a> {
     pub async fn new(
-        socket: Arc<UdpSocket>, client_addr: String, db_service: Arc<Mutex<DatabaseService<'a>>>, sender: &'static Sender<(u32, ReservationStatus)>,
-        receiver: &'static mut Receiver<(u32, ReservationStatus)>, notify: &'static Notify,
+        socket: Arc<UdpSocket>, client_addr: String, client_id: u64, db_service: Arc<Mutex<DatabaseService<'a>>>, history: Option<Arc<Mutex<RequestHistory>>>,
+        monitors: Arc<Mutex<MonitorRegistry>>,
     ) -> ServiceHandler<'a> {
         ServiceHandler {
             socket,
             client_addr,
+            client_id,
             db_service,
-            sender,
-            receiver,
-            notify,
+            history,
+            monitors,
+            verbose: false,
         }
     }
 
-    pub async fn invalid_service_type(&self, _req: RPCRequest) {
-        let response = RPCResponse::failed_invalid_service_type(_req).await;
-        let response_string = serde::json::to_string(&response);
-        self.socket
-            .clone()
-            .send_to(response_string.as_bytes(), self.client_addr.clone())
-            .await
-            .ok();
+    fn log_reply(&self, response: &RPCResponse) {
+        if self.verbose {
+            println!("Reply to {}:\n{}", self.client_addr, response.to_pretty_json());
+        }
     }
 
-    pub async fn service_1(&self, _req: RPCRequest) {
-        let response = use_internal_handler!(self, internal_service_1, _req);
-        let response_string = serde::json::to_string(&response);
-        self.socket.send_to(response_string.as_bytes(), &self.client_addr).await.unwrap();
+    async fn send_datagram(&self, datagram: &[u8]) {
+        self.socket.send_to(datagram, &self.client_addr).await.ok();
     }
-    async fn internal_service_1(&self, _req: &RPCRequest) -> Result<RPCResponse> {
-        let Service1RequestBody { source, destination } = _req.decode_body()?;
 
-        let db_service = self.db_service.lock().await;
-        let flight_ids: Vec<u32> = db_service.get_flight_ids(source, destination).await?;
-
-        let mut response = RPCResponse::finished(_req.id).await;
-        response.encode_body(Service1ResponseBody { flight_ids });
-
-        Ok(response)
+    /// Tell the client of a monitor that it ended with the server. The reply stored in the request history for the monitor
+    /// request is kept, a retransmitted request still gets its original outcome.
+    async fn send_monitor_closed(&self, request: &RPCRequest) {
+        let mut response = RPCResponse::failed(request).await;
+        response.encode_body(ServiceFailedResponse { error: APIError::ShuttingDown.to_string() });
+        self.log_reply(&response);
+        self.send_datagram(&response.to_bytes()).await;
     }
 
-    pub async fn service_2(&self, _req: RPCRequest) {
-        let response = use_internal_handler!(self, internal_service_2, _req);
-        let response_string = serde::json::to_string(&response);
-        self.socket.send_to(response_string.as_bytes(), &self.client_addr).await.unwrap();
+    /// Datagram of a monitor update, sent without being recorded in the request history.
+    async fn monitor_update(&self, request: &RPCRequest, body: impl Serialize) -> Vec<u8> {
+        let mut update_response = RPCResponse::updated(request).await;
+        update_response.encode_body(body);
+        self.log_reply(&update_response);
+        update_response.to_bytes()
     }
-    async fn internal_service_2(&self, _req: &RPCRequest) -> Result<RPCResponse> {
-        let Service2RequestBody { flight_id } = _req.decode_body()?;
-
-        let db_service = self.db_service.lock().await;
-
-        let result_flight_info: Option<(i32, f32, u32)> = db_service.get_flight_info(flight_id).await?;
-        match result_flight_info {
-            Some(flight_info) => {
-                let (departure_time, airfare, seat_avail) = flight_info;
-                let mut response = RPCResponse::finished(_req.id).await;
-                response.encode_body(Service2ResponseBody {
-                    departure_time,
-                    airfare,
-                    seat_avail,
-                });
 
-                Ok(response)
-            }
-            None => Err(APIError::RecordNotFound),
-        }
+    /// Reply to a monitor request that could not be established.
+    async fn send_error(&self, error: String, request: &RPCRequest) {
+        let mut response = RPCResponse::failed(request).await;
+        response.encode_body(ServiceFailedResponse { error }); // encode error message in response
+        self.reply(&response).await;
     }
 
-    pub async fn service_3(&self, _req: RPCRequest) {
-        let response = use_internal_handler!(self, internal_service_3, _req);
-        let response_string = serde::json::to_string(&response);
-        self.socket.send_to(response_string.as_bytes(), &self.client_addr).await.unwrap();
+    /// Send the reply of a request, recording it in the request history when at-most-once semantics is enabled.
+    pub async fn reply(&self, response: &RPCResponse) {
+        self.log_reply(response);
+        let response_bytes = response.to_bytes();
+        if let Some(history) = &self.history {
+            let key = (response.client_id, response.request_id);
+            history.lock().await.complete(&key, response_bytes.clone());
+        }
+        self.socket.send_to(&response_bytes, &self.client_addr).await.ok();
     }
-    async fn internal_service_3(&self, _req: &RPCRequest) -> Result<RPCResponse> {
-        let Service3RequestBody { flight_id, num_seat } = _req.decode_body()?;
-
-        let db_service = self.db_service.lock().await;
-        let reservation_status = db_service.make_reservation(flight_id, self.client_addr.clone(), num_seat).await?;
 
-        match reservation_status {
-            status @ (ReservationStatus::Created | ReservationStatus::Updated) => {
-                let message = status.to_string();
-                let mut success_response = RPCResponse::finished(_req.id).await;
-                success_response.encode_body(Service3ResponseBody { message });
-                // NOTE: send update for service 4
-                self.send_update(flight_id, status).await;
-                Ok(success_response)
-            }
-            ReservationStatus::InvalidFlightID => Err(APIError::RecordNotFound),
-            ReservationStatus::ZeroSeatReserved => Err(APIError::ParametersOutOfBounds),
-            ReservationStatus::InsufficientCapacity => Err(APIError::ParametersOutOfBounds),
-        }
+    pub async fn invalid_service_type(&self, _req: RPCRequest) {
+        let response = RPCResponse::failed_invalid_service_type(_req).await;
+        self.reply(&response).await;
     }
 
+    /// Notify every monitor of the flight that its seat availability changed.
     pub async fn send_update(&self, flight_id: u32, status: ReservationStatus) {
-        self.sender.send((flight_id, status)).await.ok();
-        self.notify.notify_one();
+        self.monitors.lock().await.publish(flight_id, status);
     }
 
-    pub async fn service_4(&mut self, _req: RPCRequest) {
-        async fn send_response(socket: Arc<UdpSocket>, response: RPCResponse, client_addr: &String) {
-            let response_string = serde::json::to_string(&response);
-            socket.send_to(response_string.as_bytes(), client_addr).await.ok();
-        }
-        async fn send_error(socket: Arc<UdpSocket>, error: String, request_id: u32, client_addr: &String) {
-            let mut response = RPCResponse::failed(request_id).await;
-            response.encode_body(ServiceFailedResponse { error }); // encode error message in response
-            send_response(socket, response, client_addr).await;
-        }
-
+    pub async fn service_4(&self, _req: RPCRequest) {
         let Service4RequestBody { flight_id, monitor_interval } = match _req.decode_body() {
             Ok(body) => body,
             Err(_e) => {
-                send_error(self.socket.clone(), _e.to_string(), _req.id, &self.client_addr).await;
+                self.send_error(_e.to_string(), &_req).await;
                 return;
             }
         };
@@ -155,97 +109,160 @@ impl<'a> ServiceHandler<'a> {
             Ok(true) => {}
             Ok(false) => {
                 let _e = APIError::RecordNotFound;
-                send_error(self.socket.clone(), _e.to_string(), _req.id, &self.client_addr).await;
+                self.send_error(_e.to_string(), &_req).await;
                 return;
             }
             Err(_e) => {
-                send_error(self.socket.clone(), _e.to_string(), _req.id, &self.client_addr).await;
+                self.send_error(_e.to_string(), &_req).await;
                 return;
             }
         }
 
-        let mut finished_response = RPCResponse::finished(_req.id).await;
+        let mut finished_response = RPCResponse::finished(&_req).await;
         finished_response.encode_body(Service4ResponseBody {
             message: "Monitor service successfully established".to_string(),
         });
-        send_response(self.socket.clone(), finished_response, &self.client_addr).await;
-
-        let monitor_interval = monitor_interval as u64;
-        loop {
-            let delay = sleep(Duration::from_secs(monitor_interval));
-            tokio::pin!(delay);
-
-            let received = tokio::select! {
-                received = self.recv_update() => received,
-                _ = &mut delay => break,
-            };
-            match received {
-                Some((id, _)) if flight_id != id => continue,
-                Some((_, _)) => {
-                    let db_service = self.db_service.lock().await;
-                    let (_, _, seat_avail) = db_service.get_flight_info(flight_id).await.unwrap().unwrap();
-                    let mut update_response = RPCResponse::updated(_req.id).await;
-                    update_response.encode_body(Service4MonitorResponseBody { seat_avail });
-                    send_response(self.socket.clone(), update_response, &self.client_addr).await;
-                }
-                None => {
-                    println!("Monitor channel closed!");
-                    break;
-                }
+        self.reply(&finished_response).await;
+
+        let interval = Duration::from_secs(monitor_interval as u64);
+        let expires_at = Instant::now() + interval;
+        let topic = Topic::Flight(flight_id);
+        let (subscription_id, mut events) = self.monitors.lock().await.subscribe(_req.key(), topic, interval);
+        let mut source = FlightMonitor { handler: self, request: &_req, flight_id, seat_avail: None };
+        run_monitor(&mut source, &mut events, expires_at, Delivery::Latest).await;
+        self.monitors.lock().await.unsubscribe(subscription_id);
+    }
+
+    pub async fn service_7(&self, _req: RPCRequest) {
+        let Service7RequestBody { source, destination, seat_threshold, monitor_interval } = match _req.decode_body() {
+            Ok(body) => body,
+            Err(_e) => {
+                self.send_error(_e.to_string(), &_req).await;
+                return;
             }
+        };
+        let (source, destination) = (source.into_owned(), destination.into_owned());
+
+        let flights = match self.db_service.lock().await.get_flights_on_route(source.clone(), destination.clone()).await {
+            Ok(flights) => flights,
+            Err(_e) => {
+                self.send_error(_e.to_string(), &_req).await;
+                return;
+            }
+        };
+
+        let mut finished_response = RPCResponse::finished(&_req).await;
+        finished_response.encode_body(Service7ResponseBody {
+            message: "Route monitor successfully established".to_string(),
+            flight_ids: flights.iter().map(|f| f.id).collect(),
+        });
+        self.reply(&finished_response).await;
+
+        let interval = Duration::from_secs(monitor_interval as u64);
+        let expires_at = Instant::now() + interval;
+        let topic = Topic::Route(source.clone(), destination.clone());
+        let (subscription_id, mut events) = self.monitors.lock().await.subscribe(_req.key(), topic, interval);
+        let mut source = RouteMonitor {
+            handler: self,
+            request: &_req,
+            source,
+            destination,
+            seat_threshold,
+            flights,
+            unsent: Vec::new(),
+        };
+        run_monitor(&mut source, &mut events, expires_at, Delivery::InOrder(ROUTE_POLL_INTERVAL)).await;
+        self.monitors.lock().await.unsubscribe(subscription_id);
+    }
+
+    /// Acknowledgement of Service 4 or Service 7 updates by the client, no reply is sent.
+    pub async fn monitor_ack(&self, _req: RPCRequest) {
+        match _req.decode_body::<MonitorAckRequestBody>() {
+            Ok(MonitorAckRequestBody { request_id, seq }) => {
+                self.monitors.lock().await.acknowledge((_req.client_id, request_id), seq);
+            }
+            Err(_e) => println!("Malformed acknowledgement from {}: {}", self.client_addr, _e),
+        }
+    }
+
+}
+
+/// Seat availability of a flight, for Service 4.
+struct FlightMonitor<'h, 'a> {
+    handler: &'h ServiceHandler<'a>,
+    request: &'h RPCRequest,
+    flight_id: u32,
+    seat_avail: Option<u32>,
+}
+
+impl MonitorSource for FlightMonitor<'_, '_> {
+    async fn read(&mut self) {
+        if let Ok(Some((_, _, seat_avail))) = self.handler.db_service.lock().await.get_flight_info(self.flight_id).await {
+            self.seat_avail = Some(seat_avail);
         }
     }
 
-    pub async fn recv_update(&mut self) -> Option<(u32, ReservationStatus)> {
-        self.notify.notified().await;
-        self.receiver.try_recv().ok()
+    async fn update(&mut self, seq: u32) -> Option<Vec<u8>> {
+        let seat_avail = self.seat_avail.take()?;
+        Some(self.handler.monitor_update(self.request, Service4MonitorResponseBody { seq, seat_avail }).await)
+    }
+
+    async fn send(&mut self, datagram: &[u8]) {
+        self.handler.send_datagram(datagram).await;
     }
 
-    pub async fn service_5(&self, _req: RPCRequest) {
-        let response = use_internal_handler!(self, internal_service_5, _req);
-        let response_string = serde::json::to_string(&response);
-        self.socket.send_to(response_string.as_bytes(), &self.client_addr).await.unwrap();
+    async fn close(&mut self) {
+        self.handler.send_monitor_closed(self.request).await;
     }
-    async fn internal_service_5(&self, _req: &RPCRequest) -> Result<RPCResponse> {
-        let db_service = self.db_service.lock().await;
-        let Service5RequestBody { flight_id } = _req.decode_body()?;
-        let client_addr = self.client_addr.clone();
+}
 
-        let cancellation_status = db_service.cancel_reservation(flight_id, client_addr).await?;
+/// Flights on a route, for Service 7.
+struct RouteMonitor<'h, 'a> {
+    handler: &'h ServiceHandler<'a>,
+    request: &'h RPCRequest,
+    source: String,
+    destination: String,
+    seat_threshold: u32,
+    flights: Vec<Flight>,
+    /// Changes not sent yet, gathered while the previous update waits for its acknowledgement.
+    unsent: Vec<RouteChange>,
+}
 
-        match cancellation_status {
-            status @ CancellationStatus::Success => {
-                let message = status.to_string();
-                let mut success_response = RPCResponse::finished(_req.id).await;
-                success_response.encode_body(Service5ResponseBody { message });
-                // NOTE: send update for service 4
-                self.send_update(flight_id, ReservationStatus::Updated).await;
-                Ok(success_response)
-            }
-            CancellationStatus::ReservationNotExisted => Err(APIError::RecordNotFound),
+impl MonitorSource for RouteMonitor<'_, '_> {
+    async fn read(&mut self) {
+        let current = self.handler.db_service.lock().await.get_flights_on_route(self.source.clone(), self.destination.clone()).await;
+        if let Ok(current) = current {
+            self.unsent.extend(detect_changes(&self.flights, &current, self.seat_threshold).into_iter().map(route_change));
+            self.flights = current;
         }
     }
 
-    pub async fn service_6(&self, _req: RPCRequest) {
-        let response = use_internal_handler!(self, internal_service_6, _req);
-        let response_string = serde::json::to_string(&response);
-        self.socket.send_to(response_string.as_bytes(), &self.client_addr).await.unwrap();
+    async fn update(&mut self, seq: u32) -> Option<Vec<u8>> {
+        if self.unsent.is_empty() {
+            return None;
+        }
+        let changes = std::mem::take(&mut self.unsent);
+        Some(self.handler.monitor_update(self.request, Service7MonitorResponseBody { seq, changes }).await)
     }
-    async fn internal_service_6(&self, _req: &RPCRequest) -> Result<RPCResponse> {
-        let db_service = self.db_service.lock().await;
-        let Service6RequestBody { flight_id, amount_in_kg } = _req.decode_body()?;
-        let client_addr = self.client_addr.clone();
 
-        let buy_status = db_service.buy_luggage(flight_id, client_addr, amount_in_kg).await?;
+    async fn send(&mut self, datagram: &[u8]) {
+        self.handler.send_datagram(datagram).await;
+    }
 
-        match buy_status {
-            status @ BuyLuggageStatus::Success => {
-                let message = status.to_string();
-                let mut success_response = RPCResponse::finished(_req.id).await;
-                success_response.encode_body(Service6ResponseBody { message });
-                Ok(success_response)
-            }
-            BuyLuggageStatus::ReservationNotExisted => Err(APIError::RecordNotFound),
-        }
+    async fn close(&mut self) {
+        self.handler.send_monitor_closed(self.request).await;
+    }
+}
+
+fn route_change(change: FlightChange) -> RouteChange {
+    match change {
+        FlightChange::Added(Flight { id, departure_time, airfare, seat_available, .. }) => RouteChange::FlightAdded {
+            flight_id: id,
+            departure_time,
+            airfare,
+            seat_avail: seat_available,
+        },
+        FlightChange::SeatsBelowThreshold { flight_id, seat_available } => RouteChange::SeatsBelowThreshold { flight_id, seat_avail: seat_available },
+        FlightChange::AirfareChanged { flight_id, airfare, .. } => RouteChange::AirfareChanged { flight_id, airfare },
     }
 }
@@ -0,0 +1,156 @@
+use super::router::{Endpoint, HandleFuture, Router, Service};
+use super::{APIError, Result, ServiceHandler};
+use database::types::{BuyLuggageStatus, CancellationStatus, ReservationStatus};
+use rpc_contracts::body::contracts::*;
+use rpc_contracts::RPCRequest;
+
+/// Router of every service offered by the server.
+pub fn flight_services() -> Router {
+    Router::new()
+        .register(1, QueryFlightIds)
+        .register(2, QueryFlightInfo)
+        .register(3, ReserveSeats)
+        .register_endpoint(4, MonitorFlight)
+        .register(5, CancelReservation)
+        .register(6, BuyLuggage)
+        .register_endpoint(7, MonitorRoute)
+        .register_endpoint(MONITOR_ACK, AcknowledgeUpdate)
+}
+
+/// Service 1: ids of the flights from a source to a destination.
+pub struct QueryFlightIds;
+
+impl Service for QueryFlightIds {
+    type Request<'de> = Service1RequestBody<'de>;
+    type Response = Service1ResponseBody;
+
+    async fn call<'a>(&'a self, handler: &'a ServiceHandler<'static>, request: Service1RequestBody<'a>) -> Result<Service1ResponseBody> {
+        let Service1RequestBody { source, destination } = request;
+
+        let db_service = handler.db_service.lock().await;
+        let flight_ids: Vec<u32> = db_service.get_flight_ids(source.into_owned(), destination.into_owned()).await?;
+
+        Ok(Service1ResponseBody { flight_ids })
+    }
+}
+
+/// Service 2: departure time, airfare and seat availability of a flight.
+pub struct QueryFlightInfo;
+
+impl Service for QueryFlightInfo {
+    type Request<'de> = Service2RequestBody;
+    type Response = Service2ResponseBody;
+
+    async fn call<'a>(&'a self, handler: &'a ServiceHandler<'static>, request: Service2RequestBody) -> Result<Service2ResponseBody> {
+        let Service2RequestBody { flight_id } = request;
+
+        let db_service = handler.db_service.lock().await;
+
+        let result_flight_info: Option<(i32, f32, u32)> = db_service.get_flight_info(flight_id).await?;
+        match result_flight_info {
+            Some((departure_time, airfare, seat_avail)) => Ok(Service2ResponseBody {
+                departure_time,
+                airfare,
+                seat_avail,
+            }),
+            None => Err(APIError::RecordNotFound),
+        }
+    }
+}
+
+/// Service 3: reserve seats on a flight, monitors of the flight are notified.
+pub struct ReserveSeats;
+
+impl Service for ReserveSeats {
+    type Request<'de> = Service3RequestBody;
+    type Response = Service3ResponseBody;
+
+    async fn call<'a>(&'a self, handler: &'a ServiceHandler<'static>, request: Service3RequestBody) -> Result<Service3ResponseBody> {
+        let Service3RequestBody { flight_id, num_seat } = request;
+
+        let db_service = handler.db_service.lock().await;
+        let reservation_status = db_service.make_reservation(flight_id, handler.client_id.to_string(), num_seat).await?;
+
+        match reservation_status {
+            status @ (ReservationStatus::Created | ReservationStatus::Updated) => {
+                // NOTE: send update for service 4
+                handler.send_update(flight_id, status).await;
+                Ok(Service3ResponseBody { message: status.to_string() })
+            }
+            ReservationStatus::InvalidFlightID => Err(APIError::RecordNotFound),
+            ReservationStatus::ZeroSeatReserved => Err(APIError::ParametersOutOfBounds),
+            ReservationStatus::InsufficientCapacity => Err(APIError::ParametersOutOfBounds),
+        }
+    }
+}
+
+/// Service 5: cancel the reservation of the client on a flight, monitors of the flight are notified.
+pub struct CancelReservation;
+
+impl Service for CancelReservation {
+    type Request<'de> = Service5RequestBody;
+    type Response = Service5ResponseBody;
+
+    async fn call<'a>(&'a self, handler: &'a ServiceHandler<'static>, request: Service5RequestBody) -> Result<Service5ResponseBody> {
+        let Service5RequestBody { flight_id } = request;
+
+        let db_service = handler.db_service.lock().await;
+        let cancellation_status = db_service.cancel_reservation(flight_id, handler.client_id.to_string()).await?;
+
+        match cancellation_status {
+            status @ CancellationStatus::Success => {
+                // NOTE: send update for service 4
+                handler.send_update(flight_id, ReservationStatus::Updated).await;
+                Ok(Service5ResponseBody { message: status.to_string() })
+            }
+            CancellationStatus::ReservationNotExisted => Err(APIError::RecordNotFound),
+        }
+    }
+}
+
+/// Service 6: buy luggage for the reservation of the client on a flight.
+pub struct BuyLuggage;
+
+impl Service for BuyLuggage {
+    type Request<'de> = Service6RequestBody;
+    type Response = Service6ResponseBody;
+
+    async fn call<'a>(&'a self, handler: &'a ServiceHandler<'static>, request: Service6RequestBody) -> Result<Service6ResponseBody> {
+        let Service6RequestBody { flight_id, amount_in_kg } = request;
+
+        let db_service = handler.db_service.lock().await;
+        let buy_status = db_service.buy_luggage(flight_id, handler.client_id.to_string(), amount_in_kg).await?;
+
+        match buy_status {
+            status @ BuyLuggageStatus::Success => Ok(Service6ResponseBody { message: status.to_string() }),
+            BuyLuggageStatus::ReservationNotExisted => Err(APIError::RecordNotFound),
+        }
+    }
+}
+
+/// Service 4: seat availability updates of a flight.
+pub struct MonitorFlight;
+
+impl Endpoint for MonitorFlight {
+    fn handle<'a>(&'a self, handler: &'a ServiceHandler<'static>, request: RPCRequest) -> HandleFuture<'a> {
+        Box::pin(handler.service_4(request))
+    }
+}
+
+/// Acknowledgements of monitor updates, see [`MONITOR_ACK`].
+pub struct AcknowledgeUpdate;
+
+impl Endpoint for AcknowledgeUpdate {
+    fn handle<'a>(&'a self, handler: &'a ServiceHandler<'static>, request: RPCRequest) -> HandleFuture<'a> {
+        Box::pin(handler.monitor_ack(request))
+    }
+}
+
+/// Service 7: new flights, low seat counts and airfare changes on a route.
+pub struct MonitorRoute;
+
+impl Endpoint for MonitorRoute {
+    fn handle<'a>(&'a self, handler: &'a ServiceHandler<'static>, request: RPCRequest) -> HandleFuture<'a> {
+        Box::pin(handler.service_7(request))
+    }
+}
@@ -2,23 +2,26 @@ use core::fmt::{self, Display};
 use serde::{Serialize, Deserialize};
 use std::io;
 
-#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub enum APIError {
-    MalformedRequest,
+    /// The request body could not be decoded, holds the decoding error with its kind and path.
+    MalformedRequest(String),
     DatabaseError,
     RecordNotFound,
     ParametersOutOfBounds,
     ConnectionError,
+    ShuttingDown,
 }
 
 impl Display for APIError {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        match *self {
+        match self {
             APIError::DatabaseError => formatter.write_str("Internal database service error"),
-            APIError::MalformedRequest => formatter.write_str("Request deserialization error"),
+            APIError::MalformedRequest(error) => write!(formatter, "Request deserialization error: {}", error),
             APIError::RecordNotFound => formatter.write_str("Requested resource does not exists"),
             APIError::ParametersOutOfBounds => formatter.write_str("Request parameters out of bounds"),
             APIError::ConnectionError => formatter.write_str("Socket connection is down"),
+            APIError::ShuttingDown => formatter.write_str("Server is shutting down, monitoring ended"),
         }
     }
 }
@@ -27,8 +30,8 @@ impl std::error::Error for APIError {}
 pub type Result<T> = core::result::Result<T, APIError>;
 
 impl From<serde::Error> for APIError {
-    fn from(_: serde::Error) -> Self {
-        APIError::MalformedRequest
+    fn from(error: serde::Error) -> Self {
+        APIError::MalformedRequest(error.to_string())
     }
 }
 
@@ -1,6 +1,7 @@
 use database::{get_global_db_connection, Flight};
 use database::types::Result;
-use serde::json;
+use serde::{binary, json};
+use std::io;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -20,13 +21,24 @@ async fn main() -> Result<()> {
         })
     }).expect("Remember to seed the database");
 
+    let pretty = json::Serializer::new().pretty(2).sort_keys(true);
+    let mut table = Vec::new();
     for flight in flights {
         let flight = flight?;
         let ser_f = json::to_string(&flight);
-        println!("Serialized flight {:?}", ser_f);
+        println!("Serialized flight {}", pretty.to_string(&flight).unwrap());
         let de_f: Flight = json::from_str(&ser_f).unwrap();
         println!("Deserialized flight {:?}", de_f);
+        let bin_f = binary::to_bytes(&flight);
+        println!("Binary flight {:?}, {} bytes (JSON {} bytes)", bin_f, bin_f.len(), ser_f.len());
+        let de_bin_f: Flight = binary::from_bytes(&bin_f).unwrap();
+        println!("Deserialized binary flight {:?}", de_bin_f);
+        table.push(flight);
     }
 
+    println!("Flight table:");
+    pretty.to_writer(io::stdout().lock(), &table).unwrap();
+    println!();
+
     Ok(())
 }
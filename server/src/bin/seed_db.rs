@@ -1,11 +1,21 @@
-use database::types::Result;
-use database::{define_schemas, get_global_db_connection, seed_db};
+use database::{define_schemas, get_global_db_connection, seed_db, seed_db_with_flights, Flight};
+use serde::json;
+use std::error::Error;
+use std::fs::File;
+use std::io::BufReader;
 
+/// Seed the database with random flights, or with the JSON array of flights in the file given as first argument.
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> Result<(), Box<dyn Error>> {
     let connection = get_global_db_connection();
     let connection = connection.lock().await;
     define_schemas(&connection)?;
-    seed_db(&connection)?;
+    match std::env::args().nth(1) {
+        Some(path) => {
+            let flights: Vec<Flight> = json::from_reader(BufReader::new(File::open(path)?))?;
+            seed_db_with_flights(&connection, &flights)?;
+        }
+        None => seed_db(&connection)?,
+    }
     Ok(())
 }
@@ -0,0 +1,179 @@
+use clap::ValueEnum;
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Invocation semantics the server runs with, selected by the `--semantics` flag.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum InvocationSemantics {
+    /// Execute every received request, duplicates included.
+    AtLeastOnce,
+    /// Filter duplicated requests and replay the reply stored in the request history.
+    AtMostOnce,
+}
+
+/// A request is identified by the id of the client that sent it and the sequence number the client assigned to it,
+/// see [`rpc_contracts::RPCRequest::key`].
+pub type HistoryKey = (u64, u32);
+
+#[derive(Debug, PartialEq)]
+pub enum HistoryStatus {
+    /// First time this request is seen, it has been recorded as in progress.
+    New,
+    /// The request is still being handled, the duplicate should be dropped.
+    InProgress,
+    /// The request was handled already, resend the stored reply.
+    Replay(Vec<u8>),
+    /// Every slot of the history holds a request still in progress, the new request should be dropped. Evicting one of
+    /// them would let its duplicates run again.
+    Full,
+}
+
+/// Reply of a request that was handled.
+struct CompletedEntry {
+    reply: Vec<u8>,
+    completed_at: Instant,
+}
+
+/// Reply history for at-most-once semantics.
+///
+/// Replies are evicted once they were stored for longer than `ttl`, or oldest first when more than `capacity` requests
+/// are tracked. Requests still in progress are never evicted.
+pub struct RequestHistory {
+    /// `None` while the request is in progress.
+    entries: HashMap<HistoryKey, Option<CompletedEntry>>,
+    /// Keys of the completed requests, in completion order.
+    completed: VecDeque<HistoryKey>,
+    ttl: Duration,
+    capacity: usize,
+}
+
+impl RequestHistory {
+    pub fn new(ttl: Duration, capacity: usize) -> Self {
+        RequestHistory {
+            entries: HashMap::new(),
+            completed: VecDeque::new(),
+            ttl,
+            capacity,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Look up a request, recording it as in progress if it has not been seen before.
+    pub fn begin(&mut self, key: HistoryKey) -> HistoryStatus {
+        self.begin_at(key, Instant::now())
+    }
+
+    fn begin_at(&mut self, key: HistoryKey, now: Instant) -> HistoryStatus {
+        self.evict(now);
+        if let Some(entry) = self.entries.get(&key) {
+            return match entry {
+                Some(completed) => HistoryStatus::Replay(completed.reply.clone()),
+                None => HistoryStatus::InProgress,
+            };
+        }
+
+        while self.capacity > 0 && self.entries.len() >= self.capacity {
+            match self.completed.pop_front() {
+                Some(oldest) => self.entries.remove(&oldest),
+                None => return HistoryStatus::Full,
+            };
+        }
+        self.entries.insert(key, None);
+        HistoryStatus::New
+    }
+
+    /// Store the serialized reply of a request so that duplicates can be answered without re-executing it.
+    pub fn complete(&mut self, key: &HistoryKey, reply: Vec<u8>) {
+        self.complete_at(key, reply, Instant::now())
+    }
+
+    fn complete_at(&mut self, key: &HistoryKey, reply: Vec<u8>, now: Instant) {
+        let Some(entry) = self.entries.get_mut(key) else {
+            return;
+        };
+        if entry.is_some() {
+            // a replaced reply is kept for the whole time to live again
+            self.completed.retain(|k| k != key);
+        }
+        self.completed.push_back(*key);
+        *entry = Some(CompletedEntry { reply, completed_at: now });
+    }
+
+    fn evict(&mut self, now: Instant) {
+        while let Some(oldest) = self.completed.front() {
+            match self.entries.get(oldest) {
+                Some(Some(entry)) if now.duration_since(entry.completed_at) < self.ttl => break,
+                _ => {
+                    let oldest = self.completed.pop_front().unwrap();
+                    self.entries.remove(&oldest);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(id: u32) -> HistoryKey {
+        (42, id)
+    }
+
+    #[test]
+    fn duplicate_should_replay_stored_reply() {
+        let mut history = RequestHistory::new(Duration::from_secs(60), 16);
+        assert_eq!(history.begin(key(1)), HistoryStatus::New);
+        assert_eq!(history.begin(key(1)), HistoryStatus::InProgress);
+
+        history.complete(&key(1), b"reply".to_vec());
+        assert_eq!(history.begin(key(1)), HistoryStatus::Replay(b"reply".to_vec()));
+        assert_eq!(history.begin(key(2)), HistoryStatus::New);
+    }
+
+    #[test]
+    fn expired_entries_should_be_evicted() {
+        let mut history = RequestHistory::new(Duration::from_secs(10), 16);
+        let start = Instant::now();
+        history.begin_at(key(1), start);
+        history.complete_at(&key(1), b"reply".to_vec(), start);
+
+        let status = history.begin_at(key(1), start + Duration::from_secs(11));
+        assert_eq!(status, HistoryStatus::New, "Expired reply should not be replayed");
+        assert_eq!(history.len(), 1);
+    }
+
+    #[test]
+    fn time_to_live_should_start_on_completion() {
+        let mut history = RequestHistory::new(Duration::from_secs(10), 16);
+        let start = Instant::now();
+        history.begin_at(key(1), start);
+        assert_eq!(history.begin_at(key(1), start + Duration::from_secs(30)), HistoryStatus::InProgress);
+
+        history.complete_at(&key(1), b"reply".to_vec(), start + Duration::from_secs(30));
+        let status = history.begin_at(key(1), start + Duration::from_secs(35));
+        assert_eq!(status, HistoryStatus::Replay(b"reply".to_vec()), "Long running request should keep its reply");
+        assert_eq!(history.begin_at(key(1), start + Duration::from_secs(41)), HistoryStatus::New);
+    }
+
+    #[test]
+    fn oldest_completed_entries_should_be_evicted_when_full() {
+        let mut history = RequestHistory::new(Duration::from_secs(60), 2);
+        history.begin(key(1));
+        history.begin(key(2));
+        assert_eq!(history.begin(key(3)), HistoryStatus::Full, "Requests in progress should not be evicted");
+
+        history.complete(&key(2), b"reply".to_vec());
+        assert_eq!(history.begin(key(3)), HistoryStatus::New);
+        assert_eq!(history.len(), 2);
+        assert_eq!(history.begin(key(1)), HistoryStatus::InProgress);
+        assert_eq!(history.begin(key(2)), HistoryStatus::Full);
+    }
+}
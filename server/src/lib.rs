@@ -1,4 +1,6 @@
 pub mod api;
+pub mod history;
+pub mod monitor;
 
 pub use database;
 pub use database::ReservationStatus;
@@ -1,17 +1,18 @@
 use clap::Parser;
 use rand::prelude::*;
-use rpc_contracts::RPCRequest;
-use serde::json;
+use rpc_contracts::{RPCRequest, MAX_DATAGRAM_LEN, MONITOR_ACK};
+use server::api::services::flight_services;
 use server::api::ServiceHandler;
 use server::database::{get_connection_pool, DatabaseService};
-use server::ReservationStatus;
+use server::history::{HistoryStatus, InvocationSemantics, RequestHistory};
+use server::monitor::MonitorRegistry;
 use std::error::Error;
-use std::str;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::net::UdpSocket;
-use tokio::sync::mpsc;
 use tokio::sync::Mutex;
-use tokio::sync::Notify;
+use tokio::task::JoinSet;
+use tokio::time::timeout;
 
 #[derive(Parser, Debug)]
 #[command(version, about)]
@@ -27,11 +28,38 @@ struct Args {
 
     #[arg(long, default_value = "0.25")]
     loss_prob: f64,
+
+    #[arg(short, long, value_enum, default_value = "at-most-once")]
+    semantics: InvocationSemantics,
+
+    #[arg(long, default_value = "300")]
+    history_ttl: u64,
+
+    #[arg(long, default_value = "1024")]
+    history_size: usize,
+
+    #[arg(short, long, default_value = "false")]
+    verbose: bool,
+
+    /// Seconds to wait for running requests on shutdown before aborting them.
+    #[arg(long, default_value = "10")]
+    shutdown_timeout: u64,
 }
 
-static mut SENDER: Option<mpsc::Sender<(u32, ReservationStatus)>> = None;
-static mut RECEIVER: Option<mpsc::Receiver<(u32, ReservationStatus)>> = None;
-static mut NOTIFY: Option<Notify> = None;
+/// Resolves on Ctrl-C, or on SIGTERM on unix.
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        let mut terminate = signal(SignalKind::terminate()).expect("Failed to listen for SIGTERM");
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {}
+            _ = terminate.recv() => {}
+        }
+    }
+    #[cfg(not(unix))]
+    tokio::signal::ctrl_c().await.ok();
+}
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
@@ -40,25 +68,34 @@ async fn main() -> Result<(), Box<dyn Error>> {
     let port = options.port;
     let loss = options.loss;
     let loss_prob = options.loss_prob;
+    let history = match options.semantics {
+        InvocationSemantics::AtMostOnce => {
+            let ttl = Duration::from_secs(options.history_ttl);
+            Some(Arc::new(Mutex::new(RequestHistory::new(ttl, options.history_size))))
+        }
+        InvocationSemantics::AtLeastOnce => None,
+    };
     let socket = Arc::new(UdpSocket::bind((addr, port)).await?);
     println!("{:?}", socket.local_addr());
-    let mut buf = [0_u8; 2048]; // max 2048 bytes
+    let mut buf = [0_u8; MAX_DATAGRAM_LEN];
     let pool = get_connection_pool();
     let db_service = DatabaseService::new(&pool).unwrap();
     let db_service_arc = Arc::new(Mutex::new(db_service));
+    let monitors = Arc::new(Mutex::new(MonitorRegistry::new()));
+    let router = Arc::new(flight_services());
 
-    let (sender, receiver) = mpsc::channel::<(u32, ReservationStatus)>(32);
-    let notify = Notify::new();
-
-    unsafe {
-        SENDER = Some(sender);
-        RECEIVER = Some(receiver);
-        NOTIFY = Some(notify);
-    }
+    let mut handlers = JoinSet::new();
+    let shutdown = shutdown_signal();
+    tokio::pin!(shutdown);
 
     let mut rng = rand::thread_rng();
     loop {
-        let (byte_idx, client_addr) = socket.recv_from(&mut buf).await?;
+        let (byte_idx, client_addr) = tokio::select! {
+            received = socket.recv_from(&mut buf) => received?,
+            _ = &mut shutdown => break,
+        };
+        // drop the results of the handlers that already ended
+        while handlers.try_join_next().is_some() {}
         if loss {
             let is_response_lost = rng.gen_bool(loss_prob);
             if is_response_lost {
@@ -66,33 +103,55 @@ async fn main() -> Result<(), Box<dyn Error>> {
             }
         }
         let data = &buf[..byte_idx];
-        let data_str = str::from_utf8(data).unwrap();
-        let request: RPCRequest = match json::from_str(data_str) {
+        let request = match RPCRequest::from_bytes(data) {
             Ok(r) => r,
             Err(err) => {
-                println!("Invalid request {:?}\nError {:?}", data_str, err);
+                println!("Invalid request {:?}\nError: {}", String::from_utf8_lossy(data), err);
                 continue;
             }
         };
-        println!("Received message from {}, service_type: {:?}", client_addr, request.service_type);
+        println!("Received message from {}, service_type: {:?}, codec: {}", client_addr, request.service_type, request.codec);
+        let client_addr = client_addr.to_string();
+        // acknowledgements get no reply, there is nothing to replay for their duplicates
+        if let Some(history) = history.as_ref().filter(|_| request.service_type != MONITOR_ACK) {
+            let status = history.lock().await.begin(request.key());
+            match status {
+                HistoryStatus::New => {}
+                HistoryStatus::InProgress => {
+                    println!("Duplicated request {} from {} is still in progress, dropped", request.id, client_addr);
+                    continue;
+                }
+                HistoryStatus::Full => {
+                    println!("Request history is full of requests in progress, request {} from {} dropped", request.id, client_addr);
+                    continue;
+                }
+                HistoryStatus::Replay(reply) => {
+                    println!("Duplicated request {} from {}, replaying stored reply", request.id, client_addr);
+                    socket.send_to(&reply, &client_addr).await.ok();
+                    continue;
+                }
+            }
+        }
         let db_service_arc = db_service_arc.clone();
-        let client_addr = client_addr.clone().to_string();
-        let sender = unsafe { SENDER.as_ref().unwrap() };
-        let receiver = unsafe { RECEIVER.as_mut().unwrap() };
-        let notify = unsafe { NOTIFY.as_ref().unwrap() };
-        let mut handler = ServiceHandler::new(socket.clone(), client_addr, db_service_arc, sender, receiver, notify).await;
+        let history = history.clone();
+        let mut handler = ServiceHandler::new(socket.clone(), client_addr, request.client_id, db_service_arc, history, monitors.clone()).await;
+        handler.verbose = options.verbose;
 
-        let service_type = request.service_type;
-        tokio::spawn(async move {
-            match service_type {
-                1 => handler.service_1(request).await,
-                2 => handler.service_2(request).await,
-                3 => handler.service_3(request).await,
-                4 => handler.service_4(request).await,
-                5 => handler.service_5(request).await,
-                6 => handler.service_6(request).await,
-                _ => handler.invalid_service_type(request).await,
-            };
+        let router = router.clone();
+        handlers.spawn(async move {
+            router.dispatch(&handler, request).await;
         });
     }
+
+    // no more datagrams are read, monitors tell their clients that monitoring ended and return
+    let closed = monitors.lock().await.close();
+    println!("Shutting down, {} monitor(s) closed, waiting for {} running request(s)", closed, handlers.len());
+    let deadline = Duration::from_secs(options.shutdown_timeout);
+    if timeout(deadline, async { while handlers.join_next().await.is_some() {} }).await.is_err() {
+        println!("{} request(s) still running after {}s, aborted", handlers.len(), deadline.as_secs());
+        handlers.shutdown().await;
+    }
+    pool.close();
+    println!("Database connections closed");
+    Ok(())
 }
@@ -0,0 +1,449 @@
+use crate::history::HistoryKey;
+use database::ReservationStatus;
+use std::collections::HashMap;
+use std::future::{pending, Future};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::time::{interval, sleep_until, Interval};
+
+/// Seat availability of a flight changed, with the change that caused it.
+pub type MonitorUpdate = (u32, ReservationStatus);
+
+/// Delay before the first resend of an unacknowledged update, doubled after every resend.
+pub const RESEND_DELAY: Duration = Duration::from_millis(500);
+/// Longest delay between two resends of the same update.
+pub const MAX_RESEND_DELAY: Duration = Duration::from_secs(4);
+/// How often a route monitor reads its route, to catch changes made outside of this server such as new flights.
+pub const ROUTE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Event delivered to the monitor of a subscription.
+#[derive(Debug, PartialEq)]
+pub enum MonitorEvent {
+    /// The monitored flight changed.
+    Updated(MonitorUpdate),
+    /// The client received every update up to this sequence number.
+    Acknowledged(u32),
+    /// The server is shutting down, the monitor must tell its client that monitoring ended.
+    Closed,
+}
+
+/// Identifies a subscription for as long as it is registered, ids are never reused.
+pub type SubscriptionId = u64;
+
+/// What a subscription is notified about.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Topic {
+    /// Seat availability of one flight, for Service 4.
+    Flight(u32),
+    /// Flights from a source to a destination, for Service 7. Notified of the updates of every flight: the monitor reads
+    /// its route again to find out what changed, which also catches flights added by another process.
+    Route(String, String),
+}
+
+impl Topic {
+    fn matches(&self, flight_id: u32) -> bool {
+        match self {
+            Topic::Flight(id) => *id == flight_id,
+            Topic::Route(..) => true,
+        }
+    }
+}
+
+struct Subscription {
+    /// Key of the monitor request that opened the subscription, acknowledgements refer to it.
+    request: HistoryKey,
+    topic: Topic,
+    expires_at: Instant,
+    sender: UnboundedSender<MonitorEvent>,
+}
+
+/// Monitor subscriptions of Service 4 and Service 7, shared by every request handler.
+///
+/// Every update of a flight is delivered to all the subscriptions whose topic matches it. A subscription is dropped when its
+/// monitor interval ends or when its receiver is dropped, which closes its channel.
+pub struct MonitorRegistry {
+    subscriptions: HashMap<SubscriptionId, Subscription>,
+    next_id: SubscriptionId,
+    closed: bool,
+}
+
+impl Default for MonitorRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MonitorRegistry {
+    pub fn new() -> Self {
+        MonitorRegistry {
+            subscriptions: HashMap::new(),
+            next_id: 0,
+            closed: false,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.subscriptions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.subscriptions.is_empty()
+    }
+
+    /// Subscribe a client to the updates of a topic for `interval`, `request` is the key of the monitor request and
+    /// identifies the client by its id.
+    ///
+    /// A client subscribing again to the same topic replaces its previous subscription, whose channel is closed, even from
+    /// another address. After
+    /// [`close`](MonitorRegistry::close) the subscription is not registered and its receiver only gets [`MonitorEvent::Closed`].
+    pub fn subscribe(
+        &mut self, request: HistoryKey, topic: Topic, interval: Duration,
+    ) -> (SubscriptionId, UnboundedReceiver<MonitorEvent>) {
+        self.subscribe_at(request, topic, interval, Instant::now())
+    }
+
+    fn subscribe_at(
+        &mut self, request: HistoryKey, topic: Topic, interval: Duration, now: Instant,
+    ) -> (SubscriptionId, UnboundedReceiver<MonitorEvent>) {
+        self.evict(now);
+        let (client_id, _) = request;
+        self.subscriptions.retain(|_, s| s.request.0 != client_id || s.topic != topic);
+
+        let id = self.next_id;
+        self.next_id += 1;
+        let (sender, receiver) = mpsc::unbounded_channel();
+        if self.closed {
+            sender.send(MonitorEvent::Closed).ok();
+            return (id, receiver);
+        }
+        let expires_at = now + interval;
+        self.subscriptions.insert(id, Subscription { request, topic, expires_at, sender });
+        (id, receiver)
+    }
+
+    /// Remove a subscription before it expires, e.g. when its monitor stops early.
+    pub fn unsubscribe(&mut self, id: SubscriptionId) {
+        self.subscriptions.remove(&id);
+    }
+
+    /// Send an update to every live subscription matching the flight, returns the number of subscriptions notified.
+    pub fn publish(&mut self, flight_id: u32, status: ReservationStatus) -> usize {
+        self.publish_at(flight_id, status, Instant::now())
+    }
+
+    fn publish_at(&mut self, flight_id: u32, status: ReservationStatus, now: Instant) -> usize {
+        self.evict(now);
+        let mut notified = 0;
+        self.subscriptions.retain(|_, s| {
+            if !s.topic.matches(flight_id) {
+                return true;
+            }
+            let delivered = s.sender.send(MonitorEvent::Updated((flight_id, status))).is_ok();
+            notified += delivered as usize;
+            delivered
+        });
+        notified
+    }
+
+    /// Forward an acknowledgement to the subscription opened by the `request`, returns whether it is still registered.
+    pub fn acknowledge(&mut self, request: HistoryKey, seq: u32) -> bool {
+        match self.subscriptions.values().find(|s| s.request == request) {
+            Some(subscription) => subscription.sender.send(MonitorEvent::Acknowledged(seq)).is_ok(),
+            None => false,
+        }
+    }
+
+    /// End every subscription on shutdown, returns the number of monitors told to stop.
+    pub fn close(&mut self) -> usize {
+        self.closed = true;
+        self.subscriptions.drain().filter(|(_, s)| s.sender.send(MonitorEvent::Closed).is_ok()).count()
+    }
+
+    fn evict(&mut self, now: Instant) {
+        self.subscriptions.retain(|_, s| s.expires_at > now && !s.sender.is_closed());
+    }
+}
+
+/// Latest update sent to a client and not acknowledged yet, resent with exponential backoff.
+struct PendingUpdate {
+    seq: u32,
+    datagram: Vec<u8>,
+    resend_at: Instant,
+    delay: Duration,
+}
+
+impl PendingUpdate {
+    fn new(seq: u32, datagram: Vec<u8>, sent_at: Instant) -> Self {
+        PendingUpdate {
+            seq,
+            datagram,
+            resend_at: sent_at + RESEND_DELAY,
+            delay: RESEND_DELAY,
+        }
+    }
+
+    /// Schedule the next resend after the update was resent at `now`.
+    fn backoff(&mut self, now: Instant) {
+        self.delay = (self.delay * 2).min(MAX_RESEND_DELAY);
+        self.resend_at = now + self.delay;
+    }
+}
+
+/// What a monitor reads and sends to its client, driven by [`run_monitor`]. Impls may use `async fn`.
+pub trait MonitorSource: Send {
+    /// Read what changed, on every update of the topic and every poll.
+    fn read(&mut self) -> impl Future<Output = ()> + Send;
+    /// Datagram of update `seq` with what was read since the previous update, `None` when nothing changed.
+    fn update(&mut self, seq: u32) -> impl Future<Output = Option<Vec<u8>>> + Send;
+    fn send(&mut self, datagram: &[u8]) -> impl Future<Output = ()> + Send;
+    /// Tell the client that monitoring ended because the server is shutting down.
+    fn close(&mut self) -> impl Future<Output = ()> + Send;
+}
+
+/// How a monitor delivers its updates.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Delivery {
+    /// An update is sent as soon as something changes and replaces the pending one. For Service 4: the client only needs
+    /// the latest seat availability, and it drops updates with a sequence number lower than one it already received.
+    Latest,
+    /// The source is also read every period, and an update is only sent once the previous one is acknowledged, so that
+    /// changes are neither lost nor reordered. For Service 7, whose updates each carry different changes.
+    InOrder(Duration),
+}
+
+/// Send the updates of a subscription until it expires at `expires_at`, is replaced or the server shuts down.
+///
+/// Every update is numbered from 1 and resent with exponential backoff until the client acknowledges it.
+pub async fn run_monitor(source: &mut impl MonitorSource, events: &mut UnboundedReceiver<MonitorEvent>, expires_at: Instant, delivery: Delivery) {
+    let mut poll = match delivery {
+        Delivery::Latest => None,
+        Delivery::InOrder(period) => Some(interval(period)),
+    };
+    let mut seq = 0;
+    let mut pending: Option<PendingUpdate> = None;
+    loop {
+        let resend_at = pending.as_ref().map_or(expires_at, |p| p.resend_at.min(expires_at));
+        tokio::select! {
+            received = events.recv() => match received {
+                Some(MonitorEvent::Updated(_)) => source.read().await,
+                Some(MonitorEvent::Acknowledged(acked)) => {
+                    if pending.as_ref().is_some_and(|p| p.seq <= acked) {
+                        pending = None;
+                    }
+                }
+                Some(MonitorEvent::Closed) => {
+                    source.close().await;
+                    break;
+                }
+                // replaced by a newer subscription of the same client
+                None => break,
+            },
+            _ = tick(&mut poll) => source.read().await,
+            _ = sleep_until(resend_at.into()) => {
+                match pending.as_mut().filter(|_| resend_at < expires_at) {
+                    Some(pending) => {
+                        source.send(&pending.datagram).await;
+                        pending.backoff(Instant::now());
+                    }
+                    None => break,
+                }
+                continue;
+            }
+        }
+
+        if pending.is_some() && delivery != Delivery::Latest {
+            continue;
+        }
+        if let Some(datagram) = source.update(seq + 1).await {
+            seq += 1;
+            source.send(&datagram).await;
+            pending = Some(PendingUpdate::new(seq, datagram, Instant::now()));
+        }
+    }
+}
+
+async fn tick(poll: &mut Option<Interval>) {
+    match poll {
+        Some(poll) => {
+            poll.tick().await;
+        }
+        None => pending().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INTERVAL: Duration = Duration::from_secs(60);
+
+    fn updated(flight_id: u32, status: ReservationStatus) -> Option<MonitorEvent> {
+        Some(MonitorEvent::Updated((flight_id, status)))
+    }
+
+    #[test]
+    fn every_subscriber_should_receive_updates() {
+        let mut registry = MonitorRegistry::new();
+        let (_, mut first) = registry.subscribe((42, 1), Topic::Flight(7), INTERVAL);
+        let (_, mut second) = registry.subscribe((43, 1), Topic::Flight(7), INTERVAL);
+        let (_, mut other) = registry.subscribe((44, 1), Topic::Flight(8), INTERVAL);
+
+        assert_eq!(registry.publish(7, ReservationStatus::Created), 2);
+        assert_eq!(registry.publish(7, ReservationStatus::Updated), 2);
+
+        for receiver in [&mut first, &mut second] {
+            assert_eq!(receiver.try_recv().ok(), updated(7, ReservationStatus::Created));
+            assert_eq!(receiver.try_recv().ok(), updated(7, ReservationStatus::Updated));
+        }
+        assert!(other.try_recv().is_err());
+    }
+
+    #[test]
+    fn expired_subscriptions_should_be_dropped() {
+        let mut registry = MonitorRegistry::new();
+        let start = Instant::now();
+        let (_, mut receiver) = registry.subscribe_at((42, 1), Topic::Flight(7), Duration::from_secs(10), start);
+        let (_, _other) = registry.subscribe_at((43, 1), Topic::Flight(7), Duration::from_secs(30), start);
+
+        let notified = registry.publish_at(7, ReservationStatus::Created, start + Duration::from_secs(11));
+        assert_eq!(notified, 1, "Expired subscription should not be notified");
+        assert_eq!(registry.len(), 1);
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn dropped_receivers_should_unsubscribe() {
+        let mut registry = MonitorRegistry::new();
+        let (_, receiver) = registry.subscribe((42, 1), Topic::Flight(7), INTERVAL);
+        let (id, _receiver) = registry.subscribe((43, 1), Topic::Flight(7), INTERVAL);
+        drop(receiver);
+
+        assert_eq!(registry.publish(7, ReservationStatus::Created), 1);
+        registry.unsubscribe(id);
+        assert!(registry.is_empty());
+    }
+
+    #[test]
+    fn subscribing_again_should_replace_the_subscription() {
+        let mut registry = MonitorRegistry::new();
+        let (_, mut previous) = registry.subscribe((42, 1), Topic::Flight(7), INTERVAL);
+        let (_, mut current) = registry.subscribe((42, 2), Topic::Flight(7), INTERVAL);
+
+        assert_eq!(registry.publish(7, ReservationStatus::Created), 1);
+        assert!(previous.try_recv().is_err(), "Replaced subscription should be closed");
+        assert_eq!(current.try_recv().ok(), updated(7, ReservationStatus::Created));
+    }
+
+    #[test]
+    fn acknowledgements_should_reach_their_subscription() {
+        let mut registry = MonitorRegistry::new();
+        let (_, mut first) = registry.subscribe((42, 1), Topic::Flight(7), INTERVAL);
+        let (_, mut second) = registry.subscribe((43, 1), Topic::Flight(7), INTERVAL);
+
+        assert!(registry.acknowledge((43, 1), 3));
+        assert!(!registry.acknowledge((44, 1), 3), "Unknown subscription should not be acknowledged");
+        assert!(first.try_recv().is_err());
+        assert_eq!(second.try_recv().ok(), Some(MonitorEvent::Acknowledged(3)));
+    }
+
+    #[test]
+    fn closing_should_end_every_monitor() {
+        let mut registry = MonitorRegistry::new();
+        let (_, mut flight) = registry.subscribe((42, 1), Topic::Flight(7), INTERVAL);
+        let route = Topic::Route("LAS".to_string(), "HAN".to_string());
+        let (_, mut route) = registry.subscribe((43, 1), route, INTERVAL);
+
+        assert_eq!(registry.close(), 2);
+        assert!(registry.is_empty());
+        for receiver in [&mut flight, &mut route] {
+            assert_eq!(receiver.try_recv().ok(), Some(MonitorEvent::Closed));
+        }
+
+        let (_, mut late) = registry.subscribe((44, 1), Topic::Flight(7), INTERVAL);
+        assert_eq!(late.try_recv().ok(), Some(MonitorEvent::Closed), "Subscribing after closing should end at once");
+        assert_eq!(registry.publish(7, ReservationStatus::Created), 0);
+    }
+
+    /// Source whose updates are their sequence number, counting the changes read.
+    #[derive(Default)]
+    struct Recorder {
+        changes: usize,
+        sent: Vec<u8>,
+        closed: bool,
+    }
+
+    impl MonitorSource for Recorder {
+        async fn read(&mut self) {
+            self.changes += 1;
+        }
+
+        async fn update(&mut self, seq: u32) -> Option<Vec<u8>> {
+            let changes = std::mem::take(&mut self.changes);
+            (changes > 0).then(|| vec![seq as u8])
+        }
+
+        async fn send(&mut self, datagram: &[u8]) {
+            self.sent.extend_from_slice(datagram);
+        }
+
+        async fn close(&mut self) {
+            self.closed = true;
+        }
+    }
+
+    async fn monitor(events: Vec<MonitorEvent>, delivery: Delivery) -> Recorder {
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        for event in events {
+            sender.send(event).unwrap();
+        }
+        drop(sender);
+        let mut recorder = Recorder::default();
+        run_monitor(&mut recorder, &mut receiver, Instant::now() + INTERVAL, delivery).await;
+        recorder
+    }
+
+    #[tokio::test]
+    async fn latest_delivery_should_send_every_change_at_once() {
+        let events = vec![MonitorEvent::Updated((7, ReservationStatus::Created)), MonitorEvent::Updated((7, ReservationStatus::Updated))];
+        let recorder = monitor(events, Delivery::Latest).await;
+        assert_eq!(recorder.sent, [1, 2]);
+    }
+
+    #[tokio::test]
+    async fn in_order_delivery_should_wait_for_acknowledgements() {
+        let updated = || MonitorEvent::Updated((7, ReservationStatus::Created));
+        let events = vec![updated(), updated(), updated(), MonitorEvent::Acknowledged(1), MonitorEvent::Closed];
+        let recorder = monitor(events, Delivery::InOrder(INTERVAL)).await;
+        // the changes read while update 1 was pending are sent together once it is acknowledged
+        assert_eq!(recorder.sent, [1, 2]);
+        assert!(recorder.closed);
+    }
+
+    #[test]
+    fn resend_delay_should_double_up_to_its_maximum() {
+        let start = Instant::now();
+        let mut pending = PendingUpdate::new(1, Vec::new(), start);
+        assert_eq!(pending.resend_at, start + RESEND_DELAY);
+
+        let delays: Vec<Duration> = (0..5)
+            .map(|_| {
+                pending.backoff(start);
+                pending.resend_at - start
+            })
+            .collect();
+        let expected = [1000, 2000, 4000, 4000, 4000].map(Duration::from_millis);
+        assert_eq!(delays, expected);
+    }
+
+    #[test]
+    fn route_subscriptions_should_receive_every_flight() {
+        let mut registry = MonitorRegistry::new();
+        let route = Topic::Route("LAS".to_string(), "HAN".to_string());
+        let (_, mut receiver) = registry.subscribe((42, 1), route.clone(), INTERVAL);
+        let (_, _flight) = registry.subscribe((42, 2), Topic::Flight(7), INTERVAL);
+
+        assert_eq!(registry.publish(8, ReservationStatus::Created), 1);
+        assert_eq!(receiver.try_recv().ok(), updated(8, ReservationStatus::Created));
+        assert_eq!(registry.len(), 2, "Different topics of the same client should both be kept");
+    }
+}